@@ -1,8 +1,10 @@
 //! Error types for the frost-bluepallas library
 
-use alloc::{boxed::Box, string::String};
+use alloc::{boxed::Box, collections::BTreeSet, string::String};
 use core::{error, fmt, result::Result};
 
+use crate::{ChallengeMessage, Error, Identifier};
+
 // TODO: Replace with BluePallasError within
 pub type BluePallasResult<T> = Result<T, Box<dyn error::Error>>;
 
@@ -18,11 +20,45 @@ pub enum BluePallasError {
     /// No messages have been provided for signing
     NoMessageProvided,
 
+    /// No signing commitments have been provided for a signing package
+    NoCommitmentsProvided,
+
     /// Saving Signature failed
     SaveSignatureError(String),
 
     /// Invalid Memo provided
     InvalidMemo(String),
+
+    /// Secret share is inconsistent with its verifiable secret sharing commitment
+    InvalidSecretShare(String),
+
+    /// Key file was written by a newer, unknown format version
+    UnsupportedVersion(u64),
+
+    /// Quorum parameters are inconsistent with the available participants
+    InvalidQuorum(String),
+
+    /// Enumerating quorums would produce more subsets than the allowed maximum
+    TooManyQuorums { count: u64, max: u64 },
+
+    /// The signing package holds a different nonce commitment for the signer than its nonces
+    CommitmentMismatch(NonceCommitmentKind),
+}
+
+/// Which of a participant's two nonce commitments an error refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonceCommitmentKind {
+    Hiding,
+    Binding,
+}
+
+impl fmt::Display for NonceCommitmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonceCommitmentKind::Hiding => write!(f, "hiding"),
+            NonceCommitmentKind::Binding => write!(f, "binding"),
+        }
+    }
 }
 
 impl fmt::Display for BluePallasError {
@@ -35,10 +71,30 @@ impl fmt::Display for BluePallasError {
             BluePallasError::NoMessageProvided => {
                 write!(f, "No messages have been provided for signing")
             }
+            BluePallasError::NoCommitmentsProvided => {
+                write!(f, "No signing commitments have been provided")
+            }
             BluePallasError::SaveSignatureError(msg) => {
                 write!(f, "Failed to save signature: {}", msg)
             }
             BluePallasError::InvalidMemo(msg) => write!(f, "Invalid memo: {}", msg),
+            BluePallasError::InvalidSecretShare(msg) => {
+                write!(f, "Invalid secret share: {}", msg)
+            }
+            BluePallasError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported key file version: {}", version)
+            }
+            BluePallasError::InvalidQuorum(msg) => write!(f, "Invalid quorum: {}", msg),
+            BluePallasError::TooManyQuorums { count, max } => write!(
+                f,
+                "Too many quorums: {} subsets exceeds the maximum of {}",
+                count, max
+            ),
+            BluePallasError::CommitmentMismatch(kind) => write!(
+                f,
+                "Signing package {} nonce commitment does not match the signer's nonces",
+                kind
+            ),
         }
     }
 }
@@ -62,3 +118,141 @@ impl BluePallasError {
         BluePallasError::InvalidMemo(message.into())
     }
 }
+
+/// Difference between the participants that committed in round 1 and those that sent
+/// signature shares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentifierMismatch<M: ChallengeMessage> {
+    /// Participants that committed but did not send a signature share.
+    pub missing: BTreeSet<Identifier<M>>,
+    /// Participants that sent a signature share without having committed.
+    pub extra: BTreeSet<Identifier<M>>,
+}
+
+impl<M: ChallengeMessage> fmt::Display for IdentifierMismatch<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Signature shares do not match commitments (missing: {:?}, extra: {:?})",
+            self.missing, self.extra
+        )
+    }
+}
+
+impl<M: ChallengeMessage> error::Error for IdentifierMismatch<M> {}
+
+/// Error returned by [`crate::aggregate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregateError<M: ChallengeMessage> {
+    /// The set of signature shares does not match the set of signing commitments
+    IdentifierMismatch(IdentifierMismatch<M>),
+
+    /// Fewer signature shares than the group's signing threshold were provided
+    NotEnoughShares { received: usize, required: u16 },
+
+    /// frost-core rejected the aggregation
+    Frost(Error<M>),
+}
+
+impl<M: ChallengeMessage> fmt::Display for AggregateError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregateError::IdentifierMismatch(mismatch) => write!(f, "{}", mismatch),
+            AggregateError::NotEnoughShares { received, required } => write!(
+                f,
+                "Not enough signature shares: received {}, at least {} required",
+                received, required
+            ),
+            AggregateError::Frost(e) => write!(f, "Aggregation failed: {}", e),
+        }
+    }
+}
+
+impl<M: ChallengeMessage> error::Error for AggregateError<M> {}
+
+impl<M: ChallengeMessage> From<IdentifierMismatch<M>> for AggregateError<M> {
+    fn from(mismatch: IdentifierMismatch<M>) -> Self {
+        AggregateError::IdentifierMismatch(mismatch)
+    }
+}
+
+impl<M: ChallengeMessage> From<Error<M>> for AggregateError<M> {
+    fn from(e: Error<M>) -> Self {
+        AggregateError::Frost(e)
+    }
+}
+
+/// Any error produced by this crate.
+///
+/// Functions return the most specific error type they can, which leaves callers combining
+/// FROST operations with e.g. signing package assembly juggling several types. All of them
+/// convert into this enum with `?` while keeping the original error, so a signing failure
+/// can still be told apart from a malformed input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrostBluePallasError<M: ChallengeMessage> {
+    /// A FROST protocol operation failed, e.g. an invalid signature share
+    Frost(Error<M>),
+
+    /// Aggregating signature shares failed
+    Aggregate(AggregateError<M>),
+
+    /// Serialization or input validation in this crate failed
+    BluePallas(BluePallasError),
+}
+
+impl<M: ChallengeMessage> FrostBluePallasError<M> {
+    /// The underlying frost-core error, if the failure came from the FROST protocol itself.
+    pub fn frost_error(&self) -> Option<&Error<M>> {
+        match self {
+            FrostBluePallasError::Frost(e)
+            | FrostBluePallasError::Aggregate(AggregateError::Frost(e)) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// Only this layer's message: the wrapped error is reported through `source()`, so
+// printing it here too would repeat it when walking the error chain.
+impl<M: ChallengeMessage> fmt::Display for FrostBluePallasError<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrostBluePallasError::Frost(_) => write!(f, "FROST operation failed"),
+            FrostBluePallasError::Aggregate(_) => write!(f, "Signature aggregation failed"),
+            FrostBluePallasError::BluePallas(_) => write!(f, "frost-bluepallas operation failed"),
+        }
+    }
+}
+
+impl<M: ChallengeMessage> error::Error for FrostBluePallasError<M> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FrostBluePallasError::Frost(e) => Some(e),
+            FrostBluePallasError::Aggregate(e) => Some(e),
+            FrostBluePallasError::BluePallas(e) => Some(e),
+        }
+    }
+}
+
+impl<M: ChallengeMessage> From<Error<M>> for FrostBluePallasError<M> {
+    fn from(e: Error<M>) -> Self {
+        FrostBluePallasError::Frost(e)
+    }
+}
+
+impl<M: ChallengeMessage> From<AggregateError<M>> for FrostBluePallasError<M> {
+    fn from(e: AggregateError<M>) -> Self {
+        FrostBluePallasError::Aggregate(e)
+    }
+}
+
+impl<M: ChallengeMessage> From<IdentifierMismatch<M>> for FrostBluePallasError<M> {
+    fn from(mismatch: IdentifierMismatch<M>) -> Self {
+        FrostBluePallasError::Aggregate(mismatch.into())
+    }
+}
+
+impl<M: ChallengeMessage> From<BluePallasError> for FrostBluePallasError<M> {
+    fn from(e: BluePallasError) -> Self {
+        FrostBluePallasError::BluePallas(e)
+    }
+}
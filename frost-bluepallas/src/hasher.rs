@@ -5,7 +5,7 @@ use ark_ff::PrimeField;
 use frost_core::Field;
 use mina_hasher::{create_legacy, Hashable, Hasher, ROInput};
 
-use crate::PallasScalarField;
+use crate::{PallasScalarField, CONTEXT_STRING};
 
 /// This is a Hashable interface for an array of bytes
 /// This allows us to provide a easy-to-read interface for hashing FROST elements in H1, H3, H4, H5
@@ -41,8 +41,55 @@ impl Hashable for PallasHashElement<'_> {
 
 type Fq = <PallasScalarField as Field>::Scalar;
 
+/// Tag of the `H1` (binding factor) hash.
+pub const TAG_RHO: &[u8] = b"rho";
+/// Tag of the `H3` (nonce generation) hash.
+pub const TAG_NONCE: &[u8] = b"nonce";
+/// Tag of the `H4` (message) hash.
+pub const TAG_MSG: &[u8] = b"msg";
+/// Tag of the `H5` (commitment list) hash.
+pub const TAG_COM: &[u8] = b"com";
+/// Tag of the `HDKG` (DKG proof of knowledge) hash.
+pub const TAG_DKG: &[u8] = b"dkg";
+/// Tag of the `HID` (identifier derivation) hash.
+pub const TAG_ID: &[u8] = b"id";
+
+/// Tags the ciphersuite hashes under [`CONTEXT_STRING`]. Domain separation between the FROST
+/// hash functions relies on these being used by nothing else.
+pub const RESERVED_TAGS: [&[u8]; 6] = [TAG_RHO, TAG_NONCE, TAG_MSG, TAG_COM, TAG_DKG, TAG_ID];
+
+/// Whether `input` starts with a `(CONTEXT_STRING, tag)` pair reserved for the ciphersuite.
+pub fn is_reserved(input: &[&[u8]]) -> bool {
+    matches!(input, [context, tag, ..]
+        if *context == CONTEXT_STRING.as_bytes() && RESERVED_TAGS.contains(tag))
+}
+
+/// Hash `m` under one of the [`RESERVED_TAGS`]; only the ciphersuite hash functions call this.
+pub(crate) fn ciphersuite_hash_to_scalar(tag: &[u8], m: &[u8]) -> Fq {
+    hash_segments(&[CONTEXT_STRING.as_bytes(), tag, m])
+}
+
+/// Array variant of [`ciphersuite_hash_to_scalar`].
+pub(crate) fn ciphersuite_hash_to_array(
+    tag: &[u8],
+    m: &[u8],
+) -> <PallasScalarField as frost_core::Field>::Serialization {
+    PallasScalarField::serialize(&ciphersuite_hash_to_scalar(tag, m))
+}
+
 // Maps poseidon hash of input to a scalar field element
+//
+// Inputs starting with a reserved `(CONTEXT_STRING, tag)` pair would collide with the
+// ciphersuite's own hashes, which debug builds reject.
 pub fn hash_to_scalar(input: &[&[u8]]) -> Fq {
+    debug_assert!(
+        !is_reserved(input),
+        "hash_to_scalar called with a (context, tag) pair reserved for the ciphersuite"
+    );
+    hash_segments(input)
+}
+
+fn hash_segments(input: &[&[u8]]) -> Fq {
     // Hash via PallasHashElement, which length-prefixes the segment count and each segment
     // to prevent padding and segmentation-based collision attacks.
     let wrap = PallasHashElement { value: input };
@@ -51,6 +98,10 @@ pub fn hash_to_scalar(input: &[&[u8]]) -> Fq {
     // Convert from base field to scalar field
     // This is performed in the mina-signer crate
     // https://github.com/o1-labs/proof-systems/blob/6d2ac796205456d314d7ea2a3db6e0e816d60a99/signer/src/schnorr.rs#L145-L158
+    //
+    // The Pallas base modulus is smaller than the scalar modulus, so the canonical integer of the
+    // Poseidon output is already a canonical scalar: it is taken as-is, with neither wide
+    // reduction nor rejection sampling, and the map is injective.
     Fq::from(hasher.hash(&wrap).into_bigint())
 }
 
@@ -102,4 +153,82 @@ mod tests {
         let b = hash_to_scalar(&[b"a", b"bc"]);
         assert_ne!(a, b, "different segmentation can yield the same hash");
     }
+
+    #[test]
+    fn test_reserved_tags_produce_distinct_scalars() {
+        let m = b"same message";
+        let scalars: alloc::vec::Vec<Fq> = RESERVED_TAGS
+            .iter()
+            .map(|tag| ciphersuite_hash_to_scalar(tag, m))
+            .collect();
+
+        for (i, a) in scalars.iter().enumerate() {
+            for b in &scalars[i + 1..] {
+                assert_ne!(a, b, "two reserved tags hash to the same scalar");
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_reserved() {
+        assert!(is_reserved(&[CONTEXT_STRING.as_bytes(), TAG_RHO, b"m"]));
+        assert!(is_reserved(&[CONTEXT_STRING.as_bytes(), TAG_ID]));
+        assert!(!is_reserved(&[CONTEXT_STRING.as_bytes(), b"other", b"m"]));
+        assert!(!is_reserved(&[b"another-context", TAG_RHO, b"m"]));
+        assert!(!is_reserved(&[CONTEXT_STRING.as_bytes()]));
+    }
+
+    #[test]
+    fn test_base_to_scalar_conversion_is_exact() {
+        assert!(mina_hasher::Fp::MODULUS < Fq::MODULUS);
+
+        // H3 of participant 1 in tests/helpers/vectors.json, over the nonce randomness followed
+        // by the serialized signing share. The Poseidon output in the base field already has
+        // the integer of the expected scalar.
+        let nonce_input = [
+            hex::decode("9eaa7cfff171f6b5f333dd6e616290a73e7bde9469263255bf4579f1d43ed9a9")
+                .unwrap(),
+            hex::decode("251a886d8abdc03d79378edf3b8d5ad154f35bb17a9b2adee7e64844a00bb008")
+                .unwrap(),
+        ]
+        .concat();
+        let expected =
+            hex::decode("b1dd00145a3a6450b1f70170aa0d8af7c94f5058e04de7235a3d27295e8f2d05")
+                .unwrap();
+        let input: &[&[u8]] = &[CONTEXT_STRING.as_bytes(), TAG_NONCE, &nonce_input];
+        let base = create_legacy::<PallasHashElement>(()).hash(&PallasHashElement { value: input });
+        assert_eq!(base, mina_hasher::Fp::from_le_bytes_mod_order(&expected));
+        assert_eq!(
+            ciphersuite_hash_to_scalar(TAG_NONCE, &nonce_input),
+            Fq::from_le_bytes_mod_order(&expected)
+        );
+    }
+
+    fn scalar_from_hex(s: &str) -> Fq {
+        let bytes: [u8; 32] = hex::decode(s).unwrap().try_into().unwrap();
+        PallasScalarField::deserialize(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_h1_matches_frost_vectors() {
+        // Participant 1 of round one in tests/helpers/vectors.json
+        let binding_factor_input = hex::decode(
+            "542308204baa2641e383d8792c6dd4c1a1e65bccc5d7a0f40729ae43b0235e18802a03c2f7f1206ae3b90ca653e7f1921b9bc4d9ebcb8ea8ca6992ae5a8982130983e30f4937088404891064f2701226ea70db477749690b1c0201239088c658100100000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_eq!(
+            ciphersuite_hash_to_scalar(TAG_RHO, &binding_factor_input),
+            scalar_from_hex("73ceb5bfa03f694966fbd5e93112c8d1bbd05dfdf1df775cd2a26033dc2e3d1c")
+        );
+
+        // TODO: pin HDKG and HID as well once reference values for them are published; the
+        // FROST vectors only cover the signing hashes.
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "reserved for the ciphersuite")]
+    fn test_hash_to_scalar_rejects_reserved_tag() {
+        hash_to_scalar(&[CONTEXT_STRING.as_bytes(), TAG_NONCE, b"m"]);
+    }
 }
@@ -1,14 +1,73 @@
 //! This module contains utilities for FROST key management using the BluePallas curve
 
-use alloc::collections::BTreeMap;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::{format, vec, vec::Vec};
 
+use ark_ec::CurveGroup;
 use frost_core::{self as frost};
+use mina_signer::PubKey;
 use rand_core::{CryptoRng, RngCore};
 
-use crate::{BluePallas, ChallengeMessage, Error, Identifier, SigningKey};
+use crate::errors::BluePallasError;
+use crate::{
+    BluePallas, ChallengeMessage, Ciphersuite, Error, Field, FieldError, Group, Identifier,
+    PallasGroup, PallasScalarField, SigningKey, VerifyingKey, FIELD_SIZE,
+};
 
 pub type IdentifierList<'a, M> = frost::keys::IdentifierList<'a, BluePallas<M>>;
 
+/// Checked constructors and conversions for [`Identifier`].
+///
+/// The zero identifier would be handed `f(0)`, the group secret itself, as its share.
+/// Every constructor here goes through [`IdentifierExt::try_from_scalar`], which is the
+/// single place that rejects it.
+pub trait IdentifierExt<M: ChallengeMessage>: Sized {
+    /// Use a scalar as an identifier, failing with [`FieldError::InvalidZeroScalar`] if it
+    /// is zero.
+    fn try_from_scalar(scalar: Scalar) -> Result<Self, Error<M>>;
+
+    /// Derive an identifier from arbitrary bytes such as a participant's name, like
+    /// [`Identifier::derive`].
+    fn try_derive(name: &[u8]) -> Result<Self, Error<M>>;
+
+    /// Parse the 32-byte little-endian encoding produced by [`Identifier::serialize`].
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error<M>>;
+
+    /// The small integer behind a default identifier, i.e. `Some(n)` for the identifier
+    /// built from `n: u16`, and `None` for identifiers derived from names.
+    fn as_u16(&self) -> Option<u16>;
+}
+
+impl<M: ChallengeMessage> IdentifierExt<M> for Identifier<M> {
+    fn try_from_scalar(scalar: Scalar) -> Result<Self, Error<M>> {
+        if scalar == PallasScalarField::zero() {
+            return Err(FieldError::InvalidZeroScalar.into());
+        }
+        Identifier::new(scalar)
+    }
+
+    fn try_derive(name: &[u8]) -> Result<Self, Error<M>> {
+        let scalar = BluePallas::<M>::HID(name).ok_or(Error::IdentifierDerivationNotSupported)?;
+        Self::try_from_scalar(scalar)
+    }
+
+    fn try_from_bytes(bytes: &[u8]) -> Result<Self, Error<M>> {
+        let bytes: [u8; FIELD_SIZE] = bytes.try_into().map_err(|_| Error::DeserializationError)?;
+        Self::try_from_scalar(PallasScalarField::deserialize(&bytes)?)
+    }
+
+    fn as_u16(&self) -> Option<u16> {
+        // Scalars serialize little-endian, so small integers leave every byte past the
+        // second one zero. Zero is never a valid identifier.
+        let bytes = self.serialize();
+        if bytes[2..].iter().any(|&b| b != 0) {
+            return None;
+        }
+        Some(u16::from_le_bytes([bytes[0], bytes[1]])).filter(|&n| n != 0)
+    }
+}
+
 /// Secret and public key material generated by a dealer performing
 /// [`generate_with_dealer`].
 ///
@@ -21,6 +80,36 @@ pub type SecretShare<M> = frost::keys::SecretShare<BluePallas<M>>;
 /// A secret scalar value representing a signer's share of the group secret.
 pub type SigningShare<M> = frost::keys::SigningShare<BluePallas<M>>;
 
+/// Raw 32-byte encoding of a [`SigningShare`], for shares imported from a hardware module
+/// or another tool that does not speak frost-core's serde format.
+pub trait SigningShareExt<M: ChallengeMessage>: Sized {
+    /// Parse a share from its 32-byte little-endian scalar encoding.
+    ///
+    /// Fails with [`FieldError::MalformedScalar`] if the bytes are not a canonical scalar
+    /// and with [`FieldError::InvalidZeroScalar`] for zero, which is never a valid share.
+    fn from_bytes(bytes: &[u8; FIELD_SIZE]) -> Result<Self, Error<M>>;
+
+    /// Encode the share as its 32-byte little-endian scalar, as read by
+    /// [`SigningShareExt::from_bytes`].
+    fn to_bytes(&self) -> [u8; FIELD_SIZE];
+}
+
+impl<M: ChallengeMessage> SigningShareExt<M> for SigningShare<M> {
+    fn from_bytes(bytes: &[u8; FIELD_SIZE]) -> Result<Self, Error<M>> {
+        let scalar = PallasScalarField::deserialize(bytes)?;
+        if scalar == PallasScalarField::zero() {
+            return Err(FieldError::InvalidZeroScalar.into());
+        }
+        Ok(SigningShare::new(scalar))
+    }
+
+    fn to_bytes(&self) -> [u8; FIELD_SIZE] {
+        let mut bytes = [0u8; FIELD_SIZE];
+        bytes.copy_from_slice(&self.serialize());
+        bytes
+    }
+}
+
 /// A public group element that represents a single signer's public verification share.
 pub type VerifyingShare<M> = frost::keys::VerifyingShare<BluePallas<M>>;
 
@@ -32,12 +121,207 @@ pub type VerifyingShare<M> = frost::keys::VerifyingShare<BluePallas<M>>;
 /// [`KeyPackage`]s, which they store to later use during signing.
 pub type KeyPackage<M> = frost::keys::KeyPackage<BluePallas<M>>;
 
+/// The public portion of a [`KeyPackage`].
+///
+/// Participants can publish this so others are able to verify their contributions; it
+/// never carries the [`SigningShare`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct PublicShare<M: ChallengeMessage> {
+    pub identifier: Identifier<M>,
+    pub verifying_share: VerifyingShare<M>,
+    pub verifying_key: VerifyingKey<M>,
+    pub min_signers: u16,
+}
+
+#[cfg(feature = "serde")]
+impl<M: ChallengeMessage> PublicShare<M> {
+    /// Encode the public share as JSON.
+    pub fn to_json(&self) -> Result<String, BluePallasError> {
+        serde_json::to_string(self).map_err(|e| BluePallasError::serialization_error(e.to_string()))
+    }
+}
+
+/// Version of the key-package JSON written by [`export_secret_share`] and
+/// [`export_key_package`], stored in a top-level `"version"` field.
+///
+/// Version 1 is the plain frost-core encoding without that field. It has the same layout
+/// otherwise and is still accepted on import.
+///
+/// This is unrelated to the `header.version` that frost-core writes inside every key
+/// package and share. That one versions frost-core's encoding of the FROST structures
+/// (ciphersuite ID included) and is checked by frost-core itself when deserializing,
+/// whatever the file version. `KEY_FILE_VERSION` only versions the file around them, and
+/// changes when this crate changes what it writes to key files.
+#[cfg(feature = "serde")]
+pub const KEY_FILE_VERSION: u64 = 2;
+
+#[cfg(feature = "serde")]
+fn to_versioned_json<T: serde::Serialize>(value: &T) -> Result<String, BluePallasError> {
+    let mut json = serde_json::to_value(value)
+        .map_err(|e| BluePallasError::serialization_error(e.to_string()))?;
+    json.as_object_mut()
+        .ok_or_else(|| BluePallasError::serialization_error("expected a JSON object"))?
+        .insert("version".into(), KEY_FILE_VERSION.into());
+    serde_json::to_string(&json).map_err(|e| BluePallasError::serialization_error(e.to_string()))
+}
+
+#[cfg(feature = "serde")]
+fn from_versioned_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, BluePallasError> {
+    let mut value: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| BluePallasError::deserialization_error(e.to_string()))?;
+    let version = match value
+        .as_object_mut()
+        .and_then(|object| object.remove("version"))
+    {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| BluePallasError::deserialization_error("version must be a number"))?,
+    };
+    match version {
+        // Version 1 only lacks the version field, which has been removed above
+        1 | KEY_FILE_VERSION => serde_json::from_value(value)
+            .map_err(|e| BluePallasError::deserialization_error(e.to_string())),
+        other => Err(BluePallasError::UnsupportedVersion(other)),
+    }
+}
+
+/// Encode a dealer-distributed [`SecretShare`] as versioned JSON, see [`KEY_FILE_VERSION`].
+#[cfg(feature = "serde")]
+pub fn export_secret_share<M: ChallengeMessage>(
+    secret_share: &SecretShare<M>,
+) -> Result<String, BluePallasError> {
+    to_versioned_json(secret_share)
+}
+
+/// Import a dealer-distributed [`SecretShare`] from JSON and derive its [`KeyPackage`].
+///
+/// The share is checked against its [`VerifiableSecretSharingCommitment`] as part of the
+/// import, so a corrupted or tampered file is rejected here instead of at signing time.
+/// Unversioned files are read as version 1; versions newer than [`KEY_FILE_VERSION`] are
+/// rejected with [`BluePallasError::UnsupportedVersion`].
+#[cfg(feature = "serde")]
+pub fn import_secret_share<M: ChallengeMessage>(
+    json: &str,
+) -> Result<KeyPackage<M>, BluePallasError> {
+    let secret_share: SecretShare<M> = from_versioned_json(json)?;
+
+    KeyPackage::try_from(secret_share)
+        .map_err(|e| BluePallasError::InvalidSecretShare(e.to_string()))
+}
+
+/// Encode a [`KeyPackage`], e.g. the output of DKG, as versioned JSON, see
+/// [`KEY_FILE_VERSION`].
+#[cfg(feature = "serde")]
+pub fn export_key_package<M: ChallengeMessage>(
+    key_package: &KeyPackage<M>,
+) -> Result<String, BluePallasError> {
+    to_versioned_json(key_package)
+}
+
+/// Import a [`KeyPackage`] written by [`export_key_package`] or an unversioned one.
+#[cfg(feature = "serde")]
+pub fn import_key_package<M: ChallengeMessage>(
+    json: &str,
+) -> Result<KeyPackage<M>, BluePallasError> {
+    from_versioned_json(json)
+}
+
+/// Accessors for the shareable parts of a [`KeyPackage`].
+pub trait KeyPackageExt<M: ChallengeMessage> {
+    /// This participant's public verification share.
+    fn public_share(&self) -> VerifyingShare<M>;
+
+    /// Everything in the key package except the secret signing share.
+    fn to_public(&self) -> PublicShare<M>;
+}
+
+impl<M: ChallengeMessage> KeyPackageExt<M> for KeyPackage<M> {
+    fn public_share(&self) -> VerifyingShare<M> {
+        *self.verifying_share()
+    }
+
+    fn to_public(&self) -> PublicShare<M> {
+        PublicShare {
+            identifier: *self.identifier(),
+            verifying_share: *self.verifying_share(),
+            verifying_key: *self.verifying_key(),
+            min_signers: *self.min_signers(),
+        }
+    }
+}
+
 /// Public data that contains all the signers' public keys as well as the
 /// group public key.
 ///
 /// Used for verification purposes before publishing a signature.
 pub type PublicKeyPackage<M> = frost::keys::PublicKeyPackage<BluePallas<M>>;
 
+/// Integrity checks for a [`PublicKeyPackage`].
+pub trait PublicKeyPackageExt<M: ChallengeMessage> {
+    /// Identifiers whose verifying share differs between `self` and `other`.
+    ///
+    /// An identifier present in only one of the packages counts as differing. If the
+    /// group verifying keys differ, every identifier of both packages is returned, since
+    /// none of the shares can then be trusted. An empty result means the packages match.
+    fn diff(&self, other: &PublicKeyPackage<M>) -> Vec<Identifier<M>>;
+
+    /// A [`PublicKeyPackageSummary`] of the package, with every key as a Mina address.
+    fn summary(&self) -> PublicKeyPackageSummary<M>;
+}
+
+impl<M: ChallengeMessage> PublicKeyPackageExt<M> for PublicKeyPackage<M> {
+    fn diff(&self, other: &PublicKeyPackage<M>) -> Vec<Identifier<M>> {
+        let ours = self.verifying_shares();
+        let theirs = other.verifying_shares();
+        let group_key_differs = self.verifying_key() != other.verifying_key();
+
+        let identifiers: BTreeSet<Identifier<M>> =
+            ours.keys().chain(theirs.keys()).copied().collect();
+        identifiers
+            .into_iter()
+            .filter(|id| group_key_differs || ours.get(id) != theirs.get(id))
+            .collect()
+    }
+
+    fn summary(&self) -> PublicKeyPackageSummary<M> {
+        let address = |element: <PallasGroup as Group>::Element| {
+            PubKey::from_point_unsafe(element.into_affine()).into_address()
+        };
+        PublicKeyPackageSummary {
+            address: address(self.verifying_key().to_element()),
+            min_signers: self.min_signers(),
+            participants: self.verifying_shares().len(),
+            verifying_shares: self
+                .verifying_shares()
+                .iter()
+                .map(|(id, share)| (*id, address(share.to_element())))
+                .collect(),
+        }
+    }
+}
+
+/// A readable view of a [`PublicKeyPackage`] for dashboards and logs.
+///
+/// This is not a key file format: the addresses cannot be turned back into a package, so
+/// store and exchange the [`PublicKeyPackage`] itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct PublicKeyPackageSummary<M: ChallengeMessage> {
+    /// Mina address of the group verifying key.
+    pub address: String,
+    /// Number of signers needed for a signature, if the package records it.
+    pub min_signers: Option<u16>,
+    /// Number of participants holding a share.
+    pub participants: usize,
+    /// Each participant's verifying share as a Mina address.
+    pub verifying_shares: BTreeMap<Identifier<M>, String>,
+}
+
 /// Contains the commitments to the coefficients for our secret polynomial _f_,
 /// used to generate participants' key shares.
 ///
@@ -67,6 +351,283 @@ where
     frost::keys::generate_with_dealer(max_signers, min_signers, identifiers, &mut rng)
 }
 
+type KeyPackagesOutput<M> = (BTreeMap<Identifier<M>, KeyPackage<M>>, PublicKeyPackage<M>);
+
+/// [`generate_with_dealer`], with every share verified and converted into its [`KeyPackage`].
+///
+/// Fails if any share does not verify against the dealer's commitment. As with
+/// [`generate_with_dealer`], the group key keeps the Y parity it was generated with; signing
+/// gives the group commitment an even Y, which is all Mina requires.
+pub fn generate_key_packages_with_dealer<M, RNG: RngCore + CryptoRng>(
+    max_signers: u16,
+    min_signers: u16,
+    identifiers: IdentifierList<'_, M>,
+    rng: RNG,
+) -> Result<KeyPackagesOutput<M>, Error<M>>
+where
+    M: ChallengeMessage,
+{
+    let (shares, public_key_package) =
+        generate_with_dealer(max_signers, min_signers, identifiers, rng)?;
+    let key_packages = shares
+        .into_iter()
+        .map(|(id, share)| Ok((id, KeyPackage::try_from(share)?)))
+        .collect::<Result<_, Error<M>>>()?;
+    Ok((key_packages, public_key_package))
+}
+
+/// The Lagrange coefficient of `id` for interpolating at zero over the signers in `subset`.
+///
+/// This is the `lambda_i` that [`round2::sign`](crate::round2::sign) applies to a
+/// signer's share, exposed so auditors and alternative coordinators can compute it for any
+/// quorum. `subset` must contain `id` and must not repeat an identifier.
+pub fn lagrange_coefficient<M: ChallengeMessage>(
+    id: &Identifier<M>,
+    subset: &[Identifier<M>],
+) -> Result<<PallasScalarField as Field>::Scalar, Error<M>> {
+    let identifiers: BTreeSet<Identifier<M>> = subset.iter().copied().collect();
+    if identifiers.len() != subset.len() {
+        return Err(Error::DuplicatedIdentifier);
+    }
+    if !identifiers.contains(id) {
+        return Err(Error::UnknownIdentifier);
+    }
+    frost::compute_lagrange_coefficient(&identifiers, None, *id)
+}
+
+/// Largest number of subsets [`minimal_quorums`] will enumerate before giving up.
+pub const MAX_QUORUMS: u64 = 10_000;
+
+/// Every `min_signers`-sized subset of `available`, in the order the identifiers are given.
+///
+/// Useful for scheduling and redundancy planning: each returned subset is a quorum that can
+/// sign on its own. Since the count grows combinatorially, enumeration is refused with
+/// [`BluePallasError::TooManyQuorums`] when it would exceed [`MAX_QUORUMS`] subsets.
+pub fn minimal_quorums<M: ChallengeMessage>(
+    available: &[Identifier<M>],
+    min_signers: u16,
+) -> Result<Vec<Vec<Identifier<M>>>, BluePallasError> {
+    let n = available.len();
+    let k = min_signers as usize;
+    if k == 0 || k > n {
+        return Err(BluePallasError::InvalidQuorum(format!(
+            "min_signers {} must be between 1 and the {} available participants",
+            min_signers, n
+        )));
+    }
+    let unique: BTreeSet<Identifier<M>> = available.iter().copied().collect();
+    if unique.len() != n {
+        return Err(BluePallasError::InvalidQuorum(
+            "available participants contain a duplicated identifier".to_string(),
+        ));
+    }
+
+    // C(n, k) computed incrementally; every intermediate value is itself a binomial coefficient.
+    let mut count: u128 = 1;
+    for i in 0..k.min(n - k) as u128 {
+        count = match count.checked_mul(n as u128 - i) {
+            Some(product) => product / (i + 1),
+            None => u128::MAX,
+        };
+        if count > MAX_QUORUMS as u128 {
+            break;
+        }
+    }
+    if count > MAX_QUORUMS as u128 {
+        return Err(BluePallasError::TooManyQuorums {
+            count: u64::try_from(count).unwrap_or(u64::MAX),
+            max: MAX_QUORUMS,
+        });
+    }
+
+    let mut quorums = Vec::with_capacity(count as usize);
+    let mut indices: Vec<usize> = (0..k).collect();
+    loop {
+        quorums.push(indices.iter().map(|&i| available[i]).collect());
+        // Advance the rightmost index that still has room, then reset the ones after it.
+        let Some(pos) = (0..k).rev().find(|&i| indices[i] != i + n - k) else {
+            return Ok(quorums);
+        };
+        indices[pos] += 1;
+        for i in pos + 1..k {
+            indices[i] = indices[i - 1] + 1;
+        }
+    }
+}
+
+/// Recompute the group verifying key from the participants' verifying shares.
+///
+/// The verifying shares are Lagrange-interpolated at zero in the group, which lets an
+/// auditor check a [`PublicKeyPackage`] produced by DKG without access to any secret
+/// share: the result must equal [`PublicKeyPackage::verifying_key`]. All provided shares
+/// are used, so a single inconsistent share changes the result.
+pub fn reconstruct_verifying_key<M: ChallengeMessage>(
+    shares: &BTreeMap<Identifier<M>, VerifyingShare<M>>,
+    threshold: u16,
+) -> Result<VerifyingKey<M>, Error<M>> {
+    if threshold < 2 {
+        return Err(Error::InvalidMinSigners);
+    }
+    if shares.len() < threshold as usize {
+        return Err(Error::IncorrectNumberOfShares);
+    }
+
+    let identifiers: BTreeSet<Identifier<M>> = shares.keys().copied().collect();
+    let mut group_element = <PallasGroup as Group>::identity();
+    for (identifier, share) in shares {
+        let lambda = frost::compute_lagrange_coefficient(&identifiers, None, *identifier)?;
+        group_element += share.to_element() * lambda;
+    }
+
+    Ok(VerifyingKey::new(group_element))
+}
+
+/// Recover the group signing key from `threshold` or more secret shares.
+///
+/// # Security
+///
+/// **This destroys the threshold security property of the group.** The returned
+/// [`SigningKey`] can sign alone, so whoever runs this function holds the whole key and
+/// any compromise of that machine compromises every account controlled by the group.
+/// It is only meant for disaster recovery, e.g. migrating the funds to a new key or
+/// scheme, and the recovered key should be retired as soon as that is done.
+///
+/// The signing shares are Lagrange-interpolated at zero. All provided shares are used,
+/// so an inconsistent share yields a wrong key; check the result against
+/// [`PublicKeyPackage::verifying_key`] before relying on it.
+pub fn recover_signing_key<M: ChallengeMessage>(
+    shares: &BTreeMap<Identifier<M>, SigningShare<M>>,
+    threshold: u16,
+) -> Result<SigningKey<M>, Error<M>> {
+    if threshold < 2 {
+        return Err(Error::InvalidMinSigners);
+    }
+    if shares.len() < threshold as usize {
+        return Err(Error::IncorrectNumberOfShares);
+    }
+
+    let identifiers: BTreeSet<Identifier<M>> = shares.keys().copied().collect();
+    let mut scalar = <PallasScalarField as Field>::zero();
+    for (identifier, share) in shares {
+        let lambda = frost::compute_lagrange_coefficient(&identifiers, None, *identifier)?;
+        scalar = scalar + share.to_scalar() * lambda;
+    }
+
+    SigningKey::from_scalar(scalar)
+}
+
+/// Move a group's shares to new identifiers without changing the group key.
+///
+/// `mapping` sends each old identifier in `old` to its new identifier. The sharing
+/// polynomial is interpolated from `min_signers` of the key packages and evaluated at the
+/// new identifiers, so the threshold, the group secret and the
+/// [`VerifiableSecretSharingCommitment`] all stay the same; only the evaluation points
+/// change. Every other package is checked against the interpolated polynomial.
+///
+/// # Security
+///
+/// Like [`recover_signing_key`], this needs `min_signers` secret shares in one place, so
+/// whoever runs it momentarily holds the whole key. The returned [`SecretShare`]s must be
+/// delivered over confidential channels and the old key packages destroyed.
+pub fn relabel_shares<M: ChallengeMessage>(
+    old: &BTreeMap<Identifier<M>, KeyPackage<M>>,
+    mapping: &BTreeMap<Identifier<M>, Identifier<M>>,
+) -> Result<BTreeMap<Identifier<M>, SecretShare<M>>, Error<M>> {
+    let first = old.values().next().ok_or(Error::IncorrectNumberOfShares)?;
+    let min_signers = *first.min_signers();
+    let verifying_key = *first.verifying_key();
+    if old.values().any(|package| {
+        *package.min_signers() != min_signers || *package.verifying_key() != verifying_key
+    }) {
+        return Err(Error::IncorrectCommitment);
+    }
+    if old.len() < min_signers as usize {
+        return Err(Error::IncorrectNumberOfShares);
+    }
+    if old.len() != mapping.len() || old.keys().any(|id| !mapping.contains_key(id)) {
+        return Err(Error::UnknownIdentifier);
+    }
+    let new_identifiers: BTreeSet<Identifier<M>> = mapping.values().copied().collect();
+    if new_identifiers.len() != mapping.len() {
+        return Err(Error::DuplicatedIdentifier);
+    }
+
+    // Interpolate f from the first `min_signers` packages and check the rest against it
+    let points: Vec<(Scalar, Scalar)> = old
+        .iter()
+        .take(min_signers as usize)
+        .map(|(id, package)| (id.to_scalar(), package.signing_share().to_scalar()))
+        .collect();
+    let coefficients = interpolate_coefficients(&points)?;
+    for (id, package) in old.iter().skip(min_signers as usize) {
+        if evaluate_polynomial(&coefficients, id.to_scalar()) != package.signing_share().to_scalar()
+        {
+            return Err(Error::InvalidSecretShare { culprit: Some(*id) });
+        }
+    }
+
+    let commitment = VerifiableSecretSharingCommitment::new(
+        coefficients
+            .iter()
+            .map(|a| frost::keys::CoefficientCommitment::new(PallasGroup::generator() * *a))
+            .collect(),
+    );
+    if VerifyingKey::new(PallasGroup::generator() * coefficients[0]) != verifying_key {
+        return Err(Error::IncorrectCommitment);
+    }
+
+    Ok(mapping
+        .values()
+        .map(|new_id| {
+            let value = evaluate_polynomial(&coefficients, new_id.to_scalar());
+            let share = SecretShare::new(*new_id, SigningShare::new(value), commitment.clone());
+            (*new_id, share)
+        })
+        .collect())
+}
+
+type Scalar = <PallasScalarField as Field>::Scalar;
+
+/// Coefficients, constant term first, of the polynomial through `points`.
+fn interpolate_coefficients<M: ChallengeMessage>(
+    points: &[(Scalar, Scalar)],
+) -> Result<Vec<Scalar>, Error<M>> {
+    let zero = <PallasScalarField as Field>::zero();
+    let mut coefficients = vec![zero; points.len()];
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        // Expand y_i * prod_{j != i} (x - x_j) / (x_i - x_j)
+        let mut basis = vec![<PallasScalarField as Field>::one()];
+        let mut denominator = <PallasScalarField as Field>::one();
+        for (j, (x_j, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let mut next = vec![zero; basis.len() + 1];
+            for (k, b) in basis.iter().enumerate() {
+                next[k + 1] = next[k + 1] + *b;
+                next[k] = next[k] - *b * *x_j;
+            }
+            basis = next;
+            denominator = denominator * (*x_i - *x_j);
+        }
+        let scale = *y_i
+            * <PallasScalarField as Field>::invert(&denominator)
+                .map_err(|_| Error::DuplicatedIdentifier)?;
+        for (c, b) in coefficients.iter_mut().zip(basis) {
+            *c = *c + b * scale;
+        }
+    }
+    Ok(coefficients)
+}
+
+/// Evaluate the polynomial with `coefficients`, constant term first, at `x`.
+fn evaluate_polynomial(coefficients: &[Scalar], x: Scalar) -> Scalar {
+    coefficients
+        .iter()
+        .rev()
+        .fold(<PallasScalarField as Field>::zero(), |acc, c| acc * x + *c)
+}
+
 /// Splits an existing key into FROST shares.
 ///
 /// This is identical to [`generate_with_dealer`] but receives an existing key
@@ -87,6 +648,14 @@ where
 }
 
 /// Distributed Key Generation (DKG) protocol types and functions.
+///
+/// Each participant calls [`part1`](dkg::part1), [`part2`](dkg::part2) and
+/// [`part3`](dkg::part3) in turn, exchanging the returned packages in between. With the
+/// `serde` feature the packages implement `Serialize` and `Deserialize`, so they can be
+/// carried over any transport; the `mina-frost-client` DKG command is one such orchestration.
+///
+/// The resulting group key has an odd Y coordinate about half the time. Such keys are fine:
+/// they sign and verify like even ones, as explained on [`part3`](dkg::part3).
 pub mod dkg {
     use super::*;
     type DkgPart2Output<M> = (
@@ -177,6 +746,10 @@ pub mod dkg {
     /// participant, and the [`PublicKeyPackage`]s that has public information
     /// about all participants; both of which are required to compute FROST
     /// signatures.
+    ///
+    /// As with [`generate_with_dealer`](super::generate_with_dealer), the group key is
+    /// not normalized to an even Y coordinate. Mina only requires the nonce commitment
+    /// to have even Y, which signing enforces, and verifies against the full public key.
     pub fn part3<M>(
         round2_secret_package: &round2::SecretPackage<M>,
         round1_packages: &BTreeMap<Identifier<M>, round1::Package<M>>,
@@ -7,16 +7,19 @@
 
 extern crate alloc;
 
-use alloc::{borrow::Cow, collections::BTreeMap};
+use alloc::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+};
 use core::marker::PhantomData;
 
-use ark_ec::{models::CurveConfig, CurveGroup, PrimeGroup};
+use ark_ec::{models::CurveConfig, AffineRepr, CurveGroup, PrimeGroup};
 
 use ark_ff::{fields::Field as ArkField, UniformRand};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 pub use frost_core::{self as frost, Ciphersuite, Field, FieldError, Group, GroupError};
 use frost_core::{compute_group_commitment, BindingFactorList};
-use mina_curves::pasta::{PallasParameters, ProjectivePallas};
+use mina_curves::pasta::{Pallas, PallasParameters, ProjectivePallas};
 
 use num_traits::identities::Zero;
 use rand_core::{CryptoRng, RngCore};
@@ -24,7 +27,11 @@ use rand_core::{CryptoRng, RngCore};
 pub type Error<M> = frost_core::Error<BluePallas<M>>;
 
 use crate::{
-    hasher::{hash_to_array, hash_to_scalar},
+    errors::{AggregateError, IdentifierMismatch},
+    hasher::{
+        ciphersuite_hash_to_array, ciphersuite_hash_to_scalar, TAG_COM, TAG_DKG, TAG_ID, TAG_MSG,
+        TAG_NONCE, TAG_RHO,
+    },
     negate::NegateY,
 };
 
@@ -69,6 +76,7 @@ impl Field for PallasScalarField {
         Self::Scalar::rand(rng)
     }
 
+    /// Encode the scalar as 32 little-endian bytes, arkworks' compressed form.
     fn serialize(scalar: &Self::Scalar) -> Self::Serialization {
         // Serialize the scalar in compressed form
         let mut buf = [0u8; FIELD_SIZE];
@@ -79,6 +87,8 @@ impl Field for PallasScalarField {
         buf
     }
 
+    /// Identical to [`Self::serialize`], which is already little-endian. frost-core orders
+    /// identifiers by this encoding, so it must not be byte-reversed.
     fn little_endian_serialize(scalar: &Self::Scalar) -> Self::Serialization {
         Self::serialize(scalar)
     }
@@ -124,15 +134,20 @@ impl Group for PallasGroup {
         Ok(buf)
     }
     fn deserialize(buf: &Self::Serialization) -> Result<Self::Element, GroupError> {
-        let point = <Self::Element as CanonicalDeserialize>::deserialize_compressed(&buf[..])
-            .map_err(|_| GroupError::MalformedElement);
+        // Validate curve membership explicitly rather than relying on the checks performed by
+        // the ark version in use, since verifying keys may come from untrusted sources
+        let point = <Pallas as CanonicalDeserialize>::deserialize_compressed_unchecked(&buf[..])
+            .map_err(|_| GroupError::MalformedElement)?;
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(GroupError::MalformedElement);
+        }
 
         // Ensure that the deserialized point is not the identity element
-        match point {
-            Ok(p) if p.is_zero() => Err(GroupError::InvalidIdentityElement),
-            Ok(p) => Ok(p),
-            Err(_) => Err(GroupError::MalformedElement),
+        let point = point.into_group();
+        if point.is_zero() {
+            return Err(GroupError::InvalidIdentityElement);
         }
+        Ok(point)
     }
 }
 
@@ -168,27 +183,27 @@ where
 
     type SignatureSerialization = [u8; HASH_SIZE];
     fn H1(m: &[u8]) -> <<Self::Group as Group>::Field as Field>::Scalar {
-        hash_to_scalar(&[CONTEXT_STRING.as_bytes(), b"rho", m])
+        ciphersuite_hash_to_scalar(TAG_RHO, m)
     }
     fn H2(_m: &[u8]) -> <<Self::Group as Group>::Field as Field>::Scalar {
         unimplemented!("H2 is not implemented on purpose, please see the `challenge` function");
     }
     fn H3(m: &[u8]) -> <<Self::Group as Group>::Field as Field>::Scalar {
-        hash_to_scalar(&[CONTEXT_STRING.as_bytes(), b"nonce", m])
+        ciphersuite_hash_to_scalar(TAG_NONCE, m)
     }
     fn H4(m: &[u8]) -> Self::HashOutput {
-        hash_to_array(&[CONTEXT_STRING.as_bytes(), b"msg", m])
+        ciphersuite_hash_to_array(TAG_MSG, m)
     }
     fn H5(m: &[u8]) -> Self::HashOutput {
-        hash_to_array(&[CONTEXT_STRING.as_bytes(), b"com", m])
+        ciphersuite_hash_to_array(TAG_COM, m)
     }
 
     fn HDKG(m: &[u8]) -> Option<<<Self::Group as Group>::Field as Field>::Scalar> {
-        Some(hash_to_scalar(&[CONTEXT_STRING.as_bytes(), b"dkg", m]))
+        Some(ciphersuite_hash_to_scalar(TAG_DKG, m))
     }
 
     fn HID(m: &[u8]) -> Option<<<Self::Group as Group>::Field as Field>::Scalar> {
-        Some(hash_to_scalar(&[CONTEXT_STRING.as_bytes(), b"id", m]))
+        Some(ciphersuite_hash_to_scalar(TAG_ID, m))
     }
 
     fn challenge(
@@ -308,36 +323,332 @@ pub mod round1 {
     {
         frost::round1::commit::<BluePallas<M>, RNG>(secret, rng)
     }
+
+    /// Accept nonces generated outside [`commit`], e.g. by a secure enclave.
+    ///
+    /// The commitments are recomputed from the hiding and binding nonces and must equal
+    /// `commitments`, the values that will be published to the coordinator; otherwise
+    /// [`Error::IncorrectCommitment`] is returned.
+    pub fn import_nonces<M: ChallengeMessage>(
+        nonces: &SigningNonces<M>,
+        commitments: &SigningCommitments<M>,
+    ) -> Result<SigningNonces<M>, Error<M>> {
+        let nonces = SigningNonces::from_nonces(*nonces.hiding(), *nonces.binding());
+        if nonces.commitments() != commitments {
+            return Err(Error::IncorrectCommitment);
+        }
+        Ok(nonces)
+    }
 }
 
 /// FROST(Pallas, Posiedon) Round 2 functionality and types, for signature share generation.
 pub mod round2 {
     use super::*;
-    use crate::{round1::SigningNonces, ChallengeMessage};
+    use crate::{
+        errors::{BluePallasError, FrostBluePallasError, NonceCommitmentKind},
+        round1::SigningNonces,
+        ChallengeMessage,
+    };
 
     /// A FROST(Pallas, Posiedon) participant's signature share, which the Coordinator will aggregate with all other signer's
     /// shares into the joint signature.
     pub type SignatureShare<M> = frost::round2::SignatureShare<BluePallas<M>>;
 
+    /// Computes this participant's signature share.
+    ///
+    /// The key package's verifying key may have either Y parity: Mina hashes the full
+    /// public key into the challenge and only requires the group commitment to have even
+    /// Y, which is enforced here by negating the nonces when needed.
+    ///
+    /// The signing package is checked before the number of signers is: an empty package is
+    /// rejected with [`BluePallasError::NoCommitmentsProvided`], and one without this
+    /// signer's commitments with [`Error::MissingCommitment`], however many other signers it
+    /// holds. If the package's commitments for this signer differ from `signer_nonces`,
+    /// [`BluePallasError::CommitmentMismatch`] names the hiding or binding commitment that
+    /// diverged. Other failures are reported by frost-core.
     pub fn sign<M>(
         signing_package: &SigningPackage<M>,
         signer_nonces: &SigningNonces<M>,
         key_package: &frost::keys::KeyPackage<BluePallas<M>>,
-    ) -> Result<SignatureShare<M>, Error<M>>
+    ) -> Result<SignatureShare<M>, FrostBluePallasError<M>>
     where
         M: ChallengeMessage,
     {
-        frost::round2::sign::<BluePallas<M>>(signing_package, signer_nonces, key_package)
+        if signing_package.signing_commitments().is_empty() {
+            return Err(BluePallasError::NoCommitmentsProvided.into());
+        }
+        let commitments = signing_package
+            .signing_commitment(key_package.identifier())
+            .ok_or(Error::<M>::MissingCommitment)?;
+
+        let expected = signer_nonces.commitments();
+        if commitments.hiding() != expected.hiding() {
+            return Err(BluePallasError::CommitmentMismatch(NonceCommitmentKind::Hiding).into());
+        }
+        if commitments.binding() != expected.binding() {
+            return Err(BluePallasError::CommitmentMismatch(NonceCommitmentKind::Binding).into());
+        }
+
+        Ok(frost::round2::sign::<BluePallas<M>>(
+            signing_package,
+            signer_nonces,
+            key_package,
+        )?)
     }
 }
 
+/// Aggregates the signature shares into the group signature, verifying it before returning.
+///
+/// Shares must come from exactly the participants that committed in the signing package;
+/// otherwise the missing and extra identifiers are reported in
+/// [`AggregateError::IdentifierMismatch`]. Fewer shares than the group's `min_signers`,
+/// including an empty set, are rejected with [`AggregateError::NotEnoughShares`].
 pub fn aggregate<M>(
     signing_package: &SigningPackage<M>,
     signature_shares: &BTreeMap<Identifier<M>, frost::round2::SignatureShare<BluePallas<M>>>,
     pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
-) -> Result<Signature<M>, Error<M>>
+) -> Result<Signature<M>, AggregateError<M>>
+where
+    M: ChallengeMessage,
+{
+    check_share_set(signing_package, signature_shares, pubkey_package)?;
+    Ok(frost::aggregate(
+        signing_package,
+        signature_shares,
+        pubkey_package,
+    )?)
+}
+
+/// Aggregates signature shares without verifying each share individually.
+///
+/// The aggregate signature is still verified against the group key, but when it is
+/// invalid no attempt is made to identify which share was at fault, and only
+/// [`Error::InvalidSignature`] is reported. This is only safe when every share has already
+/// been authenticated upstream, e.g. by the transport between signers and coordinator;
+/// use [`aggregate`] otherwise.
+pub fn aggregate_trusted<M>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, frost::round2::SignatureShare<BluePallas<M>>>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+) -> Result<Signature<M>, AggregateError<M>>
+where
+    M: ChallengeMessage,
+{
+    check_share_set(signing_package, signature_shares, pubkey_package)?;
+    Ok(frost::aggregate_custom(
+        signing_package,
+        signature_shares,
+        pubkey_package,
+        frost::CheaterDetection::Disabled,
+    )?)
+}
+
+/// Debug-only: aggregates the signature shares and returns the signature even if it does not
+/// verify.
+///
+/// **Never broadcast a signature from this function without checking the returned
+/// verification result.** It exists to inspect signatures that fail verification, e.g.
+/// when chasing a mismatch between the message the signers hashed and the one Mina
+/// expects. Shares are not verified individually. The outer error covers share sets that
+/// cannot be combined at all; the inner result is the group signature check that
+/// [`aggregate`] would have enforced.
+#[allow(clippy::type_complexity)]
+pub fn aggregate_unchecked<M>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, frost::round2::SignatureShare<BluePallas<M>>>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+) -> Result<(Signature<M>, Result<(), Error<M>>), AggregateError<M>>
+where
+    M: ChallengeMessage,
+{
+    check_share_set(signing_package, signature_shares, pubkey_package)?;
+
+    // Mirror frost_core::aggregate up to, but not including, the verification step
+    let binding_factor_list =
+        frost::compute_binding_factor_list(signing_package, pubkey_package.verifying_key(), &[])?;
+    let signing_package =
+        BluePallas::<M>::pre_commitment_aggregate(signing_package, &binding_factor_list)?;
+    let group_commitment = compute_group_commitment(&signing_package, &binding_factor_list)?;
+    let z = signature_shares
+        .values()
+        .fold(<PallasScalarField as Field>::zero(), |z, share| {
+            z + share.to_scalar()
+        });
+    let signature = Signature::<M>::new(group_commitment.to_element(), z);
+
+    let verification = pubkey_package
+        .verifying_key()
+        .verify(signing_package.message(), &signature);
+    Ok((signature, verification))
+}
+
+/// Progress reported by [`aggregate_with_progress`]. Events only carry identifiers, never
+/// shares or other secret material.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregateProgress<M: ChallengeMessage> {
+    /// The signature share of this participant is being verified
+    VerifyingShare(Identifier<M>),
+    /// All shares verified; they are being combined and the group signature checked
+    Aggregating,
+}
+
+/// Like [`aggregate`], but calls `progress` as each share is verified and before the
+/// shares are combined.
+///
+/// Every share is verified up front, so the first invalid share is reported as soon as it
+/// is reached instead of after a failed aggregation.
+pub fn aggregate_with_progress<M>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, frost::round2::SignatureShare<BluePallas<M>>>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+    progress: &mut dyn FnMut(AggregateProgress<M>),
+) -> Result<Signature<M>, AggregateError<M>>
 where
     M: ChallengeMessage,
 {
-    frost::aggregate(signing_package, signature_shares, pubkey_package)
+    check_share_set(signing_package, signature_shares, pubkey_package)?;
+
+    for (identifier, share) in signature_shares {
+        progress(AggregateProgress::VerifyingShare(*identifier));
+        verify_signature_share(*identifier, share, signing_package, pubkey_package)?;
+    }
+
+    progress(AggregateProgress::Aggregating);
+    Ok(frost::aggregate_custom(
+        signing_package,
+        signature_shares,
+        pubkey_package,
+        frost::CheaterDetection::Disabled,
+    )?)
+}
+
+/// Per-participant outcome of an aggregation, returned by [`aggregate_with_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateReport<M: ChallengeMessage> {
+    /// Participants that submitted a signature share
+    pub submitted: BTreeSet<Identifier<M>>,
+    /// Participants whose share verified against their verifying share
+    pub verified: BTreeSet<Identifier<M>>,
+    /// Participants whose share could not be verified, with the reason
+    pub failed: BTreeMap<Identifier<M>, Error<M>>,
+    /// The aggregated group signature, or the reason aggregation failed
+    pub result: Result<Signature<M>, AggregateError<M>>,
+}
+
+impl<M: ChallengeMessage> AggregateReport<M> {
+    /// Whether aggregation produced a valid group signature.
+    pub fn is_valid(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Like [`aggregate`], but verifies every share individually and reports which
+/// participants submitted, verified or failed alongside the final result.
+///
+/// This is meant for coordinator UIs; callers that only need the signature should use
+/// [`aggregate`], which stops at the first invalid share.
+pub fn aggregate_with_report<M>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, frost::round2::SignatureShare<BluePallas<M>>>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+) -> AggregateReport<M>
+where
+    M: ChallengeMessage,
+{
+    let mut verified = BTreeSet::new();
+    let mut failed = BTreeMap::new();
+    for (identifier, share) in signature_shares {
+        match verify_signature_share(*identifier, share, signing_package, pubkey_package) {
+            Ok(()) => {
+                verified.insert(*identifier);
+            }
+            Err(e) => {
+                failed.insert(*identifier, e);
+            }
+        }
+    }
+
+    AggregateReport {
+        submitted: signature_shares.keys().copied().collect(),
+        verified,
+        failed,
+        result: aggregate(signing_package, signature_shares, pubkey_package),
+    }
+}
+
+/// Verifies a single signature share as soon as it arrives, without waiting for the others.
+///
+/// The binding factors, group commitment (with Mina's even-Y adjustment) and Lagrange
+/// coefficient are derived from `signing_package`, so the share is checked exactly as
+/// [`aggregate`] would check it. A coordinator can reject a bad share immediately and ask
+/// the participant to resubmit. An invalid share fails with
+/// [`Error::InvalidSignatureShare`] naming `identifier`, and an identifier without a
+/// verifying share in `pubkey_package` with [`Error::UnknownIdentifier`].
+pub fn verify_signature_share<M>(
+    identifier: Identifier<M>,
+    signature_share: &frost::round2::SignatureShare<BluePallas<M>>,
+    signing_package: &SigningPackage<M>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+) -> Result<(), Error<M>>
+where
+    M: ChallengeMessage,
+{
+    let verifying_share = pubkey_package
+        .verifying_shares()
+        .get(&identifier)
+        .ok_or(Error::UnknownIdentifier)?;
+    frost::verify_signature_share(
+        identifier,
+        verifying_share,
+        signature_share,
+        signing_package,
+        pubkey_package.verifying_key(),
+    )
+}
+
+/// Structural checks shared by [`aggregate`] and [`aggregate_trusted`].
+fn check_share_set<M, T>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, T>,
+    pubkey_package: &frost::keys::PublicKeyPackage<BluePallas<M>>,
+) -> Result<(), AggregateError<M>>
+where
+    M: ChallengeMessage,
+{
+    check_share_identifiers(signing_package, signature_shares)?;
+
+    // Packages without a recorded threshold still need at least one share
+    let required = pubkey_package.min_signers().unwrap_or(1);
+    if signature_shares.len() < required as usize {
+        return Err(AggregateError::NotEnoughShares {
+            received: signature_shares.len(),
+            required,
+        });
+    }
+    Ok(())
+}
+
+/// Compare the identifiers that committed with those that sent signature shares.
+fn check_share_identifiers<M, T>(
+    signing_package: &SigningPackage<M>,
+    signature_shares: &BTreeMap<Identifier<M>, T>,
+) -> Result<(), IdentifierMismatch<M>>
+where
+    M: ChallengeMessage,
+{
+    let committed: BTreeSet<_> = signing_package
+        .signing_commitments()
+        .keys()
+        .copied()
+        .collect();
+    let shared: BTreeSet<_> = signature_shares.keys().copied().collect();
+
+    if committed == shared {
+        return Ok(());
+    }
+
+    Err(IdentifierMismatch {
+        missing: committed.difference(&shared).copied().collect(),
+        extra: shared.difference(&committed).copied().collect(),
+    })
 }
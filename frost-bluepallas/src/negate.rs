@@ -71,13 +71,17 @@ mod tests {
 
     use super::*;
     use alloc::collections::BTreeMap;
+    use ark_ec::CurveGroup;
     use ark_ff::UniformRand;
+    use ark_ff::{BigInteger, PrimeField};
+    use frost_core::Ciphersuite;
     use frost_core::{
         round1::{Nonce, NonceCommitment},
         Group,
     };
     use mina_curves::pasta::ProjectivePallas;
-    use rand_core::OsRng;
+    use rand_chacha::ChaChaRng;
+    use rand_core::{OsRng, SeedableRng};
 
     #[derive(Clone, Debug, PartialEq, Eq)]
     struct TestMessage;
@@ -188,4 +192,99 @@ mod tests {
             assert_eq!(new_b, -orig_b);
         }
     }
+
+    type Scalar = <PallasScalarField as frost_core::Field>::Scalar;
+
+    /// Number of random cases checked by each invariant test.
+    const CASES: u64 = 64;
+
+    fn nonces_from(hiding: Scalar, binding: Scalar) -> SigningNonces {
+        SigningNonces::from_nonces(
+            Nonce::<Suite>::from_scalar(hiding),
+            Nonce::<Suite>::from_scalar(binding),
+        )
+    }
+
+    #[test]
+    fn signing_nonces_negate_y_is_an_involution() {
+        let mut rng = ChaChaRng::seed_from_u64(0);
+        for _ in 0..CASES {
+            let (hiding, binding) = (Scalar::rand(&mut rng), Scalar::rand(&mut rng));
+            let nonces = nonces_from(hiding, binding);
+            let twice = nonces.negate_y().negate_y();
+
+            assert_eq!(twice.hiding().to_scalar(), hiding);
+            assert_eq!(twice.binding().to_scalar(), binding);
+            assert_eq!(twice.commitments(), nonces.commitments());
+        }
+    }
+
+    #[test]
+    fn signing_package_negate_y_is_an_involution() {
+        let mut rng = ChaChaRng::seed_from_u64(1);
+        for case in 0..CASES {
+            let commitments: BTreeMap<_, _> = (1..=(case % 4 + 1) as u16)
+                .map(|i| {
+                    let nonces = nonces_from(Scalar::rand(&mut rng), Scalar::rand(&mut rng));
+                    (
+                        frost_core::Identifier::try_from(i).unwrap(),
+                        *nonces.commitments(),
+                    )
+                })
+                .collect();
+            let package = SigningPackage::new(commitments, &case.to_le_bytes());
+
+            assert_eq!(package.negate_y().negate_y(), package);
+        }
+    }
+
+    #[test]
+    fn pre_commitment_sign_yields_even_group_commitment() {
+        let mut rng = ChaChaRng::seed_from_u64(2);
+        let (shares, pubkey_package) = frost_core::keys::generate_with_dealer::<Suite, _>(
+            3,
+            2,
+            frost_core::keys::IdentifierList::Default,
+            &mut rng,
+        )
+        .unwrap();
+
+        // Roughly half of the cases start from an odd group commitment and get negated
+        for _ in 0..CASES {
+            let mut nonces = BTreeMap::new();
+            let mut commitments = BTreeMap::new();
+            for (id, share) in shares.iter().take(2) {
+                let (n, c) = frost_core::round1::commit(share.signing_share(), &mut rng);
+                nonces.insert(*id, n);
+                commitments.insert(*id, c);
+            }
+            let package = SigningPackage::new(commitments, b"negate invariants");
+            let binding_factors = frost_core::compute_binding_factor_list(
+                &package,
+                pubkey_package.verifying_key(),
+                &[],
+            )
+            .unwrap();
+            let aggregated = Suite::pre_commitment_aggregate(&package, &binding_factors).unwrap();
+
+            for (id, signer_nonces) in &nonces {
+                let (signed_package, signed_nonces) =
+                    Suite::pre_commitment_sign(&package, signer_nonces, &binding_factors).unwrap();
+                let commitment =
+                    frost_core::compute_group_commitment(&signed_package, &binding_factors)
+                        .unwrap()
+                        .to_element()
+                        .into_affine();
+                assert!(commitment.y.into_bigint().is_even());
+
+                // The signer's nonces must still match its commitments in the package, and
+                // the coordinator must make the same choice as the signers
+                assert_eq!(
+                    signed_nonces.commitments(),
+                    &signed_package.signing_commitments()[id]
+                );
+                assert_eq!(aggregated.as_ref(), signed_package.as_ref());
+            }
+        }
+    }
 }
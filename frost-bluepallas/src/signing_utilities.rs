@@ -1,8 +1,13 @@
 //! Convenience functions to generate FROST signatures from various inputs.
 
-use alloc::collections::BTreeMap;
+use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
 
-use crate::{BluePallas, ChallengeMessage};
+use frost_core::Ciphersuite;
+
+use crate::{
+    errors::BluePallasError, keys::IdentifierExt, BluePallas, ChallengeMessage, FIELD_SIZE,
+    GROUP_SIZE,
+};
 use rand_core::{CryptoRng, RngCore};
 
 type SignResult<M> = Result<
@@ -13,6 +18,222 @@ type SignResult<M> = Result<
     frost_core::Error<BluePallas<M>>,
 >;
 
+/// Incrementally assembles a [`frost_core::SigningPackage`] from collected commitments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningPackageBuilder<M: ChallengeMessage> {
+    commitments: BTreeMap<
+        frost_core::Identifier<BluePallas<M>>,
+        frost_core::round1::SigningCommitments<BluePallas<M>>,
+    >,
+    message: Option<Vec<u8>>,
+}
+
+impl<M: ChallengeMessage> Default for SigningPackageBuilder<M> {
+    fn default() -> Self {
+        Self {
+            commitments: BTreeMap::new(),
+            message: None,
+        }
+    }
+}
+
+impl<M: ChallengeMessage> SigningPackageBuilder<M> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a participant's commitments, replacing any earlier entry for the same identifier.
+    pub fn add_commitment(
+        mut self,
+        identifier: frost_core::Identifier<BluePallas<M>>,
+        commitments: frost_core::round1::SigningCommitments<BluePallas<M>>,
+    ) -> Self {
+        self.commitments.insert(identifier, commitments);
+        self
+    }
+
+    /// Set the message bytes to be signed.
+    pub fn message(mut self, message: &[u8]) -> Self {
+        self.message = Some(message.to_vec());
+        self
+    }
+
+    /// Build the signing package, requiring at least one commitment and a message.
+    pub fn build(self) -> Result<frost_core::SigningPackage<BluePallas<M>>, BluePallasError> {
+        if self.commitments.is_empty() {
+            return Err(BluePallasError::NoCommitmentsProvided);
+        }
+        let message = self.message.ok_or(BluePallasError::NoMessageProvided)?;
+
+        Ok(frost_core::SigningPackage::new(self.commitments, &message))
+    }
+}
+
+/// Version byte leading the [`SigningPackageExt::to_compact_bytes`] encoding.
+const COMPACT_VERSION: u8 = 1;
+
+/// Bytes taken by one participant in the compact encoding: identifier, hiding and binding
+/// commitments.
+const COMPACT_ENTRY_SIZE: usize = FIELD_SIZE + 2 * GROUP_SIZE;
+
+/// A compact binary encoding of [`frost_core::SigningPackage`] for large signer sets.
+///
+/// The layout is a version byte, the number of participants as a little-endian `u16`, one
+/// fixed-size entry per participant in identifier order (the identifier followed by the
+/// compressed hiding and binding commitments), then the message length as a little-endian
+/// `u32` and the message itself. There is no per-field framing, so it is several times
+/// smaller than the JSON form.
+pub trait SigningPackageExt<M: ChallengeMessage>: Sized {
+    /// Encode the package in the compact form.
+    fn to_compact_bytes(&self) -> Result<Vec<u8>, BluePallasError>;
+
+    /// Decode a package produced by [`SigningPackageExt::to_compact_bytes`].
+    fn from_compact_bytes(bytes: &[u8]) -> Result<Self, BluePallasError>;
+
+    /// Exact length of the package serialized with `serde_json::to_vec`, computed without
+    /// serializing it. Every identifier and commitment has a fixed-width hex encoding, so
+    /// the size only depends on the number of participants and the message length.
+    fn serialized_len(&self) -> usize;
+
+    /// Exact length of [`SigningPackageExt::to_compact_bytes`], without encoding.
+    fn compact_len(&self) -> usize;
+}
+
+impl<M: ChallengeMessage> SigningPackageExt<M> for frost_core::SigningPackage<BluePallas<M>> {
+    fn to_compact_bytes(&self) -> Result<Vec<u8>, BluePallasError> {
+        let commitments = self.signing_commitments();
+        let count = u16::try_from(commitments.len())
+            .map_err(|_| BluePallasError::serialization_error("too many signing commitments"))?;
+        let message_len = u32::try_from(self.message().len())
+            .map_err(|_| BluePallasError::serialization_error("message too long"))?;
+
+        let mut bytes = Vec::with_capacity(self.compact_len());
+        bytes.push(COMPACT_VERSION);
+        bytes.extend_from_slice(&count.to_le_bytes());
+        for (identifier, commitment) in commitments {
+            bytes.extend_from_slice(&identifier.serialize());
+            for nonce_commitment in [commitment.hiding(), commitment.binding()] {
+                let encoded = nonce_commitment
+                    .serialize()
+                    .map_err(|e| BluePallasError::serialization_error(e.to_string()))?;
+                bytes.extend_from_slice(&encoded);
+            }
+        }
+        bytes.extend_from_slice(&message_len.to_le_bytes());
+        bytes.extend_from_slice(self.message());
+
+        Ok(bytes)
+    }
+
+    fn from_compact_bytes(bytes: &[u8]) -> Result<Self, BluePallasError> {
+        let mut reader = CompactReader(bytes);
+        if reader.take(1)?[0] != COMPACT_VERSION {
+            return Err(BluePallasError::deserialization_error(
+                "unsupported compact signing package version",
+            ));
+        }
+        let count = u16::from_le_bytes(reader.take_array()?);
+
+        let mut commitments = BTreeMap::new();
+        for _ in 0..count {
+            let identifier =
+                frost_core::Identifier::<BluePallas<M>>::try_from_bytes(reader.take(FIELD_SIZE)?)
+                    .map_err(|e| BluePallasError::deserialization_error(e.to_string()))?;
+            let hiding = frost_core::round1::NonceCommitment::deserialize(reader.take(GROUP_SIZE)?)
+                .map_err(|e| BluePallasError::deserialization_error(e.to_string()))?;
+            let binding =
+                frost_core::round1::NonceCommitment::deserialize(reader.take(GROUP_SIZE)?)
+                    .map_err(|e| BluePallasError::deserialization_error(e.to_string()))?;
+            let commitment = frost_core::round1::SigningCommitments::new(hiding, binding);
+            if commitments.insert(identifier, commitment).is_some() {
+                return Err(BluePallasError::deserialization_error(
+                    "duplicate identifier in compact signing package",
+                ));
+            }
+        }
+
+        let message_len = u32::from_le_bytes(reader.take_array()?) as usize;
+        let message = reader.take(message_len)?;
+        if !reader.0.is_empty() {
+            return Err(BluePallasError::deserialization_error(
+                "trailing bytes after compact signing package",
+            ));
+        }
+
+        Ok(frost_core::SigningPackage::new(commitments, message))
+    }
+
+    fn serialized_len(&self) -> usize {
+        // {"version":0,"ciphersuite":"<ID>"}
+        let header = r#"{"version":0,"ciphersuite":""}"#.len() + BluePallas::<M>::ID.len();
+        // "<identifier>":{"header":<header>,"hiding":"<hex>","binding":"<hex>"}
+        let entry = r#""":{"header":,"hiding":"","binding":""}"#.len()
+            + 2 * FIELD_SIZE
+            + header
+            + 2 * 2 * GROUP_SIZE;
+        let count = self.signing_commitments().len();
+        // {"header":<header>,"signing_commitments":{<entries>},"message":"<hex>"}
+        r#"{"header":,"signing_commitments":{},"message":""}"#.len()
+            + header
+            + count * entry
+            + count.saturating_sub(1)
+            + 2 * self.message().len()
+    }
+
+    fn compact_len(&self) -> usize {
+        7 + self.signing_commitments().len() * COMPACT_ENTRY_SIZE + self.message().len()
+    }
+}
+
+/// Raw binary encoding of a signature share for the share-submission path.
+///
+/// The share is the 32-byte little-endian scalar produced by
+/// [`PallasScalarField::serialize`](crate::PallasScalarField), with no header.
+pub trait SignatureShareExt<M: ChallengeMessage>: Sized {
+    /// Encode the share as its raw scalar bytes.
+    fn to_bytes(&self) -> [u8; FIELD_SIZE];
+
+    /// Decode a share produced by [`SignatureShareExt::to_bytes`].
+    fn from_bytes(bytes: &[u8; FIELD_SIZE]) -> Result<Self, BluePallasError>;
+}
+
+impl<M: ChallengeMessage> SignatureShareExt<M>
+    for frost_core::round2::SignatureShare<BluePallas<M>>
+{
+    fn to_bytes(&self) -> [u8; FIELD_SIZE] {
+        let mut bytes = [0u8; FIELD_SIZE];
+        bytes.copy_from_slice(&self.serialize());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; FIELD_SIZE]) -> Result<Self, BluePallasError> {
+        frost_core::round2::SignatureShare::deserialize(bytes)
+            .map_err(|e| BluePallasError::deserialization_error(e.to_string()))
+    }
+}
+
+/// Cursor over the input of [`SigningPackageExt::from_compact_bytes`].
+struct CompactReader<'a>(&'a [u8]);
+
+impl<'a> CompactReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BluePallasError> {
+        if self.0.len() < len {
+            return Err(BluePallasError::deserialization_error(
+                "compact signing package is truncated",
+            ));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], BluePallasError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
 /// Helper function to sign a message using existing key packages
 pub fn sign_from_packages<M, R: RngCore + CryptoRng>(
     message: &[u8],
@@ -0,0 +1,276 @@
+mod helpers;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use frost_bluepallas::{
+    aggregate, aggregate_trusted, aggregate_unchecked, aggregate_with_progress,
+    aggregate_with_report,
+    errors::{AggregateError, IdentifierMismatch},
+    keys::{generate_key_packages_with_dealer, IdentifierList},
+    verify_signature_share, AggregateProgress, Error,
+};
+use helpers::types::{Identifier, PublicKeyPackage, SignatureShare, SigningPackage};
+use mina_hasher::ROInput;
+use mina_tx::{pallas_message::PallasMessage, NetworkId};
+use rand_core::SeedableRng;
+
+/// Run both signing rounds for participants 1..=`num_signers` of a 2-of-3 dealer setup.
+fn sign_with(
+    num_signers: u16,
+) -> (
+    SigningPackage,
+    BTreeMap<Identifier, SignatureShare>,
+    PublicKeyPackage,
+) {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+        3,
+        2,
+        IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+    let signers = || key_packages.values().take(num_signers.into());
+    let (nonces, commitments) = helpers::commit(signers(), &mut rng);
+
+    let message = PallasMessage::from_parts(
+        ROInput::new().append_bytes(b"aggregate tests"),
+        NetworkId::Testnet,
+        true,
+    )
+    .serialize()
+    .unwrap();
+    let signing_package = SigningPackage::new(commitments, &message);
+    let signature_shares = helpers::signature_shares(signers(), &signing_package, &nonces);
+
+    (signing_package, signature_shares, pubkey_package)
+}
+
+#[test]
+fn aggregate_succeeds_with_matching_identifiers() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+    let signature = aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap();
+    assert!(pubkey_package
+        .verifying_key()
+        .verify(signing_package.message(), &signature)
+        .is_ok());
+}
+
+#[test]
+fn aggregate_reports_missing_share() {
+    let (signing_package, mut signature_shares, pubkey_package) = sign_with(2);
+    let missing = Identifier::try_from(2).unwrap();
+    signature_shares.remove(&missing);
+
+    let err = aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap_err();
+    assert_eq!(
+        err,
+        AggregateError::IdentifierMismatch(IdentifierMismatch {
+            missing: BTreeSet::from([missing]),
+            extra: BTreeSet::new(),
+        })
+    );
+}
+
+#[test]
+fn aggregate_reports_extra_share() {
+    let (signing_package, mut signature_shares, pubkey_package) = sign_with(2);
+    let extra = Identifier::try_from(3).unwrap();
+    let share = signature_shares[&Identifier::try_from(1).unwrap()];
+    signature_shares.insert(extra, share);
+
+    let err = aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap_err();
+    assert_eq!(
+        err,
+        AggregateError::IdentifierMismatch(IdentifierMismatch {
+            missing: BTreeSet::new(),
+            extra: BTreeSet::from([extra]),
+        })
+    );
+}
+
+#[test]
+fn aggregate_rejects_empty_share_set() {
+    let (signing_package, _, pubkey_package) = sign_with(2);
+    let empty_package = SigningPackage::new(BTreeMap::new(), signing_package.message());
+
+    let err = aggregate(&empty_package, &BTreeMap::new(), &pubkey_package).unwrap_err();
+    assert_eq!(
+        err,
+        AggregateError::NotEnoughShares {
+            received: 0,
+            required: 2
+        }
+    );
+}
+
+#[test]
+fn aggregate_rejects_under_quorum_share_set() {
+    let (signing_package, mut signature_shares, pubkey_package) = sign_with(2);
+
+    // Drop a signer from both the package and the shares so the identifiers still match
+    let dropped = Identifier::try_from(2).unwrap();
+    let mut commitments = signing_package.signing_commitments().clone();
+    commitments.remove(&dropped);
+    signature_shares.remove(&dropped);
+    let under_quorum_package = SigningPackage::new(commitments, signing_package.message());
+
+    let err = aggregate(&under_quorum_package, &signature_shares, &pubkey_package).unwrap_err();
+    assert_eq!(
+        err,
+        AggregateError::NotEnoughShares {
+            received: 1,
+            required: 2
+        }
+    );
+}
+
+#[test]
+fn aggregate_trusted_matches_aggregate_for_honest_shares() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+    let verified = aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap();
+    let trusted = aggregate_trusted(&signing_package, &signature_shares, &pubkey_package).unwrap();
+    assert_eq!(verified, trusted);
+}
+
+#[test]
+fn aggregate_trusted_still_verifies_the_group_signature() {
+    let (signing_package, mut signature_shares, pubkey_package) = sign_with(2);
+    let first = Identifier::try_from(1).unwrap();
+    let second = Identifier::try_from(2).unwrap();
+    let swapped = signature_shares[&second];
+    signature_shares.insert(first, swapped);
+
+    let err = aggregate_trusted(&signing_package, &signature_shares, &pubkey_package).unwrap_err();
+    assert_eq!(
+        err,
+        AggregateError::Frost(frost_core::Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn aggregate_report_lists_single_failing_participant() {
+    let (signing_package, mut signature_shares, pubkey_package) = sign_with(3);
+    let ids: Vec<Identifier> = signature_shares.keys().copied().collect();
+
+    // Participant 2 submits participant 1's share; 1 and 3 stay honest
+    let share1 = signature_shares[&ids[0]];
+    signature_shares.insert(ids[1], share1);
+
+    let report = aggregate_with_report(&signing_package, &signature_shares, &pubkey_package);
+    assert_eq!(
+        report.submitted,
+        ids.iter().copied().collect::<BTreeSet<_>>()
+    );
+    assert_eq!(report.verified, BTreeSet::from([ids[0], ids[2]]));
+    assert_eq!(
+        report.failed.keys().copied().collect::<Vec<_>>(),
+        vec![ids[1]]
+    );
+    assert!(!report.is_valid());
+    assert!(matches!(
+        report.result,
+        Err(AggregateError::Frost(
+            frost_core::Error::InvalidSignatureShare { .. }
+        ))
+    ));
+}
+
+#[test]
+fn aggregate_report_for_valid_shares() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+
+    let report = aggregate_with_report(&signing_package, &signature_shares, &pubkey_package);
+    assert_eq!(report.verified, report.submitted);
+    assert!(report.failed.is_empty());
+    assert_eq!(
+        report.result,
+        aggregate(&signing_package, &signature_shares, &pubkey_package)
+    );
+    assert!(report.is_valid());
+}
+
+#[test]
+fn aggregate_with_progress_reports_each_share_then_aggregation() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+
+    let mut events = Vec::new();
+    let signature = aggregate_with_progress(
+        &signing_package,
+        &signature_shares,
+        &pubkey_package,
+        &mut |event| events.push(event),
+    )
+    .unwrap();
+
+    assert_eq!(
+        signature,
+        aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap()
+    );
+    assert_eq!(
+        events,
+        vec![
+            AggregateProgress::VerifyingShare(Identifier::try_from(1).unwrap()),
+            AggregateProgress::VerifyingShare(Identifier::try_from(2).unwrap()),
+            AggregateProgress::Aggregating,
+        ]
+    );
+}
+
+#[test]
+fn aggregate_unchecked_matches_aggregate_for_honest_shares() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+    let verified = aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap();
+
+    let (signature, verification) =
+        aggregate_unchecked(&signing_package, &signature_shares, &pubkey_package).unwrap();
+    assert_eq!(signature, verified);
+    assert_eq!(verification, Ok(()));
+}
+
+#[test]
+fn aggregate_unchecked_returns_signature_that_fails_verification() {
+    // Shares were produced for a different message than the one in the package, as
+    // happens when signers and coordinator disagree on the message encoding
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+    let mismatched = SigningPackage::new(
+        signing_package.signing_commitments().clone(),
+        b"a differently encoded message",
+    );
+
+    let (signature, verification) =
+        aggregate_unchecked(&mismatched, &signature_shares, &pubkey_package).unwrap();
+    assert_eq!(verification, Err(frost_core::Error::InvalidSignature));
+    assert!(pubkey_package
+        .verifying_key()
+        .verify(mismatched.message(), &signature)
+        .is_err());
+    assert!(aggregate(&mismatched, &signature_shares, &pubkey_package).is_err());
+}
+
+#[test]
+fn verify_signature_share_checks_each_share_alone() {
+    let (signing_package, signature_shares, pubkey_package) = sign_with(2);
+    for (id, share) in &signature_shares {
+        verify_signature_share(*id, share, &signing_package, &pubkey_package).unwrap();
+    }
+
+    let id = Identifier::try_from(2).unwrap();
+    let mut bytes = signature_shares[&id].serialize();
+    bytes[0] ^= 1;
+    let flipped = SignatureShare::deserialize(&bytes).unwrap();
+    let err = verify_signature_share(id, &flipped, &signing_package, &pubkey_package).unwrap_err();
+    assert!(matches!(err, Error::InvalidSignatureShare { .. }));
+    assert_eq!(err.culprits(), vec![id]);
+
+    let unknown = Identifier::try_from(9).unwrap();
+    assert_eq!(
+        verify_signature_share(
+            unknown,
+            &signature_shares[&id],
+            &signing_package,
+            &pubkey_package
+        ),
+        Err(Error::UnknownIdentifier)
+    );
+}
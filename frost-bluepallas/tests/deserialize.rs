@@ -1,7 +1,8 @@
+use ark_ec::CurveGroup;
 use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::CanonicalSerialize;
 use frost_bluepallas::{PallasGroup, PallasScalarField, FIELD_SIZE, GROUP_SIZE};
-use frost_core::{Field, Group};
+use frost_core::{Field, Group, GroupError};
 
 const ONE_LE: [u8; FIELD_SIZE] = [
     0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -81,6 +82,34 @@ fn deserialize_group_element_invalid() {
     );
 }
 
+#[test]
+fn deserialize_group_element_rejects_off_curve_encodings() {
+    // Roughly half of all x-coordinates have no point on the curve; none of them may decode
+    let mut rejected = 0;
+    for x in 2u8..64 {
+        let mut buf = [0u8; GROUP_SIZE];
+        buf[0] = x;
+        match PallasGroup::deserialize(&buf) {
+            Ok(point) => assert!(
+                point.into_affine().is_on_curve(),
+                "x = {x} decoded to a point off the curve"
+            ),
+            Err(err) => {
+                assert_eq!(err, GroupError::MalformedElement);
+                rejected += 1;
+            }
+        }
+    }
+    assert!(rejected > 0, "expected some x-coordinates to be off-curve");
+
+    // A non-canonical x-coordinate (all bits set exceeds the base field modulus)
+    let buf = [0xffu8; GROUP_SIZE];
+    assert_eq!(
+        PallasGroup::deserialize(&buf),
+        Err(GroupError::MalformedElement)
+    );
+}
+
 #[test]
 fn group_regression_vectors_are_stable() {
     let generator = PallasGroup::generator();
@@ -0,0 +1,62 @@
+use std::error::Error as _;
+
+use frost_bluepallas::{
+    errors::{AggregateError, BluePallasError, FrostBluePallasError},
+    signing_utilities::SigningPackageBuilder,
+    Error,
+};
+use mina_tx::pallas_message::PallasMessage;
+
+fn build_package() -> Result<(), FrostBluePallasError<PallasMessage>> {
+    SigningPackageBuilder::<PallasMessage>::new()
+        .message(b"no commitments")
+        .build()?;
+    Ok(())
+}
+
+fn frost_operation() -> Result<(), FrostBluePallasError<PallasMessage>> {
+    Err(Error::<PallasMessage>::InvalidSignature)?
+}
+
+#[test]
+fn conversions_keep_the_error_kind() {
+    let crate_error = build_package().unwrap_err();
+    assert_eq!(
+        crate_error,
+        FrostBluePallasError::BluePallas(BluePallasError::NoCommitmentsProvided)
+    );
+    assert_eq!(crate_error.frost_error(), None);
+
+    let signing_error = frost_operation().unwrap_err();
+    assert_eq!(signing_error.frost_error(), Some(&Error::InvalidSignature));
+
+    let aggregate_error: FrostBluePallasError<PallasMessage> =
+        AggregateError::Frost(Error::InvalidSignature).into();
+    assert_eq!(
+        aggregate_error.frost_error(),
+        Some(&Error::InvalidSignature)
+    );
+}
+
+#[test]
+fn unified_error_exposes_the_original_as_source() {
+    let crate_error = build_package().unwrap_err();
+    let source = crate_error.source().unwrap();
+    assert_eq!(
+        source.downcast_ref::<BluePallasError>(),
+        Some(&BluePallasError::NoCommitmentsProvided)
+    );
+
+    let signing_error = frost_operation().unwrap_err();
+    assert!(signing_error
+        .source()
+        .unwrap()
+        .downcast_ref::<Error<PallasMessage>>()
+        .is_some());
+    // The message does not repeat the source, which error reporters print on their own
+    assert_eq!(signing_error.to_string(), "FROST operation failed");
+    assert_eq!(
+        signing_error.source().unwrap().to_string(),
+        Error::<PallasMessage>::InvalidSignature.to_string()
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+
+mod helpers;
+
+use std::collections::BTreeMap;
+
+use frost_bluepallas::{
+    aggregate,
+    errors::{BluePallasError, FrostBluePallasError, NonceCommitmentKind},
+    keys::{generate_key_packages_with_dealer, IdentifierList},
+    round1::{commit, import_nonces},
+    round2::sign,
+};
+use helpers::types::{
+    Identifier, KeyPackage, PublicKeyPackage, SigningCommitments, SigningNonces, SigningPackage,
+};
+use mina_hasher::ROInput;
+use mina_tx::{pallas_message::PallasMessage, NetworkId};
+use rand_core::SeedableRng;
+
+fn message() -> Vec<u8> {
+    PallasMessage::from_parts(
+        ROInput::new().append_bytes(b"enclave"),
+        NetworkId::Testnet,
+        true,
+    )
+    .serialize()
+    .unwrap()
+}
+
+fn dealer_key_packages(
+    rng: &mut rand_chacha::ChaChaRng,
+) -> (BTreeMap<Identifier, KeyPackage>, PublicKeyPackage) {
+    generate_key_packages_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, rng)
+        .unwrap()
+}
+
+/// Nonces and commitments that went through JSON, as if produced by an enclave.
+fn through_json(
+    nonces: &SigningNonces,
+    commitments: &SigningCommitments,
+) -> (SigningNonces, SigningCommitments) {
+    let nonces: SigningNonces =
+        serde_json::from_str(&serde_json::to_string(nonces).unwrap()).unwrap();
+    let commitments: SigningCommitments =
+        serde_json::from_str(&serde_json::to_string(commitments).unwrap()).unwrap();
+    (nonces, commitments)
+}
+
+/// Round 1 for `key_package` alone, with the output passed through JSON.
+fn external_round1(
+    key_package: &KeyPackage,
+    rng: &mut rand_chacha::ChaChaRng,
+) -> (SigningNonces, SigningCommitments) {
+    let (nonces, commitments) = commit(key_package.signing_share(), rng);
+    through_json(&nonces, &commitments)
+}
+
+#[test]
+fn check_imported_nonces_sign_correctly() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, pubkey_package) = dealer_key_packages(&mut rng);
+    let signers: Vec<&KeyPackage> = key_packages.values().take(2).collect();
+
+    let (nonces, commitments) = helpers::commit(signers.iter().copied(), &mut rng);
+    let mut imported = BTreeMap::new();
+    let mut external_commitments = BTreeMap::new();
+    for (id, nonces) in &nonces {
+        let (external_nonces, external) = through_json(nonces, &commitments[id]);
+        imported.insert(*id, import_nonces(&external_nonces, &external).unwrap());
+        external_commitments.insert(*id, external);
+    }
+
+    let signing_package = SigningPackage::new(external_commitments, &message());
+    let shares = helpers::signature_shares(signers, &signing_package, &imported);
+    let signature = aggregate(&signing_package, &shares, &pubkey_package).unwrap();
+    assert!(pubkey_package
+        .verifying_key()
+        .verify(&message(), &signature)
+        .is_ok());
+}
+
+#[test]
+fn check_mismatched_commitments_are_rejected() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, _) = dealer_key_packages(&mut rng);
+    let key_package = key_packages.into_values().next().unwrap();
+
+    let (nonces, _) = external_round1(&key_package, &mut rng);
+    let (_, other_commitments) = external_round1(&key_package, &mut rng);
+
+    assert!(matches!(
+        import_nonces(&nonces, &other_commitments),
+        Err(frost_core::Error::IncorrectCommitment)
+    ));
+}
+
+#[test]
+fn check_sign_reports_corrupted_binding_commitment() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, _) = dealer_key_packages(&mut rng);
+    let (nonces, mut commitments) = helpers::commit(key_packages.values().take(2), &mut rng);
+
+    // A coordinator bug replaces only the binding commitment of the first signer
+    let (id, key_package) = key_packages.iter().next().unwrap();
+    let (_, other) = commit(key_package.signing_share(), &mut rng);
+    let corrupted = SigningCommitments::new(*commitments[id].hiding(), *other.binding());
+    commitments.insert(*id, corrupted);
+    let signing_package = SigningPackage::new(commitments, &message());
+
+    assert_eq!(
+        sign(&signing_package, &nonces[id], key_package).unwrap_err(),
+        FrostBluePallasError::BluePallas(BluePallasError::CommitmentMismatch(
+            NonceCommitmentKind::Binding
+        ))
+    );
+}
+
+#[test]
+fn check_sign_rejects_empty_package_and_missing_own_commitment() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, _) = dealer_key_packages(&mut rng);
+    let key_packages: Vec<KeyPackage> = key_packages.into_values().collect();
+    let (nonces, _) = commit(key_packages[0].signing_share(), &mut rng);
+
+    let empty = SigningPackage::new(BTreeMap::new(), &message());
+    assert_eq!(
+        sign(&empty, &nonces, &key_packages[0]).unwrap_err(),
+        FrostBluePallasError::BluePallas(BluePallasError::NoCommitmentsProvided)
+    );
+
+    // Too few signers as well, but not being in the package is the more useful report
+    let (_, other) = commit(key_packages[1].signing_share(), &mut rng);
+    let without_signer = SigningPackage::new(
+        BTreeMap::from([(*key_packages[1].identifier(), other)]),
+        &message(),
+    );
+    assert_eq!(
+        sign(&without_signer, &nonces, &key_packages[0]).unwrap_err(),
+        FrostBluePallasError::Frost(frost_bluepallas::Error::MissingCommitment)
+    );
+}
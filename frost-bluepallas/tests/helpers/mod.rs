@@ -5,27 +5,169 @@
 pub mod samples;
 pub mod types;
 
-use frost_bluepallas::BluePallas;
+use std::collections::BTreeMap;
+
+use frost_bluepallas::keys::dkg::{self, round1, round2};
 use mina_signer::Signer;
 use mina_tx::pallas_message::{translate_pk, translate_sig, PallasMessage};
 use mina_tx::NetworkId;
+use rand_core::{CryptoRng, RngCore};
 
-type Suite = BluePallas<PallasMessage>;
+use types::{
+    Identifier, KeyPackage, PublicKeyPackage, Signature, SignatureShare, SigningCommitments,
+    SigningNonces, SigningPackage, Suite,
+};
 
-// #[cfg(test)]
+/// Check a FROST signature with `mina_signer`, whose legacy context uses the domain string
+/// of `network`. `msg` must have been signed for the same network.
 pub fn verify_signature(
     msg: &[u8],
     group_signature: frost_core::Signature<Suite>,
     group_pubkey: frost_core::VerifyingKey<Suite>,
+    network: NetworkId,
 ) {
+    assert!(verifies_on(msg, group_signature, group_pubkey, network));
+}
+
+/// Whether `mina_signer` accepts the signature under the domain string of `network`.
+pub fn verifies_on(
+    msg: &[u8],
+    group_signature: frost_core::Signature<Suite>,
+    group_pubkey: frost_core::VerifyingKey<Suite>,
+    network: NetworkId,
+) -> bool {
     // TODO remove the result type from the translate api. It's always okay
     let sig = translate_sig(&group_signature).unwrap();
 
     let pub_key = translate_pk(&group_pubkey).unwrap();
     // Check that signature validation has the expected result.
 
-    let mut ctx = mina_signer::create_legacy::<PallasMessage>(NetworkId::Testnet);
+    let mut ctx = mina_signer::create_legacy::<PallasMessage>(network);
     let pallas_message = PallasMessage::deserialize(msg)
         .unwrap_or_else(|_| PallasMessage::from_raw_bytes_default(msg));
-    assert!(ctx.verify(&sig, &pub_key, &pallas_message));
+    ctx.verify(&sig, &pub_key, &pallas_message)
+}
+
+/// How [`run_dkg_with`] carries each DKG package from its sender to a recipient.
+pub trait DkgTransport {
+    fn round1(&self, package: &round1::Package<PallasMessage>) -> round1::Package<PallasMessage> {
+        package.clone()
+    }
+
+    fn round2(&self, package: &round2::Package<PallasMessage>) -> round2::Package<PallasMessage> {
+        package.clone()
+    }
+}
+
+/// Hands the DKG packages over in memory.
+pub struct InMemory;
+
+impl DkgTransport for InMemory {}
+
+/// Run a 2-of-3 DKG between in-process participants.
+pub fn run_dkg<R: RngCore + CryptoRng>(
+    rng: &mut R,
+) -> (BTreeMap<Identifier, KeyPackage>, PublicKeyPackage) {
+    run_dkg_with(rng, &InMemory)
+}
+
+/// [`run_dkg`], passing every package through `transport`. Checks that all participants
+/// agree on the group.
+pub fn run_dkg_with<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    transport: &impl DkgTransport,
+) -> (BTreeMap<Identifier, KeyPackage>, PublicKeyPackage) {
+    let ids: Vec<Identifier> = (1..=3u16).map(|i| i.try_into().unwrap()).collect();
+
+    let mut round1_secrets = BTreeMap::new();
+    let mut round1_packages = BTreeMap::new();
+    for id in &ids {
+        let (secret, package) = dkg::part1(*id, 3, 2, &mut *rng).unwrap();
+        round1_secrets.insert(*id, secret);
+        round1_packages.insert(*id, package);
+    }
+    let received_round1 = |id: &Identifier| -> BTreeMap<_, _> {
+        round1_packages
+            .iter()
+            .filter(|(sender, _)| *sender != id)
+            .map(|(sender, package)| (*sender, transport.round1(package)))
+            .collect()
+    };
+
+    let mut round2_secrets = BTreeMap::new();
+    let mut round2_packages: BTreeMap<Identifier, BTreeMap<Identifier, _>> = BTreeMap::new();
+    for (id, secret) in round1_secrets {
+        let (secret, packages) = dkg::part2(secret, &received_round1(&id)).unwrap();
+        round2_secrets.insert(id, secret);
+        for (recipient, package) in packages {
+            round2_packages
+                .entry(recipient)
+                .or_default()
+                .insert(id, transport.round2(&package));
+        }
+    }
+
+    let mut key_packages = BTreeMap::new();
+    let mut pubkey_packages = Vec::new();
+    for id in &ids {
+        let (key_package, pubkey_package) = dkg::part3(
+            &round2_secrets[id],
+            &received_round1(id),
+            &round2_packages[id],
+        )
+        .unwrap();
+        key_packages.insert(*id, key_package);
+        pubkey_packages.push(pubkey_package);
+    }
+    assert!(pubkey_packages.windows(2).all(|w| w[0] == w[1]));
+    (key_packages, pubkey_packages.swap_remove(0))
+}
+
+/// Round 1 for each of `signers`: their nonces and commitments, by identifier.
+pub fn commit<'a, R: RngCore + CryptoRng>(
+    signers: impl IntoIterator<Item = &'a KeyPackage>,
+    rng: &mut R,
+) -> (
+    BTreeMap<Identifier, SigningNonces>,
+    BTreeMap<Identifier, SigningCommitments>,
+) {
+    let mut nonces = BTreeMap::new();
+    let mut commitments = BTreeMap::new();
+    for key_package in signers {
+        let (n, c) = frost_bluepallas::round1::commit(key_package.signing_share(), rng);
+        nonces.insert(*key_package.identifier(), n);
+        commitments.insert(*key_package.identifier(), c);
+    }
+    (nonces, commitments)
+}
+
+/// Round 2 for each of `signers` over `signing_package`, using their `nonces` from round 1.
+pub fn signature_shares<'a>(
+    signers: impl IntoIterator<Item = &'a KeyPackage>,
+    signing_package: &SigningPackage,
+    nonces: &BTreeMap<Identifier, SigningNonces>,
+) -> BTreeMap<Identifier, SignatureShare> {
+    signers
+        .into_iter()
+        .map(|key_package| {
+            let id = key_package.identifier();
+            let share =
+                frost_bluepallas::round2::sign(signing_package, &nonces[id], key_package).unwrap();
+            (*id, share)
+        })
+        .collect()
+}
+
+/// Sign `message` with `signers` and aggregate their shares under `pubkey_package`.
+pub fn sign<'a, R: RngCore + CryptoRng>(
+    signers: impl IntoIterator<Item = &'a KeyPackage>,
+    pubkey_package: &PublicKeyPackage,
+    message: &[u8],
+    rng: &mut R,
+) -> Signature {
+    let signers: Vec<&KeyPackage> = signers.into_iter().collect();
+    let (nonces, commitments) = commit(signers.iter().copied(), rng);
+    let signing_package = SigningPackage::new(commitments, message);
+    let shares = signature_shares(signers, &signing_package, &nonces);
+    frost_bluepallas::aggregate(&signing_package, &shares, pubkey_package).unwrap()
 }
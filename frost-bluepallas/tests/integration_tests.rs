@@ -239,6 +239,60 @@ fn check_sign_with_test_vectors() {
     frost_core::tests::vectors::check_sign_with_test_vectors::<Suite>(&VECTORS);
 }
 
+/// Pins H1, H3, H4 and H5 to the values recorded in the signing test vectors, so a change to
+/// the hash or its scalar conversion shows up here rather than only as a failed signature.
+#[test]
+fn check_hash_functions_match_test_vectors() {
+    let hex_bytes = |value: &Value| hex::decode(value.as_str().unwrap()).unwrap();
+    let scalar_hex = |scalar| hex::encode(PallasScalarField::serialize(&scalar));
+
+    let message = hex_bytes(&VECTORS["inputs"]["message"]);
+    let shares = VECTORS["inputs"]["participant_shares"].as_array().unwrap();
+    let outputs = VECTORS["round_one_outputs"]["outputs"].as_array().unwrap();
+
+    let mut encoded_commitments = Vec::new();
+    for output in outputs {
+        let identifier = output["identifier"].as_u64().unwrap() as u16;
+        encoded_commitments.extend(
+            Identifier::<PallasMessage>::try_from(identifier)
+                .unwrap()
+                .serialize(),
+        );
+        encoded_commitments.extend(hex_bytes(&output["hiding_nonce_commitment"]));
+        encoded_commitments.extend(hex_bytes(&output["binding_nonce_commitment"]));
+    }
+
+    for output in outputs {
+        // Binding factor input is verifying key (33) || H4(message) || H5(commitments) || identifier
+        let binding_factor_input = hex_bytes(&output["binding_factor_input"]);
+        assert_eq!(binding_factor_input[33..65], Suite::H4(&message));
+        assert_eq!(
+            binding_factor_input[65..97],
+            Suite::H5(&encoded_commitments)
+        );
+        assert_eq!(
+            scalar_hex(Suite::H1(&binding_factor_input)),
+            output["binding_factor"].as_str().unwrap()
+        );
+
+        // Nonces are H3(randomness || signing share)
+        let identifier = output["identifier"].as_u64().unwrap();
+        let share = shares
+            .iter()
+            .find(|share| share["identifier"].as_u64() == Some(identifier))
+            .unwrap();
+        let signing_share = hex_bytes(&share["participant_share"]);
+        for nonce in ["hiding", "binding"] {
+            let mut input = hex_bytes(&output[format!("{nonce}_nonce_randomness")]);
+            input.extend(&signing_share);
+            assert_eq!(
+                scalar_hex(Suite::H3(&input)),
+                output[format!("{nonce}_nonce")].as_str().unwrap()
+            );
+        }
+    }
+}
+
 #[test]
 fn check_sign_with_test_vectors_with_big_identifiers() {
     frost_core::tests::vectors::check_sign_with_test_vectors::<Suite>(&VECTORS_BIG_IDENTIFIER);
@@ -265,6 +319,65 @@ fn check_identifier_generation() -> Result<(), Error<PallasMessage>> {
     Ok(())
 }
 
+#[test]
+fn checked_identifier_constructors_reject_zero() {
+    use frost_bluepallas::keys::IdentifierExt;
+    type Id = Identifier<PallasMessage>;
+    type Scalar = <PallasScalarField as Field>::Scalar;
+
+    let zero: Result<Id, _> = Err(Error::FieldError(FieldError::InvalidZeroScalar));
+    assert_eq!(Id::try_from_scalar(Scalar::from(0u64)), zero);
+    assert_eq!(Id::try_from_bytes(&[0u8; 32]), zero);
+
+    let seven = Id::try_from(7u16).unwrap();
+    assert_eq!(Id::try_from_scalar(Scalar::from(7u64)), Ok(seven));
+    assert_eq!(Id::try_from_bytes(&seven.serialize()), Ok(seven));
+    assert_eq!(
+        Id::try_from_bytes(&seven.serialize()[..31]),
+        Err(Error::DeserializationError)
+    );
+
+    assert_eq!(
+        Id::try_derive(b"alice@example.com"),
+        Id::derive(b"alice@example.com")
+    );
+
+    assert_eq!(seven.as_u16(), Some(7));
+    assert_eq!(Id::try_from(u16::MAX).unwrap().as_u16(), Some(u16::MAX));
+    assert_eq!(Id::derive(b"alice@example.com").unwrap().as_u16(), None);
+    let past_u16 = Id::try_from_scalar(Scalar::from(u16::MAX as u64 + 1)).unwrap();
+    assert_eq!(past_u16.as_u16(), None);
+}
+
+#[test]
+fn signing_share_raw_bytes_are_validated() {
+    use frost_bluepallas::keys::{SigningShare, SigningShareExt};
+    type Share = SigningShare<PallasMessage>;
+
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (shares, _) = frost_bluepallas::keys::generate_with_dealer::<PallasMessage, _>(
+        3,
+        2,
+        frost_bluepallas::keys::IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+    let share = *shares.into_values().next().unwrap().signing_share();
+    let bytes = share.to_bytes();
+    assert_eq!(Share::from_bytes(&bytes), Ok(share));
+    assert_eq!(bytes.as_slice(), share.serialize().as_slice());
+
+    // The Pallas scalar field modulus is below 2^255, so this is out of range
+    assert_eq!(
+        Share::from_bytes(&[0xff; 32]),
+        Err(Error::FieldError(FieldError::MalformedScalar))
+    );
+    assert_eq!(
+        Share::from_bytes(&[0u8; 32]),
+        Err(Error::FieldError(FieldError::InvalidZeroScalar))
+    );
+}
+
 #[test]
 fn check_sign_with_dealer_and_identifiers() {
     let rng = rand_chacha::ChaChaRng::seed_from_u64(0);
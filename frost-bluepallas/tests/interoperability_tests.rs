@@ -1,3 +1,13 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use frost_bluepallas::Ciphersuite;
+use helpers::types::{KeyPackage, Suite};
+use mina_hasher::ROInput;
+use mina_tx::{
+    pallas_message::{translate_pk, PallasMessage},
+    NetworkId,
+};
+use rand_chacha::ChaChaRng;
 use rand_core::SeedableRng;
 
 mod helpers;
@@ -12,6 +22,167 @@ fn check_interoperability_in_sign_with_dealer() {
         let (sig, pk) =
             frost_bluepallas::signing_utilities::generate_signature_random(msg, rng).unwrap();
 
-        helpers::verify_signature(msg, sig, pk);
+        helpers::verify_signature(msg, sig, pk, NetworkId::Testnet);
+    }
+}
+
+#[test]
+fn check_interoperability_on_mainnet() {
+    for i in 0..32 {
+        let rng = rand_chacha::ChaChaRng::seed_from_u64(i);
+        let msg = PallasMessage::from_parts(
+            ROInput::new().append_bytes(b"Hello from the Raspberry Devs"),
+            NetworkId::Mainnet,
+            true,
+        )
+        .serialize()
+        .unwrap();
+        let (sig, pk) =
+            frost_bluepallas::signing_utilities::generate_signature_random(&msg, rng).unwrap();
+
+        helpers::verify_signature(&msg, sig, pk, NetworkId::Mainnet);
+        // The domain string is part of the challenge, so testnet must reject it
+        assert!(!helpers::verifies_on(&msg, sig, pk, NetworkId::Testnet));
+    }
+}
+
+#[test]
+fn check_interoperability_in_sign_with_dkg() {
+    // DKG does not normalize the group key to even Y, and does not need to: Mina
+    // verification hashes the full public key, so either parity must verify.
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let msg = "Hello from the Raspberry Devs".as_bytes();
+    let (mut seen_odd, mut seen_even) = (false, false);
+    for _ in 0..32 {
+        let (key_packages, pubkey_package) = helpers::run_dkg(&mut rng);
+        let sig = helpers::sign(
+            key_packages.values().take(2),
+            &pubkey_package,
+            msg,
+            &mut rng,
+        );
+
+        let pk = *pubkey_package.verifying_key();
+        helpers::verify_signature(msg, sig, pk, NetworkId::Testnet);
+
+        let is_odd = translate_pk(&pk).unwrap().into_compressed().is_odd;
+        seen_odd |= is_odd;
+        seen_even |= !is_odd;
     }
+    assert!(seen_odd && seen_even);
+}
+
+#[test]
+fn check_odd_y_key_package_signs_mina_valid_signatures() {
+    let msg = "Hello from the Raspberry Devs".as_bytes();
+    let (shares, pubkey_package) = (0..)
+        .map(|seed| {
+            frost_bluepallas::keys::generate_with_dealer::<PallasMessage, _>(
+                3,
+                2,
+                frost_bluepallas::keys::IdentifierList::Default,
+                &mut ChaChaRng::seed_from_u64(seed),
+            )
+            .unwrap()
+        })
+        .find(|(_, pubkey_package)| {
+            translate_pk(pubkey_package.verifying_key())
+                .unwrap()
+                .into_compressed()
+                .is_odd
+        })
+        .unwrap();
+
+    // Assemble the key packages by hand rather than through `KeyPackage::try_from`
+    let key_packages: BTreeMap<_, _> = shares
+        .into_iter()
+        .take(2)
+        .map(|(id, share)| {
+            let key_package = KeyPackage::new(
+                id,
+                *share.signing_share(),
+                pubkey_package.verifying_shares()[&id],
+                *pubkey_package.verifying_key(),
+                2,
+            );
+            (id, key_package)
+        })
+        .collect();
+
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let sig = helpers::sign(key_packages.values(), &pubkey_package, msg, &mut rng);
+
+    helpers::verify_signature(
+        msg,
+        sig,
+        *pubkey_package.verifying_key(),
+        NetworkId::Testnet,
+    );
+}
+
+#[test]
+fn check_negated_nonces_sign_mina_valid_signatures() {
+    let msg = "Hello from the Raspberry Devs".as_bytes();
+    let mut rng = ChaChaRng::seed_from_u64(0);
+    let (shares, pubkey_package) =
+        frost_bluepallas::keys::generate_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            frost_bluepallas::keys::IdentifierList::Default,
+            &mut rng,
+        )
+        .unwrap();
+    let key_packages: BTreeMap<_, _> = shares
+        .into_iter()
+        .take(2)
+        .map(|(id, share)| (id, KeyPackage::try_from(share).unwrap()))
+        .collect();
+
+    // Commit until the group commitment has odd Y, so that signing goes through the
+    // negated nonces rather than the borrowed ones
+    let (nonces, signing_package) = (0..)
+        .map(|_| {
+            let (nonces, commitments) = helpers::commit(key_packages.values(), &mut rng);
+            (
+                nonces,
+                frost_bluepallas::SigningPackage::new(commitments, msg),
+            )
+        })
+        .find(|(nonces, signing_package)| {
+            let binding_factors = frost_core::compute_binding_factor_list(
+                signing_package,
+                pubkey_package.verifying_key(),
+                &[],
+            )
+            .unwrap();
+            nonces.values().all(|signer_nonces| {
+                let (package, signer_nonces) =
+                    Suite::pre_commitment_sign(signing_package, signer_nonces, &binding_factors)
+                        .unwrap();
+                matches!((package, signer_nonces), (Cow::Owned(_), Cow::Owned(_)))
+            })
+        })
+        .unwrap();
+
+    let shares = key_packages
+        .iter()
+        .map(|(id, key_package)| {
+            let share =
+                frost_bluepallas::round2::sign(&signing_package, &nonces[id], key_package).unwrap();
+            frost_core::verify_signature_share(
+                *id,
+                key_package.verifying_share(),
+                &share,
+                &signing_package,
+                pubkey_package.verifying_key(),
+            )
+            .unwrap();
+            (*id, share)
+        })
+        .collect();
+    let sig = frost_bluepallas::aggregate(&signing_package, &shares, &pubkey_package).unwrap();
+
+    let pk = *pubkey_package.verifying_key();
+    pk.verify(msg, &sig).unwrap();
+    helpers::verify_signature(msg, sig, pk, NetworkId::Testnet);
 }
@@ -0,0 +1,344 @@
+mod helpers;
+
+use std::collections::BTreeMap;
+
+use frost_bluepallas::{
+    errors::BluePallasError,
+    keys::{
+        generate_key_packages_with_dealer, generate_with_dealer, lagrange_coefficient,
+        minimal_quorums, reconstruct_verifying_key, recover_signing_key, relabel_shares, split,
+        IdentifierList, PublicKeyPackage, PublicKeyPackageExt, SigningShare,
+    },
+    Error, Field, PallasScalarField, SigningKey, VerifyingKey,
+};
+use frost_core::keys::VerifyingShare;
+use helpers::types::{Identifier, KeyPackage, Suite};
+use mina_hasher::ROInput;
+use mina_tx::{pallas_message::PallasMessage, NetworkId};
+use rand_core::SeedableRng;
+
+#[test]
+fn reconstruct_verifying_key_matches_public_key_package() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(5, 3, IdentifierList::Default, &mut rng).unwrap();
+    let shares = pubkey_package.verifying_shares().clone();
+
+    // All shares and any threshold-sized quorum interpolate to the group key
+    let all = reconstruct_verifying_key(&shares, 3).unwrap();
+    assert_eq!(all, *pubkey_package.verifying_key());
+
+    let quorum: BTreeMap<Identifier, VerifyingShare<Suite>> =
+        shares.iter().skip(2).map(|(id, s)| (*id, *s)).collect();
+    let from_quorum = reconstruct_verifying_key(&quorum, 3).unwrap();
+    assert_eq!(from_quorum, *pubkey_package.verifying_key());
+}
+
+#[test]
+fn reconstruct_verifying_key_detects_tampered_share() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let mut shares = pubkey_package.verifying_shares().clone();
+
+    // Replace one share with another participant's share
+    let mut ids = shares.keys().copied();
+    let (first, second) = (ids.next().unwrap(), ids.next().unwrap());
+    let other = shares[&second];
+    shares.insert(first, other);
+
+    let reconstructed = reconstruct_verifying_key(&shares, 2).unwrap();
+    assert_ne!(reconstructed, *pubkey_package.verifying_key());
+}
+
+#[test]
+fn reconstruct_verifying_key_requires_threshold_shares() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(3, 3, IdentifierList::Default, &mut rng).unwrap();
+    let shares: BTreeMap<Identifier, VerifyingShare<Suite>> = pubkey_package
+        .verifying_shares()
+        .iter()
+        .take(2)
+        .map(|(id, s)| (*id, *s))
+        .collect();
+
+    assert_eq!(
+        reconstruct_verifying_key(&shares, 3),
+        Err(frost_core::Error::IncorrectNumberOfShares)
+    );
+}
+
+#[test]
+fn recover_signing_key_from_any_quorum() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (shares, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(4, 3, IdentifierList::Default, &mut rng).unwrap();
+    let signing_shares: Vec<(Identifier, SigningShare<PallasMessage>)> = shares
+        .iter()
+        .map(|(id, share)| (*id, *share.signing_share()))
+        .collect();
+
+    // Every 3-of-4 quorum, obtained by leaving one participant out, recovers the same key
+    let mut recovered = Vec::new();
+    for skipped in 0..signing_shares.len() {
+        let quorum: BTreeMap<Identifier, SigningShare<PallasMessage>> = signing_shares
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != skipped)
+            .map(|(_, (id, share))| (*id, *share))
+            .collect();
+        recovered.push(recover_signing_key(&quorum, 3).unwrap());
+    }
+    for key in &recovered {
+        assert_eq!(key.serialize(), recovered[0].serialize());
+    }
+
+    let signing_key = &recovered[0];
+    assert_eq!(
+        VerifyingKey::<PallasMessage>::from(signing_key),
+        *pubkey_package.verifying_key()
+    );
+
+    let message = PallasMessage::from_parts(
+        ROInput::new().append_bytes(b"recovered key"),
+        NetworkId::Testnet,
+        true,
+    )
+    .serialize()
+    .unwrap();
+    let signature = signing_key.sign(&mut rng, &message);
+    pubkey_package
+        .verifying_key()
+        .verify(&message, &signature)
+        .unwrap();
+}
+
+#[test]
+fn recover_signing_key_requires_threshold_shares() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (shares, _) =
+        generate_with_dealer::<PallasMessage, _>(3, 3, IdentifierList::Default, &mut rng).unwrap();
+    let signing_shares: BTreeMap<Identifier, SigningShare<PallasMessage>> = shares
+        .iter()
+        .take(2)
+        .map(|(id, share)| (*id, *share.signing_share()))
+        .collect();
+
+    assert!(matches!(
+        recover_signing_key(&signing_shares, 3),
+        Err(frost_core::Error::IncorrectNumberOfShares)
+    ));
+}
+
+#[test]
+fn public_key_package_diff_names_tampered_share() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    assert!(pubkey_package.diff(&pubkey_package.clone()).is_empty());
+
+    // Replace one share with another participant's share
+    let mut shares = pubkey_package.verifying_shares().clone();
+    let mut ids = shares.keys().copied();
+    let (first, second) = (ids.next().unwrap(), ids.next().unwrap());
+    let other = shares[&second];
+    shares.insert(first, other);
+    let tampered = PublicKeyPackage::new(shares, *pubkey_package.verifying_key(), None);
+
+    assert_eq!(pubkey_package.diff(&tampered), vec![first]);
+    assert_eq!(tampered.diff(&pubkey_package), vec![first]);
+}
+
+#[test]
+fn public_key_package_diff_reports_all_on_group_key_change() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let (_, other_group) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let tampered = PublicKeyPackage::new(
+        pubkey_package.verifying_shares().clone(),
+        *other_group.verifying_key(),
+        None,
+    );
+
+    let expected: Vec<Identifier> = pubkey_package.verifying_shares().keys().copied().collect();
+    assert_eq!(pubkey_package.diff(&tampered), expected);
+}
+
+#[test]
+fn relabeled_shares_sign_for_the_original_group_key() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (old, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+        3,
+        2,
+        IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+    let mapping: BTreeMap<Identifier, Identifier> = old
+        .keys()
+        .zip(["alice", "bob", "carol"])
+        .map(|(id, name)| (*id, Identifier::derive(name.as_bytes()).unwrap()))
+        .collect();
+
+    let relabeled = relabel_shares(&old, &mapping).unwrap();
+    let key_packages: BTreeMap<Identifier, KeyPackage> = relabeled
+        .into_iter()
+        .map(|(id, share)| (id, KeyPackage::try_from(share).unwrap()))
+        .collect();
+    let new_pubkey_package = PublicKeyPackage::new(
+        key_packages
+            .iter()
+            .map(|(id, kp)| (*id, *kp.verifying_share()))
+            .collect(),
+        *pubkey_package.verifying_key(),
+        None,
+    );
+    for key_package in key_packages.values() {
+        assert_eq!(key_package.verifying_key(), pubkey_package.verifying_key());
+    }
+
+    let message = PallasMessage::from_parts(
+        ROInput::new().append_bytes(b"relabeled shares"),
+        NetworkId::Testnet,
+        true,
+    )
+    .serialize()
+    .unwrap();
+    let signature = helpers::sign(
+        key_packages.values().skip(1),
+        &new_pubkey_package,
+        &message,
+        &mut rng,
+    );
+
+    pubkey_package
+        .verifying_key()
+        .verify(&message, &signature)
+        .unwrap();
+}
+
+#[test]
+fn relabel_shares_rejects_duplicate_targets() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (old, _) = generate_key_packages_with_dealer::<PallasMessage, _>(
+        3,
+        2,
+        IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+    let target = Identifier::try_from(9).unwrap();
+    let mapping: BTreeMap<Identifier, Identifier> = old.keys().map(|id| (*id, target)).collect();
+
+    assert!(matches!(
+        relabel_shares(&old, &mapping),
+        Err(frost_core::Error::DuplicatedIdentifier)
+    ));
+}
+
+#[test]
+fn lagrange_coefficients_reconstruct_known_key() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let key = SigningKey::<PallasMessage>::new(&mut rng);
+    let (shares, _) = split(&key, 5, 3, IdentifierList::Default, &mut rng).unwrap();
+
+    let quorum: Vec<Identifier> = shares.keys().copied().skip(1).step_by(2).collect();
+    let quorum = [quorum, shares.keys().copied().take(1).collect()].concat();
+    assert_eq!(quorum.len(), 3);
+
+    let mut secret = <PallasScalarField as Field>::zero();
+    for id in &quorum {
+        let lambda = lagrange_coefficient(id, &quorum).unwrap();
+        secret = secret + lambda * shares[id].signing_share().to_scalar();
+    }
+    assert_eq!(
+        SigningKey::<PallasMessage>::from_scalar(secret)
+            .unwrap()
+            .serialize(),
+        key.serialize()
+    );
+
+    let outsider = *shares.keys().nth(2).unwrap();
+    assert_eq!(
+        lagrange_coefficient(&outsider, &quorum),
+        Err(Error::UnknownIdentifier)
+    );
+    let duplicated = [quorum[0], quorum[0], quorum[1]];
+    assert_eq!(
+        lagrange_coefficient(&quorum[0], &duplicated),
+        Err(Error::DuplicatedIdentifier)
+    );
+}
+
+#[test]
+fn minimal_quorums_enumerates_every_subset() {
+    let ids: Vec<Identifier> = (1..=4u16).map(|i| i.try_into().unwrap()).collect();
+    let quorums = minimal_quorums(&ids, 2).unwrap();
+    let expected: Vec<Vec<Identifier>> = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)]
+        .iter()
+        .map(|&(a, b)| vec![ids[a], ids[b]])
+        .collect();
+    assert_eq!(quorums, expected);
+
+    assert_eq!(minimal_quorums(&ids, 4).unwrap(), vec![ids.clone()]);
+    assert!(matches!(
+        minimal_quorums(&ids, 5),
+        Err(BluePallasError::InvalidQuorum(_))
+    ));
+
+    let many: Vec<Identifier> = (1..=40u16).map(|i| i.try_into().unwrap()).collect();
+    assert!(matches!(
+        minimal_quorums(&many, 20),
+        Err(BluePallasError::TooManyQuorums { .. })
+    ));
+}
+
+#[test]
+fn dealer_key_packages_sign_with_even_group_commitment() {
+    use ark_ec::CurveGroup;
+    use ark_ff::{BigInteger, PrimeField};
+
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+        3,
+        2,
+        IdentifierList::Default,
+        &mut rng,
+    )
+    .unwrap();
+    assert_eq!(key_packages.len(), 3);
+    for (id, key_package) in &key_packages {
+        assert_eq!(key_package.identifier(), id);
+        assert_eq!(key_package.verifying_key(), pubkey_package.verifying_key());
+        assert_eq!(*key_package.min_signers(), 2);
+    }
+
+    let message = PallasMessage::from_parts(
+        ROInput::new().append_bytes(b"dealer key packages"),
+        NetworkId::Testnet,
+        true,
+    )
+    .serialize()
+    .unwrap();
+    let signature = helpers::sign(
+        key_packages.values().take(2),
+        &pubkey_package,
+        &message,
+        &mut rng,
+    );
+
+    assert!(signature.R().into_affine().y.into_bigint().is_even());
+    pubkey_package
+        .verifying_key()
+        .verify(&message, &signature)
+        .unwrap();
+    helpers::verify_signature(
+        &message,
+        signature,
+        *pubkey_package.verifying_key(),
+        NetworkId::Testnet,
+    );
+}
@@ -3,13 +3,20 @@
 mod helpers;
 
 use frost_bluepallas::{
-    keys::dkg::{round1, round2},
+    errors::BluePallasError,
+    keys::{
+        dkg::{round1, round2},
+        export_key_package, export_secret_share, generate_with_dealer, import_key_package,
+        import_secret_share, IdentifierList, KeyPackageExt, PublicKeyPackageExt, PublicShare,
+        KEY_FILE_VERSION,
+    },
     CONTEXT_STRING,
 };
 use helpers::types::{
     KeyPackage, PublicKeyPackage, SecretShare, SignatureShare, SigningCommitments, SigningPackage,
 };
-use mina_tx::pallas_message::PallasMessage;
+use mina_tx::pallas_message::{translate_pk, PallasMessage};
+use rand_core::SeedableRng;
 
 //TODO make the invalid jsons as similar to the valid one as possible (excpet for its difference
 //from the valid one)
@@ -343,6 +350,37 @@ fn check_secret_share_serialization() {
     assert!(serde_json::from_str::<SecretShare>(invalid_json).is_err());
 }
 
+#[test]
+fn check_import_secret_share_verifies_commitment() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (shares, _) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let (other_shares, _) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let (identifier, share) = shares.iter().next().unwrap();
+    let json = serde_json::to_string(share).unwrap();
+    let key_package = import_secret_share::<PallasMessage>(&json).unwrap();
+    assert_eq!(key_package.identifier(), identifier);
+    assert_eq!(key_package.signing_share(), share.signing_share());
+
+    // Pair the signing share with the commitment of an unrelated dealer run.
+    let corrupted = SecretShare::new(
+        *identifier,
+        *share.signing_share(),
+        other_shares[identifier].commitment().clone(),
+    );
+    let json = serde_json::to_string(&corrupted).unwrap();
+    assert!(matches!(
+        import_secret_share::<PallasMessage>(&json),
+        Err(BluePallasError::InvalidSecretShare(_))
+    ));
+
+    assert!(matches!(
+        import_secret_share::<PallasMessage>("{}"),
+        Err(BluePallasError::DeSerializationError(_))
+    ));
+}
+
 #[test]
 fn check_key_package_serialization() {
     let key_package = samples::key_package();
@@ -438,6 +476,25 @@ fn check_key_package_serialization() {
     assert!(serde_json::from_str::<KeyPackage>(invalid_json).is_err());
 }
 
+#[test]
+fn check_public_share_serialization_excludes_signing_share() {
+    let key_package = samples::key_package();
+    let public_share = key_package.to_public();
+    assert_eq!(key_package.public_share(), *key_package.verifying_share());
+
+    let json = public_share.to_json().unwrap();
+    println!("{}", json);
+
+    let verifying_share_hex = hex::encode(key_package.verifying_share().serialize().unwrap());
+    let signing_share_hex = hex::encode(key_package.signing_share().serialize());
+    assert!(json.contains(&verifying_share_hex));
+    assert!(!json.contains(&signing_share_hex));
+    assert!(!json.contains("signing_share"));
+
+    let decoded: PublicShare<PallasMessage> = serde_json::from_str(&json).unwrap();
+    assert_eq!(public_share, decoded);
+}
+
 #[test]
 fn check_public_key_package_serialization() {
     let public_key_package = samples::public_key_package();
@@ -640,3 +697,117 @@ fn check_round2_package_serialization() {
       }"#;
     assert!(serde_json::from_str::<round2::Package<PallasMessage>>(invalid_json).is_err());
 }
+
+#[test]
+fn check_import_secret_share_migrates_v1_file() {
+    // Written by frost-core before the version field was added; share of identifier 1 in
+    // the test vectors.
+    let v1 = include_str!("helpers/secret-share-v1.json");
+    let key_package = import_secret_share::<PallasMessage>(v1).unwrap();
+    assert_eq!(*key_package.identifier(), 1u16.try_into().unwrap());
+    assert_eq!(*key_package.min_signers(), 3);
+
+    // Re-exporting writes the current version, which imports to the same key package
+    let share: SecretShare = serde_json::from_str(v1).unwrap();
+    let v2 = export_secret_share(&share).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&v2).unwrap();
+    assert_eq!(value["version"], KEY_FILE_VERSION);
+    assert_eq!(
+        import_secret_share::<PallasMessage>(&v2).unwrap(),
+        key_package
+    );
+}
+
+#[test]
+fn check_key_file_rejects_future_version() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (shares, _) =
+        generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng).unwrap();
+    let share = shares.values().next().unwrap();
+    let key_package = KeyPackage::try_from(share.clone()).unwrap();
+
+    let json = export_key_package(&key_package).unwrap();
+    assert_eq!(
+        import_key_package::<PallasMessage>(&json).unwrap(),
+        key_package
+    );
+
+    for json in [
+        export_secret_share(share).unwrap(),
+        export_key_package(&key_package).unwrap(),
+    ] {
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["version"] = (KEY_FILE_VERSION + 1).into();
+        let future = value.to_string();
+        assert_eq!(
+            import_secret_share::<PallasMessage>(&future).unwrap_err(),
+            BluePallasError::UnsupportedVersion(KEY_FILE_VERSION + 1)
+        );
+        assert_eq!(
+            import_key_package::<PallasMessage>(&future).unwrap_err(),
+            BluePallasError::UnsupportedVersion(KEY_FILE_VERSION + 1)
+        );
+    }
+}
+
+#[test]
+fn check_public_key_package_summary() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (_, pubkey_package) =
+        generate_with_dealer::<PallasMessage, _>(5, 3, IdentifierList::Default, &mut rng).unwrap();
+
+    let summary = pubkey_package.summary();
+    let address = translate_pk(pubkey_package.verifying_key())
+        .unwrap()
+        .into_address();
+    assert_eq!(summary.address, address);
+    assert_eq!(summary.min_signers, Some(3));
+    assert_eq!(summary.participants, 5);
+    assert_eq!(summary.verifying_shares.len(), 5);
+    assert!(summary
+        .verifying_shares
+        .values()
+        .all(|share| share.starts_with("B62") && *share != address));
+
+    let json: serde_json::Value = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["address"], address.as_str());
+    assert_eq!(json["participants"], 5);
+    assert_eq!(json["verifying_shares"].as_object().unwrap().len(), 5);
+}
+
+#[test]
+fn check_dkg_over_json_transport_signs() {
+    // Every package crosses a (simulated) custom transport as JSON
+    fn json<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+    struct Json;
+    impl helpers::DkgTransport for Json {
+        fn round1(
+            &self,
+            package: &round1::Package<PallasMessage>,
+        ) -> round1::Package<PallasMessage> {
+            json(package)
+        }
+
+        fn round2(
+            &self,
+            package: &round2::Package<PallasMessage>,
+        ) -> round2::Package<PallasMessage> {
+            json(package)
+        }
+    }
+
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, pubkey_package) = helpers::run_dkg_with(&mut rng, &Json);
+    let pubkey_package: PublicKeyPackage = json(&pubkey_package);
+
+    let msg = b"dkg over a custom transport";
+    let sig = helpers::sign(
+        key_packages.values().skip(1),
+        &pubkey_package,
+        msg,
+        &mut rng,
+    );
+    pubkey_package.verifying_key().verify(msg, &sig).unwrap();
+}
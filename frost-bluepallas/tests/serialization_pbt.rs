@@ -1,5 +1,6 @@
-use frost_bluepallas::{PallasGroup, PallasScalarField};
+use frost_bluepallas::{Identifier, PallasGroup, PallasScalarField};
 use frost_core::{Field, Group};
+use mina_tx::pallas_message::PallasMessage;
 use num_traits::identities::Zero;
 use proptest::prelude::*;
 
@@ -48,3 +49,28 @@ proptest! {
         prop_assert_eq!(decoded, element);
     }
 }
+
+#[test]
+fn scalar_serialization_is_little_endian() {
+    let scalar = <PallasScalarField as Field>::Scalar::from(0x0102u64);
+
+    let mut expected = [0u8; 32];
+    expected[..2].copy_from_slice(&[0x02, 0x01]);
+    assert_eq!(PallasScalarField::serialize(&scalar), expected);
+    assert_eq!(
+        PallasScalarField::little_endian_serialize(&scalar),
+        expected
+    );
+    assert_eq!(PallasScalarField::deserialize(&expected).unwrap(), scalar);
+}
+
+#[test]
+fn identifier_order_follows_numeric_order() {
+    // frost-core sorts identifiers by their little-endian encoding, which determines the
+    // order of the binding factor list
+    let identifiers: Vec<Identifier<PallasMessage>> = [1u16, 2, 255, 256, 257, 65535]
+        .into_iter()
+        .map(|i| Identifier::try_from(i).unwrap())
+        .collect();
+    assert!(identifiers.windows(2).all(|pair| pair[0] < pair[1]));
+}
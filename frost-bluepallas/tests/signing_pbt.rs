@@ -0,0 +1,69 @@
+mod helpers;
+
+use frost_bluepallas::keys::{generate_key_packages_with_dealer, IdentifierList};
+use mina_hasher::ROInput;
+use mina_tx::{pallas_message::PallasMessage, NetworkId};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, RngSeed},
+};
+use rand_core::SeedableRng;
+
+/// A threshold `(min_signers, max_signers)` with `2 <= min_signers <= max_signers <= 7`.
+fn arb_threshold() -> impl Strategy<Value = (u16, u16)> {
+    (2u16..=7).prop_flat_map(|max| (2u16..=max, Just(max)))
+}
+
+proptest! {
+    // Fixed seed so that a failure reproduces on every run and machine.
+    #![proptest_config(Config {
+        cases: 32,
+        rng_seed: RngSeed::Fixed(0x6d69_6e61),
+        ..Config::default()
+    })]
+
+    #[test]
+    fn sign_aggregate_verify_for_random_thresholds(
+        (min_signers, max_signers) in arb_threshold(),
+        message in proptest::collection::vec(any::<u8>(), 0..64),
+        first_signer in any::<prop::sample::Index>(),
+        seed in any::<u64>(),
+    ) {
+        let mut rng = rand_chacha::ChaChaRng::seed_from_u64(seed);
+        let (key_packages, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+            max_signers,
+            min_signers,
+            IdentifierList::Default,
+            &mut rng,
+        )
+        .unwrap();
+
+        // Any `min_signers` consecutive participants, wrapping around, form a quorum.
+        let ids: Vec<_> = key_packages.keys().copied().collect();
+        let start = first_signer.index(ids.len());
+        let signers = ids.iter().cycle().skip(start).take(min_signers as usize);
+
+        let message = PallasMessage::from_parts(
+            ROInput::new().append_bytes(&message),
+            NetworkId::Testnet,
+            true,
+        )
+        .serialize()
+        .unwrap();
+
+        let signature = helpers::sign(
+            signers.map(|id| &key_packages[id]),
+            &pubkey_package,
+            &message,
+            &mut rng,
+        );
+
+        prop_assert!(pubkey_package.verifying_key().verify(&message, &signature).is_ok());
+        prop_assert!(helpers::verifies_on(
+            &message,
+            signature,
+            *pubkey_package.verifying_key(),
+            NetworkId::Testnet,
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+mod helpers;
+
+use std::collections::BTreeMap;
+
+use frost_bluepallas::{
+    errors::BluePallasError,
+    keys::{generate_key_packages_with_dealer, IdentifierList},
+    signing_utilities::{SignatureShareExt, SigningPackageBuilder, SigningPackageExt},
+};
+use helpers::{
+    samples,
+    types::{Identifier, KeyPackage, PublicKeyPackage, SignatureShare, SigningPackage},
+};
+use mina_tx::pallas_message::PallasMessage;
+use rand_core::SeedableRng;
+
+/// Key packages of a 2-of-`max_signers` dealer setup.
+fn dealer_key_packages(
+    max_signers: u16,
+    rng: &mut rand_chacha::ChaChaRng,
+) -> (BTreeMap<Identifier, KeyPackage>, PublicKeyPackage) {
+    generate_key_packages_with_dealer::<PallasMessage, _>(
+        max_signers,
+        2,
+        IdentifierList::Default,
+        rng,
+    )
+    .unwrap()
+}
+
+#[test]
+fn signing_package_builder_matches_manual_construction() {
+    let identifier = 42u16.try_into().unwrap();
+    let commitments = samples::signing_commitments();
+    let message = b"hello world";
+
+    let built = SigningPackageBuilder::<PallasMessage>::new()
+        .add_commitment(identifier, commitments)
+        .message(message)
+        .build()
+        .unwrap();
+    let manual = SigningPackage::new(BTreeMap::from([(identifier, commitments)]), message);
+
+    assert_eq!(built, manual);
+    assert_eq!(built, samples::signing_package());
+}
+
+#[test]
+fn signing_package_builder_requires_commitment_and_message() {
+    let identifier = 42u16.try_into().unwrap();
+
+    let no_commitments = SigningPackageBuilder::<PallasMessage>::new()
+        .message(b"hello world")
+        .build();
+    assert_eq!(no_commitments, Err(BluePallasError::NoCommitmentsProvided));
+
+    let no_message = SigningPackageBuilder::<PallasMessage>::new()
+        .add_commitment(identifier, samples::signing_commitments())
+        .build();
+    assert_eq!(no_message, Err(BluePallasError::NoMessageProvided));
+}
+
+#[test]
+fn compact_signing_package_roundtrips_and_is_smaller_than_json() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, _) = dealer_key_packages(64, &mut rng);
+    let (_, commitments) = helpers::commit(key_packages.values(), &mut rng);
+    let package = SigningPackage::new(commitments, b"a message for 64 signers");
+
+    let compact = package.to_compact_bytes().unwrap();
+    let decoded = SigningPackage::from_compact_bytes(&compact).unwrap();
+    assert_eq!(decoded, package);
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_vec(&package).unwrap();
+        assert!(
+            compact.len() < json.len(),
+            "compact {} >= json {}",
+            compact.len(),
+            json.len()
+        );
+    }
+}
+
+#[test]
+fn signing_package_serialized_len_is_exact() {
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, _) = dealer_key_packages(5, &mut rng);
+    for (signers, message) in [(1, &b""[..]), (2, b"hello world"), (5, &[0xab; 300][..])] {
+        let (_, commitments) = helpers::commit(key_packages.values().take(signers), &mut rng);
+        let package = SigningPackage::new(commitments, message);
+
+        assert_eq!(
+            package.compact_len(),
+            package.to_compact_bytes().unwrap().len()
+        );
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            package.serialized_len(),
+            serde_json::to_vec(&package).unwrap().len()
+        );
+    }
+}
+
+#[test]
+fn compact_signing_package_rejects_malformed_input() {
+    let compact = samples::signing_package().to_compact_bytes().unwrap();
+
+    // Truncated, trailing garbage and unknown version
+    assert!(SigningPackage::from_compact_bytes(&compact[..compact.len() - 1]).is_err());
+    let mut trailing = compact.clone();
+    trailing.push(0);
+    assert!(SigningPackage::from_compact_bytes(&trailing).is_err());
+    let mut version = compact;
+    version[0] = 0xff;
+    assert!(matches!(
+        SigningPackage::from_compact_bytes(&version),
+        Err(BluePallasError::DeSerializationError(_))
+    ));
+}
+
+#[test]
+fn signature_share_bytes_roundtrip_and_aggregate() {
+    let share = samples::signature_share();
+    let bytes = share.to_bytes();
+    assert_eq!(bytes.to_vec(), share.serialize());
+    assert_eq!(SignatureShare::from_bytes(&bytes).unwrap(), share);
+
+    let mut rng = rand_chacha::ChaChaRng::seed_from_u64(0);
+    let (key_packages, pubkey_package) = dealer_key_packages(3, &mut rng);
+    let signers = || key_packages.values().take(2);
+    let (nonces, commitments) = helpers::commit(signers(), &mut rng);
+    let signing_package = SigningPackage::new(commitments, b"shares sent as raw bytes");
+
+    // Each share crosses the wire as its raw scalar bytes
+    let received: BTreeMap<_, _> = helpers::signature_shares(signers(), &signing_package, &nonces)
+        .into_iter()
+        .map(|(id, share)| (id, SignatureShare::from_bytes(&share.to_bytes()).unwrap()))
+        .collect();
+
+    assert!(frost_bluepallas::aggregate(&signing_package, &received, &pubkey_package).is_ok());
+    assert!(SignatureShare::from_bytes(&[0xff; 32]).is_err());
+}
@@ -32,7 +32,8 @@ use_frost_client trusted-dealer \
     -c "$GENERATED_DIR/alice.toml" \
     -c "$GENERATED_DIR/bob.toml" \
     -c "$GENERATED_DIR/eve.toml" \
-    -t 2
+    -t 2 \
+    --yes
 
 # Validate that key generation was successful
 echo "Validating generated configuration files..."
@@ -130,6 +130,22 @@ pub struct SendSigningPackageArgs<C: Ciphersuite> {
     pub aux_msg: Vec<u8>,
 }
 
+/// Sent by a participant to the coordinator instead of its commitments or signature
+/// share when it gives up on the session, e.g. because the user declined to sign.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AbortArgs {
+    /// Why the participant gave up, shown to the coordinator.
+    pub abort: String,
+}
+
+/// Sent by the coordinator to the participants of a signing round it abandoned, asking
+/// them to commit again with fresh nonces.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RestartArgs {
+    /// Why the round was abandoned, shown to the participants.
+    pub restart: String,
+}
+
 /// An error. Wraps a StatusCode which is returned by the server when the
 /// error happens during a API call, and a generic eyre::Report.
 #[derive(Debug, Error, Serialize, Deserialize, PartialEq, Eq)]
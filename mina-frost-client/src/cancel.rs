@@ -0,0 +1,30 @@
+//! Cooperative cancellation for the long-running async commands.
+//!
+//! Embedders pass a [`CancellationToken`] to the `coordinator`, `participant`, `dkg` and
+//! `sessions` entry points and cancel it to stop a session. The binary wires the token to
+//! Ctrl-C for those commands only.
+
+use std::{error::Error, future::Future};
+
+pub use tokio_util::sync::CancellationToken;
+
+/// Error returned by an operation that was stopped through its [`CancellationToken`].
+#[derive(Debug, thiserror::Error)]
+#[error("{0} cancelled")]
+pub struct Cancelled(pub &'static str);
+
+/// Drive `fut` until it completes or `shutdown` is cancelled.
+///
+/// On cancellation `fut` is dropped, which aborts any request it has in flight, and
+/// [`Cancelled`] is returned with `what` naming the operation.
+pub async fn cancellable<T>(
+    shutdown: &CancellationToken,
+    what: &'static str,
+    fut: impl Future<Output = Result<T, Box<dyn Error>>>,
+) -> Result<T, Box<dyn Error>> {
+    tokio::select! {
+        biased;
+        _ = shutdown.cancelled() => Err(Cancelled(what).into()),
+        result = fut => result,
+    }
+}
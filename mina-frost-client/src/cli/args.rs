@@ -1,10 +1,17 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use mina_tx::{graphql::FeeTier, validate_address};
+
+use super::output::OutputFormat;
 
 #[derive(Parser, Clone)]
 #[command(version, about, long_about = None)]
 pub struct Args {
     #[command(subcommand)]
     pub command: Command,
+    /// How to report results. `json` prints a single JSON object on stdout and
+    /// exits with a nonzero code on error.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
 }
 
 #[derive(Subcommand, Clone)]
@@ -20,6 +27,10 @@ pub enum Command {
         /// $HOME/.local/frost/credentials.toml
         #[arg(short, long)]
         config: Option<String>,
+        /// The network (mainnet or testnet) the keys in this config are for.
+        /// Signing sessions for any other network are refused.
+        #[arg(short, long)]
+        network: Option<String>,
     },
     /// Exports the user's contact, printing a string with the contact
     /// information encoded.
@@ -41,6 +52,17 @@ pub enum Command {
         #[arg(short, long)]
         config: Option<String>,
     },
+    /// Imports several contacts at once from a JSON file holding an array of
+    /// `{"name": ..., "pubkey": ...}` entries. Entries whose name or public key
+    /// is already known are skipped; malformed entries are reported.
+    ImportBulk {
+        /// Path to the JSON file with the contacts to import.
+        file: String,
+        /// The path to the config file to manage. If not specified, it uses
+        /// $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+    },
     /// Lists the contacts in the user's address book, in the config file.
     Contacts {
         /// The path to the config file to manage. If not specified, it uses
@@ -98,6 +120,25 @@ pub enum Command {
         /// The threshold (minimum number of signers).
         #[arg(short = 't', long)]
         threshold: u16,
+        /// The ciphersuite to generate keys for.
+        #[arg(long, default_value = "bluepallas")]
+        ciphersuite: String,
+        /// The number of independent groups to generate. Each group has its
+        /// own keys and is stored in every config file under its own group
+        /// public key. With more than one, the description is suffixed with
+        /// the group's number.
+        #[arg(long, default_value_t = 1)]
+        count: u16,
+        /// Generate without asking to confirm the threshold and number of
+        /// participants.
+        #[arg(short = 'y', long, default_value_t = false)]
+        yes: bool,
+        /// Write each participant's key package to `<name>.json` in this
+        /// directory, as a versioned key file, instead of into their config
+        /// file. The participant then passes it in `FROST_KEY_PACKAGE` when
+        /// signing. Only one group can be generated this way.
+        #[arg(long)]
+        key_package_dir: Option<String>,
     },
     /// Generate FROST shares using Distributed Key Generation.
     Dkg {
@@ -120,6 +161,15 @@ pub enum Command {
         /// who creates the DKG session.
         #[arg(short = 'S', long, value_delimiter = ',')]
         participants: Vec<String>,
+        /// Fail if no input is received on stdin for this many seconds,
+        /// instead of waiting indefinitely.
+        #[arg(long)]
+        stdin_timeout: Option<u64>,
+        /// Write the resulting key package to this file, as a versioned key
+        /// file, instead of into the config file. Pass its contents in
+        /// `FROST_KEY_PACKAGE` when signing.
+        #[arg(long)]
+        key_package_out: Option<String>,
     },
     /// Lists the groups the user is in.
     Groups {
@@ -134,11 +184,24 @@ pub enum Command {
         /// $HOME/.local/frost/credentials.toml
         #[arg(short, long)]
         config: Option<String>,
-        /// The group to remove, identified by the group public key (use
-        /// `groups` to list)
-        #[arg(short, long)]
+        /// The group to remove, identified by the group public key or its
+        /// Mina address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
         group: String,
     },
+    /// Prints the Mina address of a group, i.e. the account its signatures
+    /// authorize.
+    Address {
+        /// The path to the config file to manage. If not specified, it uses
+        /// $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+        /// The group whose address to print, identified by the group public
+        /// key or its Mina address (use `groups` to list). May be omitted if
+        /// the config holds a single group.
+        #[arg(short, long, value_parser = parse_group)]
+        group: Option<String>,
+    },
     /// Lists the active FROST signing sessions the user is in.
     Sessions {
         /// The path to the config file to manage. If not specified, it uses
@@ -150,8 +213,8 @@ pub enum Command {
         #[arg(short, long)]
         server_url: Option<String>,
         /// Optional group whose associated server URL will be used, identified
-        /// by the group public key (use `groups` to list).
-        #[arg(short, long)]
+        /// by the group public key or its Mina address (use `groups` to list).
+        #[arg(short, long, value_parser = parse_group)]
         group: Option<String>,
         /// Whether to also close all existing sessions. Useful for cleaning
         /// up lingering sessions due to errors or if participants give up.
@@ -169,9 +232,9 @@ pub enum Command {
         /// logged in via the `login` subcommand for the given server.
         #[arg(short, long)]
         server_url: Option<String>,
-        /// The group to use, identified by the group public key (use `groups`
-        /// to list)
-        #[arg(short, long)]
+        /// The group to use, identified by the group public key or its Mina
+        /// address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
         group: String,
         /// The comma-separated hex-encoded public keys of the signers to use.
         #[arg(short = 'S', long, value_delimiter = ',')]
@@ -188,6 +251,50 @@ pub enum Command {
         /// The network ID to use for signing (mainnet or testnet).
         #[arg(short = 'n', long, default_value = "testnet")]
         network: String,
+        /// Replace the memo of a legacy transaction with these raw bytes,
+        /// given as hex (at most 32 bytes), e.g. a hash that is not valid text.
+        #[arg(long)]
+        memo_hex: Option<String>,
+        /// Set the transaction fee, in nanomina, before signing. Takes
+        /// precedence over `--fee-auto`.
+        #[arg(long)]
+        fee: Option<u64>,
+        /// Set the transaction fee from the fees pending at this Mina node's
+        /// GraphQL endpoint. The fee is part of the signed payload, so it
+        /// cannot be changed after signing.
+        #[arg(long, value_name = "ENDPOINT_URL")]
+        fee_auto: Option<String>,
+        /// How to bid against pending fees with `--fee-auto`; also picks the
+        /// fee used when nothing is pending.
+        #[arg(long, value_enum, default_value_t = FeeTierArg::Medium, requires = "fee_auto")]
+        fee_tier: FeeTierArg,
+        /// A file listing the hex-encoded identifiers allowed to sign, one
+        /// per line. Signers not listed are dropped from the session, which
+        /// fails if fewer than the group threshold remain.
+        #[arg(long)]
+        participants_file: Option<String>,
+        /// Sign even if the network differs from the one recorded in the
+        /// config at `init`.
+        #[arg(long, default_value_t = false)]
+        force_network: bool,
+        /// Fail if no input is received on stdin for this many seconds,
+        /// instead of waiting indefinitely.
+        #[arg(long)]
+        stdin_timeout: Option<u64>,
+        /// Instead of running a session over the server, write a signing
+        /// request for signers on air-gapped machines to this file.
+        #[arg(long, value_name = "PATH", requires = "commitments_file")]
+        export_unsigned: Option<String>,
+        /// The comma-separated files with the round 1 commitments the signers
+        /// produced offline (see `offline-commit`), for `--export-unsigned`.
+        /// Each is a JSON object mapping hex-encoded identifiers to
+        /// commitments.
+        #[arg(long, value_delimiter = ',', requires = "export_unsigned")]
+        commitments_file: Vec<String>,
+        /// Append a JSON-lines record of the session (participants, message,
+        /// signature or failure) to this file.
+        #[arg(long, value_name = "PATH")]
+        audit_log: Option<String>,
     },
     /// Participate in a FROST signing session.
     Participant {
@@ -200,9 +307,9 @@ pub enum Command {
         /// logged in via the `login` subcommand for the given server.
         #[arg(short, long)]
         server_url: Option<String>,
-        /// The group to use, identified by the group public key (use `groups`
-        /// to list)
-        #[arg(short, long)]
+        /// The group to use, identified by the group public key or its Mina
+        /// address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
         group: String,
         /// The session ID to use (use `sessions` to list).
         #[arg(short = 'S', long)]
@@ -210,6 +317,98 @@ pub enum Command {
         /// Automatically answer yes to signing any package.
         #[arg(short = 'y', long, default_value_t = false)]
         yes: bool,
+        /// Before signing, also show the exact message bytes and every signer's
+        /// commitments, then wait for a y/n answer.
+        #[arg(long, default_value_t = false, conflicts_with = "yes")]
+        confirm: bool,
+        /// Sign even if the transaction is for another network than the one
+        /// recorded in the config at `init`.
+        #[arg(long, default_value_t = false)]
+        force_network: bool,
+        /// Fail if no input is received on stdin for this many seconds,
+        /// instead of waiting indefinitely.
+        #[arg(long)]
+        stdin_timeout: Option<u64>,
+    },
+    /// Produce round 1 commitments for signing offline, on an air-gapped
+    /// machine. The nonces are kept in a file for `offline-sign`.
+    OfflineCommit {
+        /// The path to the config file to manage. If not specified, it uses
+        /// $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+        /// The group to sign for, identified by the group public key or its
+        /// Mina address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
+        group: String,
+        /// Where to keep the secret nonces until the signing request arrives.
+        /// The file must not leave this machine.
+        #[arg(long)]
+        nonces: String,
+        /// Where to write the commitments to hand to the coordinator. If "-",
+        /// they are printed to stdout.
+        #[arg(short = 'o', long, default_value = "-")]
+        commitments: String,
+    },
+    /// Sign a signing request exported with `coordinator --export-unsigned`,
+    /// using the nonces kept by `offline-commit`.
+    OfflineSign {
+        /// The path to the config file to manage. If not specified, it uses
+        /// $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+        /// The group to sign for, identified by the group public key or its
+        /// Mina address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
+        group: String,
+        /// The signing request file.
+        #[arg(short, long)]
+        request: String,
+        /// The nonces file written by `offline-commit`. It is removed once
+        /// the signature share has been written.
+        #[arg(long)]
+        nonces: String,
+        /// Where to write the signature share to hand back to the
+        /// coordinator. If "-", it is printed to stdout.
+        #[arg(short = 'o', long, default_value = "-")]
+        share: String,
+        /// The network (mainnet or testnet) the request must be for.
+        #[arg(short = 'n', long, default_value = "testnet")]
+        network: String,
+        /// Automatically answer yes to signing the request.
+        #[arg(short = 'y', long, default_value_t = false)]
+        yes: bool,
+        /// Sign even if the network differs from the one recorded in the
+        /// config at `init`.
+        #[arg(long, default_value_t = false)]
+        force_network: bool,
+        /// Fail if no input is received on stdin for this many seconds,
+        /// instead of waiting indefinitely.
+        #[arg(long)]
+        stdin_timeout: Option<u64>,
+    },
+    /// Aggregate the signature shares returned for a signing request into
+    /// the group's signature.
+    OfflineAggregate {
+        /// The path to the config file to manage. If not specified, it uses
+        /// $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+        /// The group the request was signed by, identified by the group
+        /// public key or its Mina address (use `groups` to list)
+        #[arg(short, long, value_parser = parse_group)]
+        group: String,
+        /// The signing request file the shares were produced for.
+        #[arg(short, long)]
+        request: String,
+        /// The comma-separated signature share files written by
+        /// `offline-sign`.
+        #[arg(short = 'S', long, value_delimiter = ',')]
+        shares: Vec<String>,
+        /// Where to write the generated signature. If "-", it is printed to
+        /// stdout.
+        #[arg(short = 'o', long, default_value = "-")]
+        signature: String,
     },
     /// Build a GraphQL mutation for sending a Mina transaction.
     GraphqlBuild {
@@ -228,5 +427,71 @@ pub enum Command {
         /// Endpoint URL of the Mina node's GraphQL API.
         #[arg(short = 'e', long)]
         endpoint_url: String,
+        /// The path to the config file to check the network against. If not
+        /// specified, it uses $HOME/.local/frost/credentials.toml
+        #[arg(short, long)]
+        config: Option<String>,
+        /// The network (mainnet or testnet) the node belongs to.
+        #[arg(short = 'n', long, default_value = "testnet")]
+        network: String,
+        /// Broadcast even if the network differs from the one recorded in the
+        /// config at `init`.
+        #[arg(long, default_value_t = false)]
+        force_network: bool,
     },
 }
+
+/// Command line form of [`FeeTier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FeeTierArg {
+    Low,
+    Medium,
+    High,
+}
+
+impl From<FeeTierArg> for FeeTier {
+    fn from(tier: FeeTierArg) -> Self {
+        match tier {
+            FeeTierArg::Low => FeeTier::Low,
+            FeeTierArg::Medium => FeeTier::Medium,
+            FeeTierArg::High => FeeTier::High,
+        }
+    }
+}
+
+/// Parse a group given by its hex-encoded public key or by its `B62q...` Mina address,
+/// so a mistyped address is rejected before any command runs.
+fn parse_group(s: &str) -> Result<String, String> {
+    if s.starts_with("B62") {
+        validate_address(s).map_err(|e| e.to_string())?;
+    } else if hex::decode(s).is_err() {
+        return Err(format!(
+            "{s:?} is neither a hex-encoded group public key nor a Mina address"
+        ));
+    }
+    Ok(s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_args_reject_bad_addresses() {
+        let parse = |group: &str| {
+            Args::try_parse_from(["mina-frost-client", "address", "--group", group])
+                .map(|args| args.command)
+        };
+
+        assert!(matches!(
+            parse("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"),
+            Ok(Command::Address { group: Some(_), .. })
+        ));
+        assert!(parse("00ab").is_ok());
+
+        let err = parse("B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgh").unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(err.to_string().contains("is not a Mina address"));
+        assert!(parse("not-a-key").is_err());
+    }
+}
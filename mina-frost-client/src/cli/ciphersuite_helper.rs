@@ -1,12 +1,16 @@
 use std::{error::Error, marker::PhantomData};
 
+use eyre::OptionExt;
+
 use crate::BluePallasSuite;
 use frost_core::{
     keys::{KeyPackage, PublicKeyPackage},
     Ciphersuite,
 };
 
+use frost_bluepallas::keys;
 use mina_tx::pallas_message::translate_pk;
+use zeroize::Zeroizing;
 
 /// Additional information about a group, derived from the key packages.
 #[derive(Debug, Clone)]
@@ -15,6 +19,9 @@ pub struct GroupInfo {
     pub mina_verifying_key: String,
     pub threshold: usize,
     pub num_participants: usize,
+    /// Hex-encoded identifier and verifying share of every participant, in identifier
+    /// order.
+    pub verifying_shares: Vec<(String, String)>,
 }
 
 /// A trait that helps obtaining ciphersuite-dependent information.
@@ -24,6 +31,17 @@ pub trait CiphersuiteHelper<C: Ciphersuite> {
         encoded_key_package: &[u8],
         encoded_public_key_package: &[u8],
     ) -> Result<GroupInfo, Box<dyn Error>>;
+
+    /// Encode a key package as a versioned key file, see
+    /// [`KEY_FILE_VERSION`](frost_bluepallas::keys::KEY_FILE_VERSION).
+    fn export_key_package(
+        &self,
+        key_package: &KeyPackage<C>,
+    ) -> Result<Zeroizing<String>, Box<dyn Error>>;
+
+    /// Read a key package from a key file written by
+    /// [`CiphersuiteHelper::export_key_package`] or an unversioned one.
+    fn import_key_package(&self, json: &str) -> Result<KeyPackage<C>, Box<dyn Error>>;
 }
 
 /// An implementation of CiphersuiteHelper that works for any Ciphersuite.
@@ -56,17 +74,54 @@ where
         encoded_key_package: &[u8],
         encoded_public_key_package: &[u8],
     ) -> Result<GroupInfo, Box<dyn Error>> {
-        let key_package: KeyPackage<C> = postcard::from_bytes(encoded_key_package)?;
         let public_key_package: PublicKeyPackage<BluePallasSuite> =
             postcard::from_bytes(encoded_public_key_package)?;
         let hex_verifying_key = hex::encode(public_key_package.verifying_key().serialize()?);
         let mina_verifying_key = translate_pk(public_key_package.verifying_key())?.into_address();
+        let verifying_shares = public_key_package
+            .verifying_shares()
+            .iter()
+            .map(|(identifier, share)| {
+                Ok((
+                    hex::encode(identifier.serialize()),
+                    hex::encode(share.serialize()?),
+                ))
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?;
+
+        // The key package may be kept out of the config file and supplied at signing time
+        let threshold = if encoded_key_package.is_empty() {
+            public_key_package
+                .min_signers()
+                .ok_or_eyre("group has no key package and its threshold is not recorded")?
+        } else {
+            let key_package: KeyPackage<C> = postcard::from_bytes(encoded_key_package)?;
+            *key_package.min_signers()
+        };
 
         Ok(GroupInfo {
             hex_verifying_key,
             mina_verifying_key,
-            threshold: *key_package.min_signers() as usize,
+            threshold: threshold as usize,
             num_participants: public_key_package.verifying_shares().len(),
+            verifying_shares,
         })
     }
+
+    fn export_key_package(
+        &self,
+        key_package: &KeyPackage<C>,
+    ) -> Result<Zeroizing<String>, Box<dyn Error>> {
+        let encoded = Zeroizing::new(postcard::to_allocvec(key_package)?);
+        let key_package: Zeroizing<KeyPackage<BluePallasSuite>> =
+            Zeroizing::new(postcard::from_bytes(&encoded)?);
+        Ok(Zeroizing::new(keys::export_key_package(&*key_package)?))
+    }
+
+    fn import_key_package(&self, json: &str) -> Result<KeyPackage<C>, Box<dyn Error>> {
+        let key_package: Zeroizing<KeyPackage<BluePallasSuite>> =
+            Zeroizing::new(keys::import_key_package(json)?);
+        let encoded = Zeroizing::new(postcard::to_allocvec(&*key_package)?);
+        Ok(postcard::from_bytes(&encoded)?)
+    }
 }
@@ -12,12 +12,16 @@ use std::{
 
 use crate::cipher::{PrivateKey, PublicKey};
 use eyre::{eyre, OptionExt};
-use frost_core::{Ciphersuite, Identifier};
+use frost_core::{keys::PublicKeyPackage, Ciphersuite, Identifier};
+use mina_tx::{network_id::NetworkIdEnvelope, pallas_message::translate_pk};
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
-use super::{ciphersuite_helper::ciphersuite_helper, contact::Contact, write_atomic};
+use super::{
+    ciphersuite_helper::ciphersuite_helper, contact::Contact, output::Report, write_atomic,
+};
+use crate::BluePallasSuite;
 
 /// The config file, which is serialized with serde.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -32,6 +36,10 @@ pub struct Config<C: Ciphersuite> {
     pub version: u8,
     /// The communication key pair for the user.
     pub communication_key: Option<CommunicationKey>,
+    /// The Mina network the keys in this config are meant for, if recorded
+    /// at `init`. Signing for a different network is refused.
+    #[serde(default)]
+    pub network: Option<String>,
     /// The address book of the user, keyed by each contact's name.
     #[serde(default)]
     pub contact: BTreeMap<String, Contact>,
@@ -51,6 +59,7 @@ where
             path: None,
             version: 0,
             communication_key: None,
+            network: None,
             contact: BTreeMap::new(),
             group: BTreeMap::new(),
         }
@@ -81,6 +90,20 @@ impl<C: Ciphersuite> Config<C> {
             .cloned()
             .ok_or_eyre("contact not found")?)
     }
+
+    /// Get a group by its hex-encoded public key, the key of [`Config::group`], or by
+    /// its `B62q...` Mina address.
+    pub fn group_by_id(&self, id: &str) -> Result<(&String, &Group<C>), Box<dyn Error>> {
+        if let Some(entry) = self.group.get_key_value(id) {
+            return Ok(entry);
+        }
+        for (group_id, group) in &self.group {
+            if group.address()? == id {
+                return Ok((group_id, group));
+            }
+        }
+        Err(eyre!("Group not found").into())
+    }
 }
 
 /// The communication key pair for the user.
@@ -122,6 +145,13 @@ pub struct Group<C: Ciphersuite> {
 impl<C: Ciphersuite> ZeroizeOnDrop for Group<C> {}
 
 impl<C: Ciphersuite> Group<C> {
+    /// The Mina address of the group, i.e. the account its signatures authorize.
+    pub fn address(&self) -> Result<String, Box<dyn Error>> {
+        let public_key_package: PublicKeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&self.public_key_package)?;
+        Ok(translate_pk(public_key_package.verifying_key())?.into_address())
+    }
+
     /// Returns a human-readable summary of the contact; used when it is
     /// printed to the terminal.
     pub fn as_human_readable_summary(&self, config: &Config<C>) -> Result<String, Box<dyn Error>> {
@@ -140,9 +170,39 @@ impl<C: Ciphersuite> Group<C> {
             let contact = config.contact_by_pubkey(&participant.pubkey)?;
             s += &format!("\t{}\t({})\n", contact.name, hex::encode(contact.pubkey.0));
         }
+        s += "Verifying shares:\n";
+        for (identifier, verifying_share) in &info.verifying_shares {
+            s += &format!("\t{}\t{}\n", identifier, verifying_share);
+        }
         Ok(s)
     }
 
+    /// Returns the same information as [`Group::as_human_readable_summary`], for
+    /// `--output json`.
+    pub fn to_report(&self, config: &Config<C>) -> Result<Report, Box<dyn Error>> {
+        let helper = ciphersuite_helper::<C>();
+        let info = helper.group_info(&self.key_package, &self.public_key_package)?;
+        let participants = self
+            .participant
+            .iter()
+            .map(|(identifier, participant)| {
+                let contact = config.contact_by_pubkey(&participant.pubkey)?;
+                let mut entry = contact.to_report();
+                entry.insert("identifier".into(), identifier.clone().into());
+                Ok(serde_json::Value::Object(entry))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        let mut report = Report::new();
+        report.insert("description".into(), self.description.clone().into());
+        report.insert("public_key".into(), info.hex_verifying_key.into());
+        report.insert("address".into(), info.mina_verifying_key.into());
+        report.insert("server_url".into(), self.server_url.clone().into());
+        report.insert("threshold".into(), info.threshold.into());
+        report.insert("participants".into(), participants.into());
+        Ok(report)
+    }
+
     /// Get a group participant by their pubkey.
     pub fn participant_by_pubkey(&self, pubkey: &PublicKey) -> Result<Participant, Box<dyn Error>> {
         Ok(self
@@ -168,6 +228,20 @@ pub struct Participant {
 }
 
 impl Participant {
+    /// Create a participant entry, storing the identifier in its canonical serialization.
+    pub fn new<C: Ciphersuite>(identifier: &Identifier<C>, pubkey: PublicKey) -> Self {
+        Self {
+            identifier: identifier.serialize(),
+            pubkey,
+        }
+    }
+
+    /// The key under which a participant is stored in [`Group::participant`]: the
+    /// lowercase hex encoding of the serialized identifier scalar.
+    pub fn map_key<C: Ciphersuite>(identifier: &Identifier<C>) -> String {
+        hex::encode(identifier.serialize())
+    }
+
     /// Return the parsed identifier for the participant.
     pub fn identifier<C: Ciphersuite>(&self) -> Result<Identifier<C>, Box<dyn std::error::Error>> {
         Ok(Identifier::<C>::deserialize(&self.identifier)?)
@@ -193,6 +267,13 @@ impl<C: Ciphersuite> Config<C> {
         self.path.as_deref()
     }
 
+    /// The file next to the config file that records the round 1 nonces this user
+    /// has signed with, e.g. `credentials.used-nonces.json`.
+    pub fn used_nonces_path(&self) -> Option<PathBuf> {
+        self.path()
+            .map(|path| path.with_extension("used-nonces.json"))
+    }
+
     /// Read the config from given path, or the default path if None.
     /// If the path does not exist, it will load a default (empty) config.
     /// Calling `write()` later will write to the specified path.
@@ -211,6 +292,14 @@ impl<C: Ciphersuite> Config<C> {
         Ok(config)
     }
 
+    /// Check that `requested` is the network this config was created for.
+    ///
+    /// Configs created before networks were recorded accept any network, and
+    /// `force` skips the check entirely.
+    pub fn check_network(&self, requested: &str, force: bool) -> Result<(), Box<dyn Error>> {
+        check_network(self.network.as_deref(), requested, force)
+    }
+
     /// Write the config to path it was loaded from.
     pub fn write(&self) -> Result<(), Box<dyn Error>> {
         let s = Zeroizing::new(toml::to_string_pretty(self)?);
@@ -223,3 +312,156 @@ impl<C: Ciphersuite> Config<C> {
         )?)
     }
 }
+
+/// Same as [`Config::check_network`], for a config recorded for the `configured` network.
+pub fn check_network(
+    configured: Option<&str>,
+    requested: &str,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    let Some(configured) = configured else {
+        return Ok(());
+    };
+    let configured_id = NetworkIdEnvelope::try_from(configured.to_string())?;
+    let requested_id = NetworkIdEnvelope::try_from(requested.to_string())?;
+    if configured_id == requested_id {
+        return Ok(());
+    }
+    if force {
+        eprintln!("Warning: config is for network {configured}, using {requested} as requested");
+        return Ok(());
+    }
+    Err(eyre!(
+        "config is for network {configured} but {requested} was requested; \
+        pass --force-network to override"
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use frost_bluepallas::keys::generate_with_dealer;
+    use frost_core::keys::{IdentifierList, KeyPackage, PublicKeyPackage};
+    use mina_tx::pallas_message::PallasMessage;
+
+    use super::*;
+    use crate::{cipher::Cipher, BluePallasSuite};
+
+    #[test]
+    fn test_participant_identifiers_roundtrip_through_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.toml");
+        let path_str = path.to_str().unwrap().to_string();
+
+        let identifiers: Vec<Identifier<BluePallasSuite>> = vec![
+            Identifier::try_from(1).unwrap(),
+            Identifier::try_from(7).unwrap(),
+            Identifier::try_from(u16::MAX).unwrap(),
+            Identifier::derive(b"alice").unwrap(),
+        ];
+
+        let mut participant = BTreeMap::new();
+        for identifier in &identifiers {
+            let (_, pubkey) = Cipher::generate_keypair().unwrap();
+            participant.insert(
+                Participant::map_key(identifier),
+                Participant::new(identifier, pubkey),
+            );
+        }
+
+        let mut config = Config::<BluePallasSuite>::read(Some(path_str.clone())).unwrap();
+        config.group.insert(
+            "group".to_string(),
+            Group {
+                _phantom: PhantomData,
+                description: "test group".to_string(),
+                public_key_package: vec![],
+                key_package: vec![],
+                server_url: None,
+                participant,
+            },
+        );
+        config.write().unwrap();
+
+        // Identifiers are stored as hex strings in the file.
+        let contents = std::fs::read_to_string(&path).unwrap();
+        for identifier in &identifiers {
+            assert!(contents.contains(&hex::encode(identifier.serialize())));
+        }
+
+        let reloaded = Config::<BluePallasSuite>::read(Some(path_str)).unwrap();
+        let group = &reloaded.group["group"];
+        assert_eq!(group.participant.len(), identifiers.len());
+        for identifier in &identifiers {
+            let stored = &group.participant[&Participant::map_key(identifier)];
+            assert_eq!(stored.identifier::<BluePallasSuite>().unwrap(), *identifier);
+        }
+    }
+
+    #[test]
+    fn test_group_config_preserves_verifying_shares() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.toml");
+        let path_str = path.to_str().unwrap().to_string();
+
+        let mut rng = rand::thread_rng();
+        let (shares, public_key_package) =
+            generate_with_dealer::<PallasMessage, _>(4, 2, IdentifierList::Default, &mut rng)
+                .unwrap();
+        let key_package = KeyPackage::try_from(shares.into_values().next().unwrap()).unwrap();
+
+        let mut config = Config::<BluePallasSuite>::read(Some(path_str.clone())).unwrap();
+        config.group.insert(
+            "group".to_string(),
+            Group {
+                _phantom: PhantomData,
+                description: "test group".to_string(),
+                public_key_package: postcard::to_allocvec(&public_key_package).unwrap(),
+                key_package: postcard::to_allocvec(&key_package).unwrap(),
+                server_url: None,
+                participant: BTreeMap::new(),
+            },
+        );
+        config.write().unwrap();
+
+        let reloaded = Config::<BluePallasSuite>::read(Some(path_str)).unwrap();
+        let group = &reloaded.group["group"];
+        let decoded: PublicKeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&group.public_key_package).unwrap();
+        assert_eq!(
+            decoded.verifying_shares(),
+            public_key_package.verifying_shares()
+        );
+        assert_eq!(decoded.verifying_shares().len(), 4);
+
+        let summary = group.as_human_readable_summary(&reloaded).unwrap();
+        for (identifier, share) in public_key_package.verifying_shares() {
+            assert!(summary.contains(&format!(
+                "\t{}\t{}\n",
+                hex::encode(identifier.serialize()),
+                hex::encode(share.serialize().unwrap())
+            )));
+        }
+    }
+
+    #[test]
+    fn test_check_network_blocks_mismatch_unless_forced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.toml");
+        let path_str = path.to_str().unwrap().to_string();
+
+        let mut config = Config::<BluePallasSuite>::read(Some(path_str.clone())).unwrap();
+        config.network = Some("testnet".to_string());
+        config.write().unwrap();
+        let config = Config::<BluePallasSuite>::read(Some(path_str)).unwrap();
+
+        let err = config.check_network("mainnet", false).unwrap_err();
+        assert!(err.to_string().contains("--force-network"));
+        assert!(config.check_network("mainnet", true).is_ok());
+        assert!(config.check_network("testnet", false).is_ok());
+        assert!(config.check_network("devnet", false).is_ok());
+
+        let legacy = Config::<BluePallasSuite>::default();
+        assert!(legacy.check_network("mainnet", false).is_ok());
+    }
+}
@@ -7,8 +7,9 @@ use crate::cipher::PublicKey;
 use eyre::{eyre, OptionExt};
 use frost_core::Ciphersuite;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 
-use super::{args::Command, config::Config};
+use super::{args::Command, config::Config, output::Report};
 
 /// A FROST contact, which critically has the public key required to
 /// send and receive encrypted and authenticated messages to them.
@@ -34,6 +35,14 @@ impl Contact {
         )
     }
 
+    /// Returns the name and hex-encoded public key of the contact, for `--output json`.
+    pub fn to_report(&self) -> Report {
+        let mut report = Report::new();
+        report.insert("name".into(), self.name.clone().into());
+        report.insert("pubkey".into(), hex::encode(&self.pubkey.0).into());
+        report
+    }
+
     /// Returns the contact encoded as a text string, with Bech32.
     pub fn as_text(&self) -> Result<String, Box<dyn Error>> {
         let bytes = postcard::to_allocvec(self)?;
@@ -56,7 +65,7 @@ impl Contact {
 }
 
 /// Import a contact into the user's address book, in the config file.
-pub fn import<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn import<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::Import {
         contact: text_contact,
         config,
@@ -68,6 +77,25 @@ pub fn import<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
     let mut config = Config::<C>::read(config)?;
 
     let mut contact = Contact::from_text(&text_contact)?;
+    check_not_registered(&config, &contact)?;
+    // We don't want the version when writing to the config file.
+    contact.version = None;
+    config.contact.insert(contact.name.clone(), contact.clone());
+
+    eprintln!("Imported this contact:");
+    eprint!("{}", contact.as_human_readable_summary());
+
+    config.write()?;
+
+    Ok(contact.to_report())
+}
+
+/// Return an error if the contact's name or public key is already present in
+/// the config, either as another contact or as the user's own key.
+fn check_not_registered<C: Ciphersuite>(
+    config: &Config<C>,
+    contact: &Contact,
+) -> Result<(), Box<dyn Error>> {
     if config.contact.contains_key(&contact.name) {
         return Err(eyre!(
             "contact with name {} already exists. Either remove the existing \
@@ -91,20 +119,92 @@ pub fn import<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
         )
         .into());
     }
-    // We don't want the version when writing to the config file.
-    contact.version = None;
-    config.contact.insert(contact.name.clone(), contact.clone());
+    Ok(())
+}
 
-    eprintln!("Imported this contact:");
-    eprint!("{}", contact.as_human_readable_summary());
+/// Outcome of a bulk contact import.
+#[derive(Clone, Debug, Default)]
+pub struct BulkImportSummary {
+    /// Names of the contacts that were added.
+    pub imported: Vec<String>,
+    /// Entries that were skipped because they are already registered, with the reason.
+    pub skipped: Vec<(String, String)>,
+    /// Entries that could not be parsed, by index in the file, with the reason.
+    pub failed: Vec<(usize, String)>,
+}
 
-    config.write()?;
+impl BulkImportSummary {
+    pub fn to_report(&self) -> Report {
+        let mut report = Report::new();
+        report.insert("imported".into(), self.imported.clone().into());
+        report.insert(
+            "skipped".into(),
+            self.skipped
+                .iter()
+                .map(|(name, reason)| json!({ "name": name, "reason": reason }))
+                .collect(),
+        );
+        report.insert(
+            "failed".into(),
+            self.failed
+                .iter()
+                .map(|(index, reason)| json!({ "index": index, "reason": reason }))
+                .collect(),
+        );
+        report
+    }
+}
 
-    Ok(())
+/// Import every contact listed in a JSON file into the user's address book.
+///
+/// Each entry is processed independently: duplicates are skipped and malformed
+/// entries are recorded, without preventing the remaining entries from being
+/// imported.
+pub fn import_bulk<C: Ciphersuite>(args: &Command) -> Result<BulkImportSummary, Box<dyn Error>> {
+    let Command::ImportBulk { file, config } = (*args).clone() else {
+        panic!("invalid Command");
+    };
+
+    let mut config = Config::<C>::read(config)?;
+
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&std::fs::read_to_string(file)?)?;
+    let mut summary = BulkImportSummary::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let mut contact: Contact = match serde_json::from_value(entry) {
+            Ok(contact) => contact,
+            Err(e) => {
+                eprintln!("Entry {index}: invalid contact: {e}");
+                summary.failed.push((index, e.to_string()));
+                continue;
+            }
+        };
+        if let Err(e) = check_not_registered(&config, &contact) {
+            eprintln!("Entry {index}: skipping {}: {e}", contact.name);
+            summary.skipped.push((contact.name, e.to_string()));
+            continue;
+        }
+        contact.version = None;
+        eprintln!("Imported {}", contact.name);
+        summary.imported.push(contact.name.clone());
+        config.contact.insert(contact.name.clone(), contact);
+    }
+
+    eprintln!(
+        "Imported {} contacts, skipped {}, failed {}",
+        summary.imported.len(),
+        summary.skipped.len(),
+        summary.failed.len()
+    );
+
+    if !summary.imported.is_empty() {
+        config.write()?;
+    }
+
+    Ok(summary)
 }
 
 /// Export a contact from the user's address book in the config file.
-pub fn export<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn export<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::Export { name, config } = (*args).clone() else {
         panic!("invalid Command");
     };
@@ -128,30 +228,41 @@ pub fn export<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
         "Check if contains the expected information. If it does, copy the following \
         contact string and send to other participants you want to use FROST with:"
     );
-    eprintln!("{}", contact.as_text()?);
+    let text = contact.as_text()?;
+    eprintln!("{}", text);
 
-    Ok(())
+    let mut report = contact.to_report();
+    report.insert("contact".into(), text.into());
+    Ok(report)
 }
 
 /// List the contacts in the address book in the config file.
-pub fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn list<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::Contacts { config } = (*args).clone() else {
         panic!("invalid Command");
     };
 
     let config = Config::<C>::read(config)?;
 
+    let mut contacts = Vec::new();
     for contact in config.contact.values() {
+        let text = contact.as_text()?;
         eprint!("{}", contact.as_human_readable_summary());
-        eprintln!("{}", contact.as_text()?);
+        eprintln!("{}", text);
         eprintln!();
+
+        let mut entry = contact.to_report();
+        entry.insert("contact".into(), text.into());
+        contacts.push(entry);
     }
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("contacts".into(), contacts.into());
+    Ok(report)
 }
 
 /// Remove a contact from the user's address book in the config file.
-pub fn remove<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn remove<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::RemoveContact { config, pubkey } = (*args).clone() else {
         panic!("invalid Command");
     };
@@ -171,9 +282,53 @@ pub fn remove<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
         .clone()
         .ok_or_eyre("contact not found")?;
 
-    config.contact.remove(&name);
+    let contact = config.contact.remove(&name).expect("found above");
 
     config.write()?;
 
-    Ok(())
+    Ok(contact.to_report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cipher::Cipher, BluePallasSuite};
+
+    #[test]
+    fn test_import_bulk_reports_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("credentials.toml");
+        let contacts_path = dir.path().join("contacts.json");
+
+        let pubkeys: Vec<String> = (0..3)
+            .map(|_| hex::encode(Cipher::generate_keypair().unwrap().1 .0))
+            .collect();
+        let entries = json!([
+            { "name": "alice", "pubkey": pubkeys[0] },
+            { "name": "bob", "pubkey": pubkeys[1] },
+            { "name": "carol", "pubkey": pubkeys[2] },
+            // Same public key as alice under another name
+            { "name": "mallory", "pubkey": pubkeys[0] },
+            { "name": "dave" },
+        ]);
+        std::fs::write(&contacts_path, entries.to_string()).unwrap();
+
+        let args = Command::ImportBulk {
+            file: contacts_path.to_str().unwrap().to_string(),
+            config: Some(config_path.to_str().unwrap().to_string()),
+        };
+        let summary = import_bulk::<BluePallasSuite>(&args).unwrap();
+
+        assert_eq!(summary.imported, vec!["alice", "bob", "carol"]);
+        assert_eq!(summary.skipped.len(), 1);
+        assert_eq!(summary.skipped[0].0, "mallory");
+        assert_eq!(summary.failed.len(), 1);
+        assert_eq!(summary.failed[0].0, 4);
+
+        let config =
+            Config::<BluePallasSuite>::read(Some(config_path.to_str().unwrap().to_string()))
+                .unwrap();
+        assert_eq!(config.contact.len(), 3);
+        assert!(config.contact.values().all(|c| c.version.is_none()));
+    }
 }
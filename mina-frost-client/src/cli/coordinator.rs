@@ -1,49 +1,99 @@
 use crate::{
+    cancel::CancellationToken,
     cipher::PublicKey,
-    coordinator::{coordinate_signing, Config as CoordinatorConfig},
+    coordinator::{
+        coordinate_signing, coordinate_signing::retain_allowed, Config as CoordinatorConfig,
+    },
+    signing_request::SigningRequest,
     BluePallasSuite,
 };
 use eyre::Context;
 use eyre::OptionExt;
-use frost_core::{keys::PublicKeyPackage, Ciphersuite, VerifyingKey};
+use frost_bluepallas::keys::IdentifierExt;
+use frost_core::{
+    keys::{KeyPackage, PublicKeyPackage},
+    round1::SigningCommitments,
+    Ciphersuite, Identifier, VerifyingKey,
+};
 use mina_tx::{
-    errors::MinaTxError, network_id::NetworkIdEnvelope, TransactionEnvelope, TransactionSignature,
+    errors::MinaTxError,
+    graphql::{build_pooled_fees_query, parse_pooled_fees, suggest_fee, FeeTier},
+    network_id::NetworkIdEnvelope,
+    TransactionEnvelope, TransactionKind, TransactionSignature,
 };
 use reqwest::Url;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     error::Error,
     fs,
     io::{BufRead, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use super::args::Command;
 use super::config::Config as ConfigFile;
+use super::output::{OutputFormat, Report};
+use super::stdin;
 
 /// This is the BluePallas/BluePallas specific run command for the coordinator which will save the output
 /// of the signing session into a Mina-specific transaction.
-pub async fn run_bluepallas(args: &Command) -> Result<(), Box<dyn Error>> {
+pub async fn run_bluepallas(
+    args: &Command,
+    output: OutputFormat,
+    shutdown: &CancellationToken,
+) -> Result<Report, Box<dyn Error>> {
     // Match on command type early to ensure we are running the coordinator command, panic otherwise
     let Command::Coordinator {
         signature: signature_path,
+        export_unsigned,
         ..
     } = args
     else {
         panic!("invalid Command");
     };
 
-    let (bytes, transaction, vk) = run(args).await?;
+    if let Some(request_path) = export_unsigned {
+        return export_signing_request(args, request_path, shutdown).await;
+    }
+
+    let (bytes, transaction, vk) = run(args, shutdown).await?;
 
     // Save signature to the specified path or stdout
-    save_signature(signature_path, bytes, transaction, vk)
+    let transaction_signature = save_signature(signature_path, bytes, transaction, vk, output)
         .map_err(|e| MinaTxError::SaveSignatureError(e.to_string()))?;
 
-    Ok(())
+    signature_report(&transaction_signature, signature_path)
+}
+
+/// Summarise a signing session for `--output json`. When the signature was not written to
+/// a file, the full signed transaction is embedded instead.
+pub(crate) fn signature_report(
+    transaction_signature: &TransactionSignature,
+    signature_path: &str,
+) -> Result<Report, Box<dyn Error>> {
+    let mut report = Report::new();
+    report.insert(
+        "signature".into(),
+        transaction_signature.signature.to_base58().into(),
+    );
+    report.insert(
+        "address".into(),
+        transaction_signature.publicKey.pubKey.into_address().into(),
+    );
+    if signature_path == "-" {
+        report.insert(
+            "transaction_signature".into(),
+            serde_json::to_value(transaction_signature)?,
+        );
+    } else {
+        report.insert("signature_path".into(), signature_path.into());
+    }
+    Ok(report)
 }
 
 pub(crate) async fn run(
     args: &Command,
+    shutdown: &CancellationToken,
 ) -> Result<(Vec<u8>, TransactionEnvelope, VerifyingKey<BluePallasSuite>), Box<dyn Error>> {
     // Note, we duplicate pattern matching code here and in run(), but given that there is no way to pass a Command::Coordinator type
     // to this function, we must instead repeat the check again
@@ -53,26 +103,41 @@ pub(crate) async fn run(
         server_url,
         group: group_id,
         signers,
-        message,
         network,
-        signature: _,
+        participants_file,
+        force_network,
+        stdin_timeout,
+        audit_log,
+        ..
     } = (*args).clone()
     else {
         panic!("invalid Command");
     };
 
-    let mut input = Box::new(std::io::stdin().lock());
+    let mut input = stdin::input(
+        stdin_timeout,
+        "the transaction to sign as JSON, terminated by end of input",
+        shutdown,
+    );
     let mut output = std::io::stdout();
 
     // Load and validate configuration
     let (user_config, group_config, public_key_package) =
         load_coordinator_config::<BluePallasSuite>(config_path, &group_id)?;
+    user_config.check_network(&network, force_network)?;
 
     // Parse signers from command line arguments
-    let signers = parse_signers::<BluePallasSuite>(&signers, &group_config)?;
+    let mut signers = parse_signers::<BluePallasSuite>(&signers, &group_config)?;
+
+    // Restrict the quorum to the operator-approved identifiers, if any
+    if let Some(path) = participants_file {
+        let allowed = load_participants_file(&path)?;
+        let key_package: KeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&group_config.key_package)?;
+        signers = restrict_signers(signers, &allowed, *key_package.min_signers())?;
+    }
 
-    let network_id: NetworkIdEnvelope = network.try_into()?;
-    let transaction = load_transaction(&message, network_id, &mut output, &mut input)?;
+    let transaction = prepare_transaction(args, &mut output, &mut input).await?;
     let message_bytes = transaction.serialize()?;
 
     // Setup coordinator configuration
@@ -81,13 +146,15 @@ pub(crate) async fn run(
         group_config: &group_config,
         server_url,
         message: message_bytes,
+        audit_log,
     };
 
     let coordinator_config =
         setup_coordinator_config::<BluePallasSuite>(public_key_package.clone(), signers, params)?;
 
     // Execute signing
-    let signature_bytes = coordinate_signing(&coordinator_config, &mut input, &mut output).await?;
+    let signature_bytes =
+        coordinate_signing(&coordinator_config, &mut input, &mut output, shutdown).await?;
 
     Ok((
         signature_bytes,
@@ -96,6 +163,131 @@ pub(crate) async fn run(
     ))
 }
 
+/// Write a [`SigningRequest`] for the signers listed in `--commitments-file` instead of
+/// running a session over the server.
+async fn export_signing_request(
+    args: &Command,
+    request_path: &str,
+    shutdown: &CancellationToken,
+) -> Result<Report, Box<dyn Error>> {
+    let Command::Coordinator { stdin_timeout, .. } = args else {
+        panic!("invalid Command");
+    };
+
+    let mut input = stdin::input(
+        *stdin_timeout,
+        "the transaction to sign as JSON, terminated by end of input",
+        shutdown,
+    );
+    export_signing_request_with_input(args, request_path, &mut std::io::stdout(), &mut input).await
+}
+
+/// Same as [`export_signing_request`], reading the transaction from `input` unless it is
+/// given as a file.
+pub(crate) async fn export_signing_request_with_input(
+    args: &Command,
+    request_path: &str,
+    output: &mut dyn Write,
+    input: &mut dyn BufRead,
+) -> Result<Report, Box<dyn Error>> {
+    let Command::Coordinator {
+        config: config_path,
+        group: group_id,
+        network,
+        participants_file,
+        force_network,
+        commitments_file,
+        ..
+    } = (*args).clone()
+    else {
+        panic!("invalid Command");
+    };
+
+    let (user_config, _, public_key_package) =
+        load_coordinator_config::<BluePallasSuite>(config_path, &group_id)?;
+    user_config.check_network(&network, force_network)?;
+
+    let mut commitments = BTreeMap::new();
+    for path in &commitments_file {
+        for (identifier, signer_commitments) in load_commitments_file::<BluePallasSuite>(path)? {
+            match commitments.insert(identifier, signer_commitments) {
+                Some(other) if other != signer_commitments => {
+                    return Err(eyre::eyre!(
+                        "conflicting commitments for participant {} in {path}",
+                        hex::encode(identifier.serialize())
+                    )
+                    .into());
+                }
+                _ => {}
+            }
+        }
+    }
+    if let Some(path) = participants_file {
+        let allowed = load_participants_file(&path)?;
+        commitments = retain_allowed(commitments, &allowed);
+    }
+    let min_signers = public_key_package
+        .min_signers()
+        .ok_or_eyre("the group's threshold is not recorded")?;
+    if commitments.len() < min_signers as usize {
+        return Err(eyre::eyre!(
+            "commitments from {} signers were given, but {} are required",
+            commitments.len(),
+            min_signers
+        )
+        .into());
+    }
+
+    let transaction = prepare_transaction(args, output, input).await?;
+    let request = SigningRequest::new(&transaction, commitments)?;
+    fs::write(request_path, request.to_json()?)?;
+    eprintln!("Signing request saved to {request_path}");
+
+    let mut report = Report::new();
+    report.insert("signing_request_path".into(), request_path.into());
+    report.insert(
+        "participants".into(),
+        serde_json::to_value(&request.participants)?,
+    );
+    Ok(report)
+}
+
+/// Load the transaction to sign and apply the memo and fee options to it.
+async fn prepare_transaction(
+    args: &Command,
+    output: &mut dyn Write,
+    input: &mut dyn BufRead,
+) -> Result<TransactionEnvelope, Box<dyn Error>> {
+    let Command::Coordinator {
+        message,
+        network,
+        memo_hex,
+        fee,
+        fee_auto,
+        fee_tier,
+        ..
+    } = (*args).clone()
+    else {
+        panic!("invalid Command");
+    };
+
+    let network_id: NetworkIdEnvelope = network.try_into()?;
+    let mut transaction = load_transaction(&message, network_id, output, input)?;
+    if let Some(memo_hex) = memo_hex {
+        set_memo_hex(&mut transaction, &memo_hex)?;
+    }
+    let fee = match (fee, fee_auto) {
+        (Some(fee), _) => Some(fee),
+        (None, Some(endpoint)) => Some(query_suggested_fee(&endpoint, fee_tier.into()).await?),
+        (None, None) => None,
+    };
+    if let Some(fee) = fee {
+        eprintln!("Using fee: {fee} nanomina");
+        set_fee(&mut transaction, fee);
+    }
+    Ok(transaction)
+}
+
 fn load_transaction(
     message_path: &str,
     network_id: NetworkIdEnvelope,
@@ -112,6 +304,41 @@ fn load_transaction(
     }
 }
 
+/// Replace the memo of a legacy transaction with the hex-encoded bytes in `memo_hex`.
+fn set_memo_hex(
+    transaction: &mut TransactionEnvelope,
+    memo_hex: &str,
+) -> Result<(), Box<dyn Error>> {
+    let memo = hex::decode(memo_hex.trim()).wrap_err("--memo-hex is not valid hex")?;
+    let TransactionKind::Legacy(tx) = transaction.inner_mut() else {
+        return Err(eyre::eyre!("--memo-hex only applies to legacy transactions").into());
+    };
+    *tx = tx.clone().set_memo_bytes(&memo)?;
+    Ok(())
+}
+
+/// Set the fee paid by the transaction, in nanomina.
+fn set_fee(transaction: &mut TransactionEnvelope, fee: u64) {
+    match transaction.inner_mut() {
+        TransactionKind::Legacy(tx) => tx.fee = fee,
+        TransactionKind::ZkApp(zkapp) => zkapp.fee_payer.body.fee = fee,
+    }
+}
+
+/// Ask the Mina node at `endpoint` for the fees in its transaction pool and suggest one.
+async fn query_suggested_fee(endpoint: &str, tier: FeeTier) -> Result<u64, Box<dyn Error>> {
+    let body = reqwest::Client::new()
+        .post(endpoint)
+        .json(&build_pooled_fees_query())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let pooled_fees = parse_pooled_fees(&body)?;
+    Ok(suggest_fee(&pooled_fees, tier))
+}
+
 // Avoid clippy warnings about complex return types
 type LoadCoordinatorConfigResult<C> = Result<
     (
@@ -132,11 +359,7 @@ fn load_coordinator_config<C: Ciphersuite>(
 ) -> LoadCoordinatorConfigResult<C> {
     let user_config = ConfigFile::read(config_path)?;
 
-    let group_config = user_config
-        .group
-        .get(group_id)
-        .ok_or_eyre("Group not found")?
-        .clone();
+    let group_config = user_config.group_by_id(group_id)?.1.clone();
 
     let public_key_package: PublicKeyPackage<C> =
         postcard::from_bytes(&group_config.public_key_package)?;
@@ -162,6 +385,63 @@ fn parse_signers<C: Ciphersuite>(
         .collect::<Result<HashMap<_, _>, Box<dyn Error>>>()
 }
 
+/// Read the identifiers allowed to take part in signing.
+///
+/// The file lists one hex-encoded identifier per line, as stored in the group's
+/// participant table. Empty lines and lines starting with `#` are ignored.
+fn load_participants_file(
+    path: &str,
+) -> Result<BTreeSet<Identifier<BluePallasSuite>>, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(path).wrap_err(format!("error reading participants file {path}"))?;
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let bytes = hex::decode(line).wrap_err(format!("invalid identifier {line}"))?;
+            Ok(Identifier::try_from_bytes(&bytes)?)
+        })
+        .collect()
+}
+
+/// Read the round 1 commitments that signers produced offline.
+///
+/// The file is a JSON object mapping each hex-encoded identifier to its commitments,
+/// as serialized by frost-core.
+pub(crate) fn load_commitments_file<C: Ciphersuite>(
+    path: &str,
+) -> Result<BTreeMap<Identifier<C>, SigningCommitments<C>>, Box<dyn Error>> {
+    let contents =
+        fs::read_to_string(path).wrap_err(format!("error reading commitments file {path}"))?;
+    Ok(serde_json::from_str(&contents).wrap_err(format!("invalid commitments file {path}"))?)
+}
+
+/// Keep only the signers whose identifier is allowed, failing if the remaining
+/// signers cannot reach `min_signers`.
+fn restrict_signers<C: Ciphersuite>(
+    signers: HashMap<PublicKey, frost_core::Identifier<C>>,
+    allowed: &BTreeSet<frost_core::Identifier<C>>,
+    min_signers: u16,
+) -> Result<HashMap<PublicKey, frost_core::Identifier<C>>, Box<dyn Error>> {
+    let (kept, excluded): (HashMap<_, _>, HashMap<_, _>) = signers
+        .into_iter()
+        .partition(|(_, identifier)| allowed.contains(identifier));
+    for pubkey in excluded.keys() {
+        eprintln!("Excluding signer {pubkey}: not listed in the participants file");
+    }
+    if kept.len() < min_signers as usize {
+        return Err(eyre::eyre!(
+            "only {} of the requested signers are allowed by the participants file, \
+            but {} are required",
+            kept.len(),
+            min_signers
+        )
+        .into());
+    }
+    Ok(kept)
+}
+
 /// Parameters for setting up coordinator configuration
 ///
 /// This structure groups related parameters to avoid the Clippy warning about
@@ -171,6 +451,7 @@ struct CoordinatorSetupParams<'a, C: Ciphersuite> {
     group_config: &'a crate::cli::config::Group<C>,
     server_url: Option<String>,
     message: Vec<u8>,
+    audit_log: Option<String>,
 }
 
 /// Setup coordinator configuration for signing
@@ -229,6 +510,7 @@ fn setup_coordinator_config<C: Ciphersuite>(
                 .pubkey
                 .clone(),
         ),
+        audit_log: params.audit_log.map(PathBuf::from),
     };
 
     Ok(coordinator_config)
@@ -241,7 +523,8 @@ pub fn save_signature(
     signature_bytes: Vec<u8>,
     transaction: TransactionEnvelope,
     vk: VerifyingKey<BluePallasSuite>,
-) -> Result<(), Box<dyn Error>> {
+    output: OutputFormat,
+) -> Result<TransactionSignature, Box<dyn Error>> {
     let (transaction_signature, warnings_opt) =
         TransactionSignature::from_frost_signature_bytes(vk, &signature_bytes, transaction)?;
 
@@ -256,13 +539,18 @@ pub fn save_signature(
     let output_str = serde_json::to_string_pretty(&transaction_signature)
         .map_err(|e| MinaTxError::DeSerializationError(e.to_string()))?;
 
+    // In JSON mode the signed transaction and its location are part of the command report
     if signature_path == "-" {
-        println!("{}", output_str);
+        if output == OutputFormat::Text {
+            println!("{}", output_str);
+        }
     } else {
         fs::write(signature_path, output_str)?;
-        println!("Signature saved to {}", signature_path);
+        if output == OutputFormat::Text {
+            println!("Signature saved to {}", signature_path);
+        }
     }
-    Ok(())
+    Ok(transaction_signature)
 }
 
 fn load_transaction_from_json<P: AsRef<Path>>(
@@ -292,3 +580,160 @@ fn load_transaction_from_str(
         .map_err(|e| eyre::eyre!("Failed to parse transaction from JSON: {}", e))?;
     Ok(transaction)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mina_tx::{legacy_tx::LegacyTransaction, PubKeySer, Sig};
+
+    fn sample_transaction_signature() -> TransactionSignature {
+        let pub_key = mina_signer::PubKey::from_address(
+            "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+        )
+        .unwrap();
+        let payload = TransactionEnvelope::new_legacy(
+            mina_tx::NetworkId::Testnet,
+            LegacyTransaction::new_payment(pub_key.clone(), pub_key.clone(), 1_000, 1_000, 0),
+        );
+        TransactionSignature {
+            publicKey: PubKeySer { pubKey: pub_key },
+            signature: Sig {
+                field: Default::default(),
+                scalar: Default::default(),
+            },
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_signature_report_json_shape() {
+        let transaction_signature = sample_transaction_signature();
+
+        let report = signature_report(&transaction_signature, "signature.json").unwrap();
+        let value = crate::cli::output::render_json(&Ok(report));
+        assert_eq!(value["status"], "ok");
+        assert_eq!(
+            value["address"],
+            "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg"
+        );
+        assert!(value["signature"].is_string());
+        assert_eq!(value["signature_path"], "signature.json");
+
+        let report = signature_report(&transaction_signature, "-").unwrap();
+        assert!(report["transaction_signature"].is_object());
+        assert!(!report.contains_key("signature_path"));
+    }
+
+    #[test]
+    fn test_participants_file_restricts_quorum() {
+        let identifiers: Vec<frost_core::Identifier<BluePallasSuite>> = (1..=3u16)
+            .map(|i| frost_core::Identifier::try_from(i).unwrap())
+            .collect();
+        let signers: HashMap<PublicKey, _> = identifiers
+            .iter()
+            .map(|id| (crate::cipher::Cipher::generate_keypair().unwrap().1, *id))
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("participants.txt");
+        let contents = format!(
+            "# signer 2 is compromised\n{}\n\n{}\n",
+            hex::encode(identifiers[0].serialize()),
+            hex::encode(identifiers[2].serialize())
+        );
+        fs::write(&path, contents).unwrap();
+        let allowed = load_participants_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(allowed.len(), 2);
+
+        let kept = restrict_signers(signers.clone(), &allowed, 2).unwrap();
+        assert_eq!(kept.len(), 2);
+        assert!(!kept.values().any(|id| *id == identifiers[1]));
+
+        // The allowed set cannot reach a threshold of 3
+        assert!(restrict_signers(signers, &allowed, 3).is_err());
+    }
+
+    #[test]
+    fn test_commitments_file_builds_signing_request() {
+        let (shares, _) = frost_bluepallas::keys::generate_with_dealer::<
+            mina_tx::pallas_message::PallasMessage,
+            _,
+        >(
+            3,
+            2,
+            frost_core::keys::IdentifierList::Default,
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        let commitments: BTreeMap<_, _> = shares
+            .iter()
+            .take(2)
+            .map(|(identifier, share)| {
+                let (_, commitments) = frost_bluepallas::round1::commit(
+                    share.signing_share(),
+                    &mut rand::thread_rng(),
+                );
+                (*identifier, commitments)
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("commitments.json");
+        fs::write(&path, serde_json::to_string(&commitments).unwrap()).unwrap();
+        let loaded = load_commitments_file::<BluePallasSuite>(path.to_str().unwrap()).unwrap();
+        assert_eq!(loaded, commitments);
+
+        let transaction = sample_transaction_signature().payload;
+        let request = SigningRequest::new(&transaction, loaded).unwrap();
+        let imported = SigningRequest::from_json(&request.to_json().unwrap()).unwrap();
+        assert_eq!(imported, request);
+        assert_eq!(imported.participants.len(), 2);
+
+        fs::write(&path, "{}{").unwrap();
+        assert!(load_commitments_file::<BluePallasSuite>(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_set_memo_hex_sets_binary_memo() {
+        let mut transaction = sample_transaction_signature().payload;
+        let hash = [0xffu8; 32];
+
+        set_memo_hex(&mut transaction, &hex::encode(hash)).unwrap();
+
+        let TransactionKind::Legacy(tx) = transaction.inner() else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(tx.memo_payload().unwrap(), &hash);
+        assert!(set_memo_hex(&mut transaction, "zz").is_err());
+        assert!(set_memo_hex(&mut transaction, &hex::encode([0u8; 33])).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fee_auto_sets_fee_from_pooled_fees() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            let body = r#"{"data":{"pooledUserCommands":[{"fee":"20000000"},{"fee":"40000000"},{"fee":"30000000"}]}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let fee = query_suggested_fee(&format!("http://{addr}"), FeeTier::Medium)
+            .await
+            .unwrap();
+        let mut transaction = sample_transaction_signature().payload;
+        set_fee(&mut transaction, fee);
+
+        let TransactionKind::Legacy(tx) = transaction.inner() else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(tx.fee, 30_000_000);
+    }
+}
@@ -13,29 +13,43 @@ use zeroize::Zeroizing;
 
 use super::{
     args::Command,
+    ciphersuite_helper::ciphersuite_helper,
     config::{Config, Group, Participant},
+    output::Report,
+    stdin, write_atomic,
 };
 
 use crate::api;
+use crate::cancel::CancellationToken;
 use crate::dkg;
 
 /// CLI entry point for distributed key generation
 ///
 /// Generates FROST key shares using distributed key generation protocol
-/// and updates the participant config file with group information.
-pub async fn run<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+/// and updates the participant config file with group information. Cancelling
+/// `shutdown` aborts the protocol and leaves the config file untouched.
+pub async fn run<C: Ciphersuite>(
+    args: &Command,
+    shutdown: &CancellationToken,
+) -> Result<Report, Box<dyn Error>> {
     let Command::Dkg {
         config: config_path,
         description,
         server_url,
         threshold,
         participants,
+        stdin_timeout,
+        key_package_out,
     } = (*args).clone()
     else {
         panic!("invalid Command");
     };
 
-    let mut input = Box::new(std::io::stdin().lock());
+    let mut input = stdin::input(
+        stdin_timeout,
+        "answers to the DKG confirmation prompts",
+        shutdown,
+    );
     let mut output = std::io::stdout();
 
     // Setup DKG configuration
@@ -44,7 +58,7 @@ pub async fn run<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
 
     // Generate key shares through DKG
     let (key_package, public_key_package, pubkey_map) =
-        dkg::keygen::<C>(dkg_config, &mut input, &mut output).await?;
+        dkg::keygen::<C>(dkg_config, &mut input, &mut output, shutdown).await?;
     let key_package = Zeroizing::new(key_package);
 
     // Create participants map from DKG results
@@ -58,9 +72,8 @@ pub async fn run<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
         &key_package,
         &public_key_package,
         &participants_map,
-    )?;
-
-    Ok(())
+        key_package_out.as_deref(),
+    )
 }
 
 /// Setup DKG configuration from command line arguments and config file
@@ -144,11 +157,8 @@ fn create_participants_map<C: Ciphersuite>(
         let pubkey = identifier_to_pubkey
             .get(identifier)
             .ok_or_eyre("missing pubkey")?;
-        let participant = Participant {
-            identifier: identifier.serialize(),
-            pubkey: pubkey.clone(),
-        };
-        participants.insert(hex::encode(identifier.serialize()), participant);
+        let participant = Participant::new(identifier, pubkey.clone());
+        participants.insert(Participant::map_key(identifier), participant);
     }
 
     Ok(participants)
@@ -157,7 +167,9 @@ fn create_participants_map<C: Ciphersuite>(
 /// Update config file with group information
 ///
 /// This function takes the generated key package and updates the participant's config
-/// file with the group information.
+/// file with the group information, returning a report of the new group. With
+/// `key_package_out`, the key package is written to that file as a versioned key file
+/// instead and the config file holds none.
 fn update_config_with_group<C: Ciphersuite>(
     config_path: Option<String>,
     description: &str,
@@ -165,11 +177,20 @@ fn update_config_with_group<C: Ciphersuite>(
     key_package: &Zeroizing<frost_core::keys::KeyPackage<C>>,
     public_key_package: &frost_core::keys::PublicKeyPackage<C>,
     participants: &BTreeMap<String, Participant>,
-) -> Result<(), Box<dyn Error>> {
+    key_package_out: Option<&str>,
+) -> Result<Report, Box<dyn Error>> {
+    let encoded_key_package = match key_package_out {
+        Some(path) => {
+            let json = ciphersuite_helper::<C>().export_key_package(key_package)?;
+            write_atomic::write_file(path, json.as_bytes())?;
+            Vec::new()
+        }
+        None => postcard::to_allocvec(key_package)?,
+    };
     let group = Group::<C> {
         _phantom: PhantomData,
         description: description.to_string(),
-        key_package: postcard::to_allocvec(key_package)?,
+        key_package: encoded_key_package,
         public_key_package: postcard::to_allocvec(public_key_package)?,
         participant: participants.clone(),
         server_url: Some(server_url.to_string()),
@@ -179,6 +200,7 @@ fn update_config_with_group<C: Ciphersuite>(
     // `comm_participant_pubkey_getter` callback.
     // TODO: is this an issue?
     let mut config = Config::read(config_path)?;
+    let mut report = group.to_report(&config)?;
     config.group.insert(
         hex::encode(public_key_package.verifying_key().serialize()?),
         group,
@@ -190,5 +212,18 @@ fn update_config_with_group<C: Ciphersuite>(
         config.path().expect("should not be None").display()
     );
 
-    Ok(())
+    if let Some(path) = key_package_out {
+        eprintln!("Key package written to {path}");
+        report.insert("key_package_file".into(), path.into());
+    }
+    report.insert(
+        "config_path".into(),
+        config
+            .path()
+            .expect("should not be None")
+            .display()
+            .to_string()
+            .into(),
+    );
+    Ok(report)
 }
@@ -1,12 +1,18 @@
-use super::args::Command;
-use mina_tx::TransactionSignature;
+use super::{args::Command, config::Config, output::Report};
+use crate::BluePallasSuite;
+use mina_tx::{
+    errors::MinaTxError,
+    graphql::{parse_broadcast_response, BroadcastResult, BroadcastStatus},
+    TransactionSignature,
+};
+use reqwest::StatusCode;
 use std::fs;
 
 // ------------------------------------------------------------
 // Build & save GraphQL JSON
 // ------------------------------------------------------------
 
-pub fn graphql_build_command(args: &Command) -> Result<(), Box<dyn std::error::Error>> {
+pub fn graphql_build_command(args: &Command) -> Result<Report, Box<dyn std::error::Error>> {
     let Command::GraphqlBuild {
         input_path,
         output_path,
@@ -20,43 +26,219 @@ pub fn graphql_build_command(args: &Command) -> Result<(), Box<dyn std::error::E
     let graphql_json = tx_sig
         .to_graphql_query_json()
         .expect("Failed to build GraphQL JSON");
-    fs::write(output_path, graphql_json)?;
+    fs::write(&output_path, graphql_json)?;
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("graphql_path".into(), output_path.into());
+    Ok(report)
 }
 
 // ------------------------------------------------------------
 // Broadcast saved GraphQL JSON
 // ------------------------------------------------------------
 
-pub async fn graphql_broadcast_command(args: &Command) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn graphql_broadcast_command(
+    args: &Command,
+) -> Result<Report, Box<dyn std::error::Error>> {
     let Command::GraphqlBroadcast {
         graphql_path,
         endpoint_url: endpoint,
+        config,
+        network,
+        force_network,
     } = (*args).clone()
     else {
         panic!("invalid Command");
     };
+    Config::<BluePallasSuite>::read(config)?.check_network(&network, force_network)?;
     let graphql_json = fs::read_to_string(graphql_path)?;
 
-    println!("Using GraphQL endpoint: {}", endpoint);
+    eprintln!("Using GraphQL endpoint: {}", endpoint);
 
+    let result = broadcast_graphql(&endpoint, graphql_json).await?;
+    eprintln!("GraphQL broadcast succeeded.");
+    eprintln!("Transaction hash: {}", result.tx_hash);
+    eprintln!("Status: pending");
+    let mut report = Report::new();
+    report.insert("endpoint".into(), endpoint.into());
+    report.insert("tx_hash".into(), result.tx_hash.into());
+    report.insert("tx_status".into(), "pending".into());
+
+    Ok(report)
+}
+
+/// Failure while broadcasting a GraphQL mutation.
+#[derive(Debug, thiserror::Error)]
+pub enum BroadcastError {
+    /// The node could not be reached or did not answer with a usable GraphQL response.
+    #[error("GraphQL broadcast failed: {0}")]
+    Transport(String),
+    /// The node answered but rejected the transaction in the GraphQL `errors` array.
+    #[error("transaction rejected by node: {}", .0.join("; "))]
+    Rejected(Vec<String>),
+    /// The node accepted the transaction but reported failure reasons for it.
+    #[error("transaction {tx_hash} failed: {}", .reasons.join("; "))]
+    Failed {
+        tx_hash: String,
+        reasons: Vec<String>,
+    },
+}
+
+async fn broadcast_graphql(
+    endpoint: &str,
+    graphql_json: String,
+) -> Result<BroadcastResult, BroadcastError> {
     let client = reqwest::Client::new();
     let response = client
-        .post(&endpoint)
+        .post(endpoint)
         .header("Content-Type", "application/json")
         .body(graphql_json)
         .send()
-        .await?;
+        .await
+        .map_err(|e| BroadcastError::Transport(e.to_string()))?;
+
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| BroadcastError::Transport(e.to_string()))?;
+
+    parse_graphql_response(status, &body)
+}
+
+/// Interpret a GraphQL response. Mina reports rejected transactions in an `errors` array
+/// while still answering with HTTP 200, so the status code alone is not enough. A
+/// transaction the node accepted with failure reasons is an error as well.
+fn parse_graphql_response(
+    status: StatusCode,
+    body: &str,
+) -> Result<BroadcastResult, BroadcastError> {
+    let parsed: Option<serde_json::Value> = serde_json::from_str(body).ok();
+
+    if let Some(errors) = parsed
+        .as_ref()
+        .and_then(|value| value.get("errors"))
+        .and_then(|errors| errors.as_array())
+        .filter(|errors| !errors.is_empty())
+    {
+        let messages = errors
+            .iter()
+            .map(|error| {
+                error
+                    .get("message")
+                    .and_then(|message| message.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string())
+            })
+            .collect();
+        return Err(BroadcastError::Rejected(messages));
+    }
+
+    if !status.is_success() {
+        return Err(BroadcastError::Transport(format!("{}: {}", status, body)));
+    }
+
+    if parsed.is_none() {
+        return Err(BroadcastError::Transport(format!(
+            "response is not valid JSON: {}",
+            body
+        )));
+    }
+
+    let result = parse_broadcast_response(body).map_err(|e| match e {
+        MinaTxError::BroadcastRejected(messages) => BroadcastError::Rejected(messages),
+        other => BroadcastError::Transport(other.to_string()),
+    })?;
+    match result.status {
+        BroadcastStatus::Pending => Ok(result),
+        BroadcastStatus::Failed(reasons) => Err(BroadcastError::Failed {
+            tx_hash: result.tx_hash,
+            reasons,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_broadcast_is_refused_for_another_network() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("credentials.toml");
+        let mut config =
+            Config::<BluePallasSuite>::read(Some(config_path.to_str().unwrap().to_string()))
+                .unwrap();
+        config.network = Some("testnet".to_string());
+        config.write().unwrap();
+
+        // Refused before the mutation is read or the node contacted
+        let args = Command::GraphqlBroadcast {
+            graphql_path: dir
+                .path()
+                .join("missing.json")
+                .to_str()
+                .unwrap()
+                .to_string(),
+            endpoint_url: "http://127.0.0.1:9/graphql".to_string(),
+            config: Some(config_path.to_str().unwrap().to_string()),
+            network: "mainnet".to_string(),
+            force_network: false,
+        };
+        let err = graphql_broadcast_command(&args).await.unwrap_err();
+        assert!(err.to_string().contains("--force-network"));
+    }
+
+    #[test]
+    fn test_graphql_errors_with_200_are_node_rejections() {
+        let body = r#"{
+            "errors": [
+                {"message": "Couldn't send zkApp command: Insufficient_fee", "path": ["sendZkapp"]}
+            ],
+            "data": null
+        }"#;
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await?;
-        return Err(format!("GraphQL broadcast failed ({}): {}", status, body).into());
-    } else {
-        println!("GraphQL broadcast succeeded.");
-        println!("Response: {}", response.text().await?);
+        match parse_graphql_response(StatusCode::OK, body) {
+            Err(BroadcastError::Rejected(messages)) => {
+                assert_eq!(
+                    messages,
+                    vec!["Couldn't send zkApp command: Insufficient_fee".to_string()]
+                );
+            }
+            other => panic!("expected node rejection, got {:?}", other),
+        }
     }
 
-    Ok(())
+    #[test]
+    fn test_graphql_failure_reasons_are_an_error() {
+        let body = r#"{"data": {"sendPayment": {"payment": {"hash": "5Ju...", "failureReason": "Amount_insufficient_to_create_account"}}}}"#;
+
+        match parse_graphql_response(StatusCode::OK, body) {
+            Err(BroadcastError::Failed { tx_hash, reasons }) => {
+                assert_eq!(tx_hash, "5Ju...");
+                assert_eq!(
+                    reasons,
+                    vec!["Amount_insufficient_to_create_account".to_string()]
+                );
+            }
+            other => panic!("expected a failed transaction, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_graphql_success_and_transport_failures() {
+        let body = r#"{"data": {"sendPayment": {"payment": {"hash": "5Ju..."}}}}"#;
+        let result = parse_graphql_response(StatusCode::OK, body).unwrap();
+        assert_eq!(result.tx_hash, "5Ju...");
+        assert_eq!(result.status, BroadcastStatus::Pending);
+
+        assert!(matches!(
+            parse_graphql_response(StatusCode::BAD_GATEWAY, "upstream unavailable"),
+            Err(BroadcastError::Transport(_))
+        ));
+        assert!(matches!(
+            parse_graphql_response(StatusCode::OK, "<html>not graphql</html>"),
+            Err(BroadcastError::Transport(_))
+        ));
+    }
 }
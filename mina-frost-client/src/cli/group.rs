@@ -1,36 +1,144 @@
 use std::error::Error;
 
-use eyre::OptionExt;
-use frost_core::Ciphersuite;
+use eyre::{eyre, OptionExt};
+use frost_core::{keys::PublicKeyPackage, Ciphersuite};
+use mina_tx::pallas_message::translate_pk;
 
-use super::{args::Command, config::Config};
+use super::{
+    args::Command,
+    config::Config,
+    output::{OutputFormat, Report},
+};
+use crate::BluePallasSuite;
 
-pub fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn list<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::Groups { config } = (*args).clone() else {
         panic!("invalid Command");
     };
 
     let config = Config::<C>::read(config)?;
 
+    let mut groups = Vec::new();
     for group in config.group.values() {
         eprint!("{}", group.as_human_readable_summary(&config)?);
         eprintln!();
+        groups.push(group.to_report(&config)?);
     }
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("groups".into(), groups.into());
+    Ok(report)
 }
 
 /// Remove a group from the user's config file.
-pub fn remove<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn remove<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::RemoveGroup { config, group } = (*args).clone() else {
         panic!("invalid Command");
     };
 
     let mut config = Config::<C>::read(config)?;
 
-    config.group.remove(&group).ok_or_eyre("group not found")?;
+    let group = config.group_by_id(&group)?.0.clone();
+    let removed = config.group.remove(&group).ok_or_eyre("group not found")?;
 
     config.write()?;
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("group_public_key".into(), group.into());
+    report.insert("description".into(), removed.description.clone().into());
+    Ok(report)
+}
+
+/// Print the Mina address of a group, along with the network its config was created for.
+pub fn address(args: &Command, output: OutputFormat) -> Result<Report, Box<dyn Error>> {
+    let Command::Address { config, group } = (*args).clone() else {
+        panic!("invalid Command");
+    };
+
+    let config = Config::<BluePallasSuite>::read(config)?;
+
+    let (group_id, group) = match group {
+        Some(group_id) => config.group_by_id(&group_id)?,
+        None => match config.group.len() {
+            1 => config.group.iter().next().expect("one group"),
+            0 => return Err(eyre!("no groups in the config").into()),
+            _ => {
+                return Err(eyre!(
+                    "the config holds several groups; pass --group (use `groups` to list)"
+                )
+                .into())
+            }
+        },
+    };
+
+    let public_key_package: PublicKeyPackage<BluePallasSuite> =
+        postcard::from_bytes(&group.public_key_package)?;
+    let address = translate_pk(public_key_package.verifying_key())?.into_address();
+
+    if output == OutputFormat::Text {
+        println!("{address}");
+        match &config.network {
+            Some(network) => eprintln!("Network: {network}"),
+            None => eprintln!("Network: not recorded, the address is the same on every network"),
+        }
+    }
+
+    let mut report = Report::new();
+    report.insert("group_public_key".into(), group_id.clone().into());
+    report.insert("address".into(), address.into());
+    report.insert("network".into(), config.network.clone().into());
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::tests::helpers::TestConfigs;
+
+    #[tokio::test]
+    async fn test_address_matches_group_verifying_key() {
+        let configs = TestConfigs::init(&["alice", "bob"], Some("testnet")).await;
+        configs.deal(2);
+
+        let report = address(
+            &Command::Address {
+                config: Some(configs.paths[0].clone()),
+                group: None,
+            },
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(report["network"], "testnet");
+
+        let config = Config::<BluePallasSuite>::read(Some(configs.paths[0].clone())).unwrap();
+        let group = config.group.values().next().unwrap();
+        let public_key_package: PublicKeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&group.public_key_package).unwrap();
+        let parsed =
+            mina_signer::PubKey::from_address(report["address"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            parsed,
+            translate_pk(public_key_package.verifying_key()).unwrap()
+        );
+
+        // The group can also be picked by its Mina address
+        let by_address = address(
+            &Command::Address {
+                config: Some(configs.paths[0].clone()),
+                group: Some(report["address"].as_str().unwrap().to_string()),
+            },
+            OutputFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(by_address["group_public_key"], report["group_public_key"]);
+
+        let missing = address(
+            &Command::Address {
+                config: Some(configs.paths[0].clone()),
+                group: Some("00".to_string()),
+            },
+            OutputFormat::Json,
+        );
+        assert!(missing.is_err());
+    }
 }
@@ -2,14 +2,16 @@ use std::error::Error;
 
 use crate::cipher::Cipher;
 use frost_core::Ciphersuite;
+use mina_tx::network_id::NetworkIdEnvelope;
 
 use super::{
     args::Command,
     config::{CommunicationKey, Config},
+    output::Report,
 };
 
-pub async fn init<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
-    let Command::Init { config } = (*args).clone() else {
+pub async fn init<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
+    let Command::Init { config, network } = (*args).clone() else {
         panic!("invalid Command");
     };
 
@@ -17,6 +19,17 @@ pub async fn init<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
     // We don't do this cause it would just be more boilerplate at the moment
     let mut config = Config::<C>::read(config)?;
 
+    if let Some(network) = network {
+        NetworkIdEnvelope::try_from(network.clone())?;
+        if config.network.as_ref().is_some_and(|n| *n != network) {
+            eprintln!(
+                "Changing config network from {} to {network}",
+                config.network.as_deref().unwrap_or_default()
+            );
+        }
+        config.network = Some(network);
+    }
+
     if config.communication_key.is_some() {
         eprintln!("Skipping keypair generation; keypair already generated and stored");
     } else {
@@ -36,5 +49,28 @@ pub async fn init<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
     the config file."
     );
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert(
+        "config_path".into(),
+        config
+            .path()
+            .expect("should not be None")
+            .display()
+            .to_string()
+            .into(),
+    );
+    report.insert(
+        "communication_pubkey".into(),
+        hex::encode(
+            &config
+                .communication_key
+                .as_ref()
+                .expect("set above")
+                .pubkey
+                .0,
+        )
+        .into(),
+    );
+    report.insert("network".into(), config.network.clone().into());
+    Ok(report)
 }
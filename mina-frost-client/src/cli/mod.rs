@@ -10,7 +10,13 @@ pub mod dkg;
 pub mod graphql;
 pub mod group;
 pub mod init;
+pub mod offline;
+pub mod output;
 pub mod participant;
 pub mod session;
+pub mod stdin;
 pub mod trusted_dealer;
 pub mod write_atomic;
+
+#[cfg(test)]
+pub(crate) mod tests;
@@ -0,0 +1,379 @@
+//! Signing without frostd, for signers on air-gapped machines.
+//!
+//! Each signer runs `offline-commit` and hands the commitments to the coordinator, who
+//! exports a [`SigningRequest`] with `coordinator --export-unsigned`. Signers sign it with
+//! `offline-sign`, using the nonces kept from round 1, and the coordinator combines the
+//! returned shares with `offline-aggregate`. Commitments and shares are JSON objects
+//! mapping the signer's hex-encoded identifier to the value, so files from several
+//! signers can simply be merged.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    io::{BufRead, Write},
+};
+
+use eyre::{eyre, Context};
+use frost_core::{
+    round1::{SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+    Identifier,
+};
+use mina_tx::network_id::NetworkIdEnvelope;
+use rand::thread_rng;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::{
+    args::Command,
+    config::Config as ConfigFile,
+    coordinator::{save_signature, signature_report},
+    output::{OutputFormat, Report},
+    participant::{key_package_env, load_participant_config},
+    stdin, write_atomic,
+};
+use crate::{
+    api::SendSigningPackageArgs,
+    cancel::CancellationToken,
+    coordinator::coordinate_signing::aggregate_and_verify,
+    participant::comms::{prompt_confirmation, Confirmation},
+    signing_request::SigningRequest,
+    BluePallasSuite,
+};
+
+/// Round 1 nonces kept by a signer between `offline-commit` and `offline-sign`.
+#[derive(Serialize, Deserialize)]
+struct NoncesFile {
+    /// The hex-encoded public key of the group the nonces were generated for.
+    group: String,
+    nonces: SigningNonces<BluePallasSuite>,
+}
+
+/// CLI entry point for `offline-commit`.
+pub fn commit(args: &Command) -> Result<Report, Box<dyn Error>> {
+    let Command::OfflineCommit {
+        config,
+        group,
+        nonces: nonces_path,
+        commitments: commitments_path,
+    } = (*args).clone()
+    else {
+        panic!("invalid Command");
+    };
+
+    let (_, _, key_package) = load_participant_config::<BluePallasSuite>(
+        config,
+        &group,
+        key_package_env().as_deref().map(String::as_str),
+    )?;
+    let (nonces, commitments) =
+        frost_bluepallas::round1::commit(key_package.signing_share(), &mut thread_rng());
+    let nonces_file = NoncesFile {
+        group: hex::encode(key_package.verifying_key().serialize()?),
+        nonces,
+    };
+    let json = Zeroizing::new(serde_json::to_string(&nonces_file)?);
+    write_atomic::write_file(&nonces_path, json.as_bytes())?;
+    eprintln!("Nonces saved to {nonces_path}; keep this file on this machine");
+
+    let commitments = BTreeMap::from([(*key_package.identifier(), commitments)]);
+    write_output(
+        &commitments_path,
+        &serde_json::to_string_pretty(&commitments)?,
+    )?;
+
+    let mut report = Report::new();
+    report.insert(
+        "identifier".into(),
+        hex::encode(key_package.identifier().serialize()).into(),
+    );
+    report.insert("nonces_path".into(), nonces_path.into());
+    report.insert("commitments".into(), serde_json::to_value(&commitments)?);
+    Ok(report)
+}
+
+/// CLI entry point for `offline-sign`.
+pub fn sign(args: &Command) -> Result<Report, Box<dyn Error>> {
+    let Command::OfflineSign { stdin_timeout, .. } = args else {
+        panic!("invalid Command");
+    };
+
+    // Not a session command: Ctrl-C simply ends the process
+    let mut input = stdin::input(
+        *stdin_timeout,
+        "a y/n answer to the signing prompt",
+        &CancellationToken::new(),
+    );
+    sign_with_input(args, &mut input, &mut std::io::stderr())
+}
+
+/// Same as [`sign`], reading the confirmation answer from `input` and writing the prompt
+/// to `output`.
+pub fn sign_with_input(
+    args: &Command,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<Report, Box<dyn Error>> {
+    let Command::OfflineSign {
+        config,
+        group,
+        request: request_path,
+        nonces: nonces_path,
+        share: share_path,
+        network,
+        yes,
+        force_network,
+        stdin_timeout: _,
+    } = (*args).clone()
+    else {
+        panic!("invalid Command");
+    };
+
+    let (user_config, _, key_package) = load_participant_config::<BluePallasSuite>(
+        config,
+        &group,
+        key_package_env().as_deref().map(String::as_str),
+    )?;
+    user_config.check_network(&network, force_network)?;
+    let network_id = NetworkIdEnvelope::try_from(network)?.0;
+
+    let request = SigningRequest::from_json(
+        &fs::read_to_string(&request_path)
+            .wrap_err(format!("error reading signing request {request_path}"))?,
+    )?;
+    let json = Zeroizing::new(
+        fs::read_to_string(&nonces_path)
+            .wrap_err(format!("error reading nonces file {nonces_path}"))?,
+    );
+    let nonces_file: NoncesFile =
+        serde_json::from_str(&json).map_err(|_| eyre!("invalid nonces file {nonces_path}"))?;
+    if nonces_file.group != hex::encode(key_package.verifying_key().serialize()?) {
+        return Err(eyre!("the nonces in {nonces_path} were generated for another group").into());
+    }
+
+    let confirmation = if yes {
+        Confirmation::Skip
+    } else {
+        Confirmation::Prompt
+    };
+    prompt_confirmation(
+        input,
+        output,
+        &SendSigningPackageArgs {
+            signing_package: vec![request.signing_package.clone()],
+            aux_msg: Vec::new(),
+        },
+        confirmation,
+    )?;
+
+    let share = request.sign(
+        &network_id,
+        &key_package,
+        &nonces_file.nonces,
+        user_config.used_nonces_path().as_deref(),
+    )?;
+    let shares = BTreeMap::from([(*key_package.identifier(), share)]);
+    write_output(&share_path, &serde_json::to_string_pretty(&shares)?)?;
+    // The nonces are spent; signing again with them would leak the key
+    fs::remove_file(&nonces_path)?;
+
+    let mut report = Report::new();
+    report.insert(
+        "identifier".into(),
+        hex::encode(key_package.identifier().serialize()).into(),
+    );
+    report.insert("signature_share".into(), serde_json::to_value(&shares)?);
+    Ok(report)
+}
+
+/// CLI entry point for `offline-aggregate`.
+pub fn aggregate(args: &Command, output: OutputFormat) -> Result<Report, Box<dyn Error>> {
+    let Command::OfflineAggregate {
+        config,
+        group,
+        request: request_path,
+        shares: share_paths,
+        signature: signature_path,
+    } = (*args).clone()
+    else {
+        panic!("invalid Command");
+    };
+
+    let user_config = ConfigFile::<BluePallasSuite>::read(config)?;
+    let (_, group_config) = user_config.group_by_id(&group)?;
+    let public_key_package: frost_core::keys::PublicKeyPackage<BluePallasSuite> =
+        postcard::from_bytes(&group_config.public_key_package)?;
+
+    let request = SigningRequest::from_json(
+        &fs::read_to_string(&request_path)
+            .wrap_err(format!("error reading signing request {request_path}"))?,
+    )?;
+    let mut shares = BTreeMap::new();
+    for path in &share_paths {
+        shares.extend(read_identified::<SignatureShare<BluePallasSuite>>(path)?);
+    }
+    if let Some(missing) = request
+        .participants
+        .iter()
+        .find(|identifier| !shares.contains_key(*identifier))
+    {
+        return Err(eyre!(
+            "no signature share from participant {}",
+            hex::encode(missing.serialize())
+        )
+        .into());
+    }
+
+    let transaction = request.transaction()?;
+    let commitments: BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>> =
+        request.signing_package.signing_commitments().clone();
+    let signature = aggregate_and_verify(&transaction, commitments, &shares, &public_key_package)?;
+
+    let transaction_signature = save_signature(
+        &signature_path,
+        signature.serialize()?,
+        transaction,
+        *public_key_package.verifying_key(),
+        output,
+    )?;
+    signature_report(&transaction_signature, &signature_path)
+}
+
+/// Read a JSON object mapping hex-encoded identifiers to values, as written by
+/// `offline-commit` and `offline-sign`.
+fn read_identified<T: DeserializeOwned>(
+    path: &str,
+) -> Result<BTreeMap<Identifier<BluePallasSuite>, T>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path).wrap_err(format!("error reading {path}"))?;
+    Ok(serde_json::from_str(&contents).wrap_err(format!("invalid file {path}"))?)
+}
+
+/// Write `contents` to `path`, or to stdout if `path` is "-".
+fn write_output(path: &str, contents: &str) -> Result<(), Box<dyn Error>> {
+    if path == "-" {
+        println!("{contents}");
+    } else {
+        fs::write(path, contents)?;
+        eprintln!("Written to {path}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::cli::{coordinator::export_signing_request_with_input, tests::helpers::TestConfigs};
+    use mina_tx::{legacy_tx::LegacyTransaction, pallas_message::translate_pk};
+
+    #[tokio::test]
+    async fn test_offline_commit_sign_and_aggregate() {
+        let fixture = TestConfigs::init(&["alice", "bob", "carol"], None).await;
+        let group_id = fixture.deal(2);
+        let path = |name: &str| fixture.path(name);
+        let configs = &fixture.paths;
+        let alice = ConfigFile::<BluePallasSuite>::read(Some(configs[0].clone())).unwrap();
+        let group = &alice.group[&group_id];
+        let public_key_package: frost_core::keys::PublicKeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&group.public_key_package).unwrap();
+
+        // Alice and Bob commit on their own machines
+        let signers = [("alice", &configs[0]), ("bob", &configs[1])];
+        for (name, config) in signers {
+            commit(&Command::OfflineCommit {
+                config: Some(config.clone()),
+                group: group_id.clone(),
+                nonces: path(&format!("{name}.nonces.json")),
+                commitments: path(&format!("{name}.commitments.json")),
+            })
+            .unwrap();
+        }
+
+        // Carol coordinates and exports the request
+        let group_pk = translate_pk(public_key_package.verifying_key()).unwrap();
+        let address = group_pk.clone().into_address();
+        let transaction =
+            LegacyTransaction::new_payment(group_pk.clone(), group_pk, 1_000, 1_000, 0);
+        fs::write(
+            path("tx.json"),
+            serde_json::to_string(&transaction).unwrap(),
+        )
+        .unwrap();
+        let export = Command::Coordinator {
+            config: Some(configs[2].clone()),
+            server_url: None,
+            group: group_id.clone(),
+            signers: Vec::new(),
+            message: path("tx.json"),
+            signature: String::new(),
+            network: "testnet".to_string(),
+            memo_hex: None,
+            fee: None,
+            fee_auto: None,
+            fee_tier: crate::cli::args::FeeTierArg::Medium,
+            participants_file: None,
+            force_network: false,
+            stdin_timeout: None,
+            export_unsigned: Some(path("request.json")),
+            commitments_file: vec![path("alice.commitments.json"), path("bob.commitments.json")],
+            audit_log: None,
+        };
+        export_signing_request_with_input(
+            &export,
+            &path("request.json"),
+            &mut std::io::sink(),
+            &mut std::io::empty(),
+        )
+        .await
+        .unwrap();
+
+        let offline_sign = |name: &str, config: &String, answer: &str| {
+            let command = Command::OfflineSign {
+                config: Some(config.clone()),
+                group: group_id.clone(),
+                request: path("request.json"),
+                nonces: path(&format!("{name}.nonces.json")),
+                share: path(&format!("{name}.share.json")),
+                network: "testnet".to_string(),
+                yes: false,
+                force_network: false,
+                stdin_timeout: None,
+            };
+            let mut output = Vec::new();
+            let result = sign_with_input(&command, &mut answer.as_bytes(), &mut output);
+            (result, String::from_utf8(output).unwrap())
+        };
+
+        // Declining leaves the nonces in place for a later answer
+        let (result, prompt) = offline_sign("alice", &configs[0], "n\n");
+        assert!(result.is_err());
+        assert!(prompt.contains("Do you want to sign it? (y/n)"));
+        for (name, config) in signers {
+            offline_sign(name, config, "y\n").0.unwrap();
+            assert!(!Path::new(&path(&format!("{name}.nonces.json"))).exists());
+        }
+        // The nonces are gone, so the request cannot be signed a second time
+        assert!(offline_sign("alice", &configs[0], "y\n").0.is_err());
+
+        let aggregate_with = |shares: Vec<String>| {
+            aggregate(
+                &Command::OfflineAggregate {
+                    config: Some(configs[2].clone()),
+                    group: group_id.clone(),
+                    request: path("request.json"),
+                    shares,
+                    signature: path("signature.json"),
+                },
+                OutputFormat::Json,
+            )
+        };
+        assert!(aggregate_with(vec![path("alice.share.json")]).is_err());
+        let report =
+            aggregate_with(vec![path("alice.share.json"), path("bob.share.json")]).unwrap();
+        assert_eq!(report["signature_path"], path("signature.json"));
+        assert_eq!(report["address"], address);
+        assert!(Path::new(&path("signature.json")).exists());
+    }
+}
@@ -0,0 +1,90 @@
+//! Machine-readable command output for `--output json`.
+
+use std::error::Error;
+
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+/// How command results are reported on stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-oriented text.
+    #[default]
+    Text,
+    /// A single JSON object with a `status` field.
+    Json,
+}
+
+/// Fields a command reports about its result, e.g. the produced signature or group address.
+pub type Report = Map<String, Value>;
+
+/// Render a command result as `{ "status": "ok", ... }` or
+/// `{ "status": "error", "message": "..." }`.
+pub fn render_json(result: &Result<Report, Box<dyn Error>>) -> Value {
+    match result {
+        Ok(fields) => {
+            let mut out = Report::new();
+            out.insert("status".to_string(), json!("ok"));
+            out.extend(fields.clone());
+            Value::Object(out)
+        }
+        Err(e) => json!({
+            "status": "error",
+            "message": error_chain(e.as_ref()),
+        }),
+    }
+}
+
+/// The message of `err` followed by those of its sources, separated by `": "`.
+///
+/// Wrapping errors such as `FrostBluePallasError` only describe their own layer and leave
+/// the cause to `source()`, so printing `err` alone would drop it.
+pub fn error_chain(err: &(dyn Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use frost_bluepallas::errors::FrostBluePallasError;
+    use mina_tx::pallas_message::PallasMessage;
+
+    use super::*;
+
+    #[test]
+    fn test_render_json_ok_includes_fields() {
+        let mut report = Report::new();
+        report.insert("address".to_string(), json!("B62q..."));
+        let value = render_json(&Ok(report));
+        assert_eq!(value, json!({ "status": "ok", "address": "B62q..." }));
+    }
+
+    #[test]
+    fn test_render_json_error_has_message() {
+        let value = render_json(&Err("group not found".into()));
+        assert_eq!(
+            value,
+            json!({ "status": "error", "message": "group not found" })
+        );
+    }
+
+    #[test]
+    fn test_render_json_error_includes_sources() {
+        let err: FrostBluePallasError<PallasMessage> =
+            frost_bluepallas::Error::<PallasMessage>::InvalidSignature.into();
+        let value = render_json(&Err(err.into()));
+        assert_eq!(
+            value["message"],
+            format!(
+                "FROST operation failed: {}",
+                frost_bluepallas::Error::<PallasMessage>::InvalidSignature
+            )
+        );
+    }
+}
@@ -1,39 +1,67 @@
 use std::error::Error;
+use std::io::Write;
 use std::rc::Rc;
 
+use crate::cancel::CancellationToken;
 use crate::BluePallasSuite;
+use eyre::eyre;
 use eyre::Context;
 use eyre::OptionExt;
 use reqwest::Url;
+use zeroize::Zeroizing;
 
-use frost_core::keys::KeyPackage;
+use frost_core::keys::{KeyPackage, PublicKeyPackage};
 use frost_core::Ciphersuite;
+use mina_tx::network_id::NetworkId;
 
-use super::{args::Command, config::Config as ConfigFile};
+use super::{
+    args::Command, ciphersuite_helper::ciphersuite_helper, config::Config as ConfigFile,
+    output::Report, stdin,
+};
 
-use crate::cli::config::{Group, Participant};
-use crate::participant::sign;
+use crate::cli::config::{check_network, Group, Participant};
 use crate::participant::Config as ParticipantConfig;
+use crate::participant::{sign, Confirmation, SigningDeclined};
+
+/// Environment variable holding the participant's key file contents, used for groups
+/// whose entry in the config file has an empty key package.
+pub const KEY_PACKAGE_ENV: &str = "FROST_KEY_PACKAGE";
 
 /// CLI entry point for participant signing
-pub async fn run_bluepallas(args: &Command) -> Result<(), Box<dyn Error>> {
+///
+/// Cancelling `shutdown` aborts the session along with any request in flight.
+pub async fn run_bluepallas(
+    args: &Command,
+    shutdown: &CancellationToken,
+) -> Result<Report, Box<dyn Error>> {
     let Command::Participant {
         config: config_path,
         server_url,
         group,
         session,
         yes,
+        confirm,
+        force_network,
+        stdin_timeout,
     } = (*args).clone()
     else {
         panic!("invalid Command");
     };
 
-    let mut input = Box::new(std::io::stdin().lock());
+    let mut input = stdin::input(
+        stdin_timeout,
+        "a y/n answer to the signing prompt",
+        shutdown,
+    );
     let mut output = std::io::stdout();
 
     // Load and validate configuration
-    let (user_config, group_config, key_package) =
-        load_participant_config::<BluePallasSuite>(config_path, &group)?;
+    let (user_config, group_config, key_package) = load_participant_config::<BluePallasSuite>(
+        config_path,
+        &group,
+        key_package_env().as_deref().map(String::as_str),
+    )?;
+    let group_public_key = hex::encode(key_package.verifying_key().serialize()?);
 
     // Setup participant configuration
     let participant_config = setup_participant_config::<BluePallasSuite>(
@@ -42,12 +70,38 @@ pub async fn run_bluepallas(args: &Command) -> Result<(), Box<dyn Error>> {
         key_package,
         server_url,
         &session,
+        force_network,
     )?;
 
+    let confirmation = if yes {
+        Confirmation::Skip
+    } else if confirm {
+        Confirmation::Detailed
+    } else {
+        Confirmation::Prompt
+    };
+
     // Execute signing
-    sign(participant_config, &mut input, &mut output, yes).await?;
+    let result = sign(
+        participant_config,
+        &mut input,
+        &mut output,
+        confirmation,
+        shutdown,
+    )
+    .await;
+    // Declining still fails the command, after the coordinator was told to abort
+    if let Err(err) = result {
+        if err.downcast_ref::<SigningDeclined>().is_some() {
+            writeln!(output, "Signing declined; no signature share was sent.")?;
+        }
+        return Err(err);
+    }
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("group_public_key".into(), group_public_key.into());
+    report.insert("session_id".into(), session.into());
+    Ok(report)
 }
 
 // Avoid clippy warnings about complex return types
@@ -57,34 +111,76 @@ type LoadParticipantConfigResult<C> =
 /// Load and validate participant configuration
 ///
 /// This function reads the user config file, extracts the specified group,
-/// and deserializes the key package.
-fn load_participant_config<C: Ciphersuite>(
+/// and deserializes the key package. A key package in the config file takes
+/// precedence; if the group has none, it is read from `key_package_env`, the
+/// contents of [`KEY_PACKAGE_ENV`] as returned by [`key_package_env`].
+pub(crate) fn load_participant_config<C: Ciphersuite>(
     config_path: Option<String>,
     group_id: &str,
+    key_package_env: Option<&str>,
 ) -> LoadParticipantConfigResult<C> {
     let user_config = ConfigFile::read(config_path)?;
 
-    let group_config = user_config
-        .group
-        .get(group_id)
-        .ok_or_eyre("Group not found")?
-        .clone();
+    let group_config = user_config.group_by_id(group_id)?.1.clone();
 
-    let key_package: KeyPackage<C> = postcard::from_bytes(&group_config.key_package)?;
+    let key_package: KeyPackage<C> = if group_config.key_package.is_empty() {
+        key_package_from_env(&group_config, key_package_env)?
+    } else {
+        postcard::from_bytes(&group_config.key_package)?
+    };
 
     Ok((user_config, group_config, key_package))
 }
 
+/// The contents of [`KEY_PACKAGE_ENV`], if it is set.
+pub(crate) fn key_package_env() -> Option<Zeroizing<String>> {
+    std::env::var(KEY_PACKAGE_ENV).ok().map(Zeroizing::new)
+}
+
+/// Parse the key package passed in [`KEY_PACKAGE_ENV`] and check it belongs to `group`.
+///
+/// The variable holds the contents of a key file as written by `trusted-dealer
+/// --key-package-dir` or `dkg --key-package-out`; unversioned key package JSON is accepted
+/// too.
+///
+/// The variable holds a secret, so errors name it but never quote its contents.
+fn key_package_from_env<C: Ciphersuite>(
+    group: &Group<C>,
+    json: Option<&str>,
+) -> Result<KeyPackage<C>, Box<dyn Error>> {
+    let json = json.ok_or_else(|| {
+        eyre!("the group has no key package in the config file and {KEY_PACKAGE_ENV} is not set")
+    })?;
+    let key_package = ciphersuite_helper::<C>()
+        .import_key_package(json)
+        .map_err(|_| eyre!("{KEY_PACKAGE_ENV} is not a valid key package file"))?;
+
+    let public_key_package: PublicKeyPackage<C> = postcard::from_bytes(&group.public_key_package)?;
+    let share = public_key_package
+        .verifying_shares()
+        .get(key_package.identifier());
+    if key_package.verifying_key() != public_key_package.verifying_key()
+        || share != Some(key_package.verifying_share())
+    {
+        return Err(eyre!("{KEY_PACKAGE_ENV} holds a key package for another group").into());
+    }
+
+    Ok(key_package)
+}
+
 /// Setup participant configuration for signing
 ///
 /// This function constructs the ParticipantConfig with all necessary parameters
 /// including network settings, keys, and coordinator lookup functionality.
+/// Transactions for another network than the config's are refused unless
+/// `force_network` is set.
 fn setup_participant_config<C: Ciphersuite>(
     user_config: &ConfigFile<C>,
     group_config: &Group<C>,
     key_package: KeyPackage<C>,
     server_url: Option<String>,
     session: &str,
+    force_network: bool,
 ) -> Result<ParticipantConfig<C>, Box<dyn Error>> {
     // Determine server URL
     let server_url = if let Some(server_url) = server_url {
@@ -131,11 +227,34 @@ fn setup_participant_config<C: Ciphersuite>(
                 .clone(),
         ),
         comm_coordinator_pubkey_getter: Some(coordinator_pubkey_getter),
+        used_nonces_path: user_config.used_nonces_path(),
+        network_check: Some(create_network_check(
+            user_config.network.clone(),
+            force_network,
+        )),
     };
 
     Ok(participant_config)
 }
 
+/// Type alias for the network check run on every signing package
+type NetworkCheck = Rc<dyn Fn(&NetworkId) -> Result<(), Box<dyn Error>>>;
+
+/// Create the network check for the participant
+///
+/// This function creates a closure that refuses transactions for another network than
+/// `configured`, the one recorded in the config file.
+fn create_network_check(configured: Option<String>, force_network: bool) -> NetworkCheck {
+    Rc::new(move |network| {
+        let requested = match network {
+            NetworkId::Testnet => "testnet",
+            NetworkId::Mainnet => "mainnet",
+            NetworkId::Custom(name) => name,
+        };
+        check_network(configured.as_deref(), requested, force_network)
+    })
+}
+
 /// Type alias for coordinator public key getter function
 type CoordinatorPubkeyGetter = Rc<dyn Fn(&crate::api::PublicKey) -> Option<crate::api::PublicKey>>;
 
@@ -153,3 +272,105 @@ fn create_coordinator_pubkey_getter(
             .map(|p| p.pubkey.clone())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::Cipher;
+    use crate::cli::{tests::helpers::TestConfigs, trusted_dealer};
+    use crate::mock_frostd::{MockFrostd, TestGroup};
+
+    #[tokio::test]
+    async fn test_key_package_from_env_signs_without_key_in_config() {
+        let configs = TestConfigs::init(&["alice", "bob"], None).await;
+        let key_dir = configs.path("keys");
+        // With a key package directory, the config files get group entries without keys
+        let mut command = configs.trusted_dealer(2);
+        if let Command::TrustedDealer {
+            key_package_dir, ..
+        } = &mut command
+        {
+            *key_package_dir = Some(key_dir.clone());
+        }
+        let report = trusted_dealer::run::<BluePallasSuite>(&command).unwrap();
+        let group_id = report["group_public_key"].as_str().unwrap().to_string();
+        let key_file =
+            |name: &str| std::fs::read_to_string(format!("{key_dir}/{name}.json")).unwrap();
+        let load = |config: &String, key_package_env: Option<&str>| {
+            load_participant_config::<BluePallasSuite>(
+                Some(config.clone()),
+                &group_id,
+                key_package_env,
+            )
+        };
+
+        let err = load(&configs.paths[0], None).unwrap_err();
+        assert!(err.to_string().contains(KEY_PACKAGE_ENV));
+
+        let err = load(&configs.paths[0], Some("{\"secret\": \"do not print\"}")).unwrap_err();
+        assert!(!err.to_string().contains("do not print"));
+
+        // Bob's key package does not pass for alice's
+        let (_, _, bob_key_package) =
+            load(&configs.paths[1], Some(key_file("bob").as_str())).unwrap();
+        let (_, alice_group, alice_key_package) =
+            load(&configs.paths[0], Some(key_file("alice").as_str())).unwrap();
+        assert!(alice_group.key_package.is_empty());
+        let other = KeyPackage::new(
+            *alice_key_package.identifier(),
+            *bob_key_package.signing_share(),
+            *bob_key_package.verifying_share(),
+            *bob_key_package.verifying_key(),
+            *bob_key_package.min_signers(),
+        );
+        assert!(load(
+            &configs.paths[0],
+            Some(serde_json::to_string(&other).unwrap().as_str())
+        )
+        .is_err());
+
+        // Both sign a session over frostd with the key packages loaded this way
+        let public_key_package: PublicKeyPackage<BluePallasSuite> =
+            postcard::from_bytes(&alice_group.public_key_package).unwrap();
+        let group = TestGroup::from_key_packages(
+            public_key_package,
+            [alice_key_package, bob_key_package],
+            1_000,
+        );
+        let server = MockFrostd::start().await;
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+        let shutdown = CancellationToken::new();
+        let participate = |index: usize| {
+            server.participate(
+                server.client(),
+                group.signers[index].clone(),
+                coordinator_keys.1.clone(),
+                None,
+                &shutdown,
+            )
+        };
+        let (signature, first, second) = tokio::join!(
+            server.coordinate(server.client(), &config, &shutdown),
+            participate(0),
+            participate(1),
+        );
+        first.unwrap();
+        second.unwrap();
+        group.assert_signed(&signature.unwrap());
+    }
+
+    #[test]
+    fn test_participant_refuses_transactions_for_another_network() {
+        let check = create_network_check(Some("testnet".to_string()), false);
+        let err = check(&NetworkId::Mainnet).unwrap_err();
+        assert!(err.to_string().contains("--force-network"));
+        assert!(check(&NetworkId::Testnet).is_ok());
+
+        assert!(
+            create_network_check(Some("testnet".to_string()), true)(&NetworkId::Mainnet).is_ok()
+        );
+        // Configs from before networks were recorded accept any network
+        assert!(create_network_check(None, false)(&NetworkId::Mainnet).is_ok());
+    }
+}
@@ -4,11 +4,24 @@ use eyre::{eyre, OptionExt as _};
 use frost_core::Ciphersuite;
 use rand::thread_rng;
 
-use crate::{api, client::Client};
-
-use super::{args::Command, config::Config};
+use crate::{
+    api,
+    cancel::{cancellable, CancellationToken},
+    client::Client,
+};
+
+use super::{args::Command, config::Config, output::Report};
+
+/// List the active sessions on the server, closing them if requested. Cancelling
+/// `shutdown` stops at the next request.
+pub async fn list<C: Ciphersuite>(
+    args: &Command,
+    shutdown: &CancellationToken,
+) -> Result<Report, Box<dyn Error>> {
+    cancellable(shutdown, "session listing", list_sessions::<C>(args)).await
+}
 
-pub async fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+async fn list_sessions<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
     let Command::Sessions {
         config,
         group,
@@ -24,7 +37,7 @@ pub async fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
     let server_url = if let Some(server_url) = server_url {
         server_url
     } else if let Some(group) = group {
-        let group = config.group.get(&group).ok_or_eyre("Group not found")?;
+        let (_, group) = config.group_by_id(&group)?;
         group
             .server_url
             .clone()
@@ -65,6 +78,7 @@ pub async fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
     // Get session ID from server
     let r = client.list_sessions().await?;
 
+    let mut sessions = Vec::new();
     if r.session_ids.is_empty() {
         eprintln!("No active sessions.");
     } else {
@@ -86,6 +100,12 @@ pub async fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
                     .unwrap_or("(Unknown contact)".to_string())
             );
             eprintln!("Signers: {}", participants.len());
+            sessions.push(serde_json::json!({
+                "session_id": session_id.to_string(),
+                "coordinator": hex::encode(&r.coordinator_pubkey.0),
+                "signers": r.pubkeys.iter().map(|p| hex::encode(&p.0)).collect::<Vec<_>>(),
+                "closed": close_all,
+            }));
             for participant in participants {
                 if let Ok(participant) = participant {
                     eprintln!(
@@ -107,5 +127,7 @@ pub async fn list<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>>
         }
     }
 
-    Ok(())
+    let mut report = Report::new();
+    report.insert("sessions".into(), sessions.into());
+    Ok(report)
 }
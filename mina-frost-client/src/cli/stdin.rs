@@ -0,0 +1,192 @@
+//! Reading standard input with an optional inactivity timeout.
+//!
+//! Commands that prompt on stdin otherwise block forever when a script forgets
+//! to pipe input into them. Stdin is read on a background thread, so that a
+//! prompt also gives up as soon as the session it belongs to is cancelled
+//! instead of holding the async command up until the user answers.
+
+use std::{
+    io::{self, BufRead, Read},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::cancel::{CancellationToken, Cancelled};
+
+/// How often a pending read checks whether it was cancelled.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Return a reader over stdin which fails after `timeout_secs` seconds without
+/// input, if given, or once `shutdown` is cancelled.
+///
+/// `expected` describes what the command expects to read and is included in the
+/// timeout error.
+pub fn input(
+    timeout_secs: Option<u64>,
+    expected: &str,
+    shutdown: &CancellationToken,
+) -> Box<dyn BufRead> {
+    Box::new(
+        TimeoutReader::new(io::stdin(), timeout_secs.map(Duration::from_secs), expected)
+            .cancelled_by(shutdown.clone()),
+    )
+}
+
+/// A [`BufRead`] which reads from the wrapped reader on a background thread and
+/// returns an [`io::ErrorKind::TimedOut`] error if no data arrives in time.
+pub struct TimeoutReader {
+    chunks: Receiver<io::Result<Vec<u8>>>,
+    buffer: Vec<u8>,
+    position: usize,
+    timeout: Option<Duration>,
+    shutdown: Option<CancellationToken>,
+    expected: String,
+}
+
+impl TimeoutReader {
+    /// Wait at most `timeout` for each chunk of input, or indefinitely if None.
+    pub fn new<R: Read + Send + 'static>(
+        mut reader: R,
+        timeout: Option<Duration>,
+        expected: &str,
+    ) -> Self {
+        let (sender, chunks) = mpsc::channel();
+        thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            loop {
+                let result = reader.read(&mut chunk).map(|n| chunk[..n].to_vec());
+                let done = !matches!(&result, Ok(data) if !data.is_empty());
+                if sender.send(result).is_err() || done {
+                    break;
+                }
+            }
+        });
+        Self {
+            chunks,
+            buffer: Vec::new(),
+            position: 0,
+            timeout,
+            shutdown: None,
+            expected: expected.to_string(),
+        }
+    }
+
+    /// Stop waiting for input, failing with [`Cancelled`], once `shutdown` is cancelled.
+    pub fn cancelled_by(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Wait for the next chunk from the reader thread, or None at end of input.
+    fn next_chunk(&self) -> io::Result<Option<Vec<u8>>> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let wait = match deadline {
+                Some(deadline) => deadline
+                    .saturating_duration_since(Instant::now())
+                    .min(CANCEL_POLL_INTERVAL),
+                None => CANCEL_POLL_INTERVAL,
+            };
+            match self.chunks.recv_timeout(wait) {
+                Ok(chunk) => return chunk.map(Some),
+                // The reader thread has finished, so this is end of input
+                Err(RecvTimeoutError::Disconnected) => return Ok(None),
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            // Not `Interrupted`, which `read_line` and friends would retry forever
+            if self
+                .shutdown
+                .as_ref()
+                .is_some_and(CancellationToken::is_cancelled)
+            {
+                return Err(io::Error::other(Cancelled("reading stdin")));
+            }
+            if let (Some(timeout), Some(deadline)) = (self.timeout, deadline) {
+                if Instant::now() >= deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!(
+                            "no input received on stdin within {} seconds; expected {}",
+                            timeout.as_secs(),
+                            self.expected
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+impl Read for TimeoutReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for TimeoutReader {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position >= self.buffer.len() {
+            self.buffer = self.next_chunk()?.unwrap_or_default();
+            self.position = 0;
+        }
+        Ok(&self.buffer[self.position..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.position = (self.position + amt).min(self.buffer.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_timeout_fires_when_pipe_never_writes() {
+        let (reader, _writer) = io::pipe().unwrap();
+        let mut input =
+            TimeoutReader::new(reader, Some(Duration::from_millis(100)), "a transaction");
+
+        let mut line = String::new();
+        let err = input.read_line(&mut line).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(err.to_string().contains("expected a transaction"));
+    }
+
+    #[test]
+    fn test_lines_are_read_before_timeout() {
+        let (reader, mut writer) = io::pipe().unwrap();
+        writer.write_all(b"y\nsecond\n").unwrap();
+        drop(writer);
+        let mut input = TimeoutReader::new(reader, Some(Duration::from_secs(5)), "a confirmation");
+
+        let lines: Vec<String> = input.by_ref().lines().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["y", "second"]);
+    }
+
+    #[test]
+    fn test_pending_read_stops_when_cancelled() {
+        let (reader, _writer) = io::pipe().unwrap();
+        let shutdown = CancellationToken::new();
+        let mut input =
+            TimeoutReader::new(reader, None, "a confirmation").cancelled_by(shutdown.clone());
+
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            shutdown.cancel();
+        });
+        let mut line = String::new();
+        let err = input.read_line(&mut line).unwrap_err();
+        assert!(err
+            .get_ref()
+            .is_some_and(|inner| inner.downcast_ref::<Cancelled>().is_some()));
+        canceller.join().unwrap();
+    }
+}
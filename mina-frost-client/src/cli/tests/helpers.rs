@@ -0,0 +1,62 @@
+use tempfile::TempDir;
+
+use crate::{
+    cli::{args::Command, init::init, trusted_dealer},
+    BluePallasSuite,
+};
+
+/// Initialized config files in a temporary directory, removed when this is dropped.
+pub struct TestConfigs {
+    dir: TempDir,
+    pub names: Vec<String>,
+    pub paths: Vec<String>,
+}
+
+impl TestConfigs {
+    /// Run `init` for a config file named after each of `names`, recording `network`.
+    pub async fn init(names: &[&str], network: Option<&str>) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+        let mut paths = Vec::new();
+        for name in &names {
+            let path = dir.path().join(format!("{name}.toml"));
+            let path = path.to_str().unwrap().to_string();
+            init::<BluePallasSuite>(&Command::Init {
+                config: Some(path.clone()),
+                network: network.map(String::from),
+            })
+            .await
+            .unwrap();
+            paths.push(path);
+        }
+        Self { dir, names, paths }
+    }
+
+    /// Path of `file` in the temporary directory.
+    pub fn path(&self, file: &str) -> String {
+        self.dir.path().join(file).to_str().unwrap().to_string()
+    }
+
+    /// A `trusted-dealer` command generating one `threshold`-of-n group of everyone,
+    /// without prompting.
+    pub fn trusted_dealer(&self, threshold: u16) -> Command {
+        Command::TrustedDealer {
+            config: self.paths.clone(),
+            description: "test group".to_string(),
+            names: self.names.clone(),
+            server_url: None,
+            threshold,
+            ciphersuite: "bluepallas".to_string(),
+            count: 1,
+            yes: true,
+            key_package_dir: None,
+        }
+    }
+
+    /// Run [`TestConfigs::trusted_dealer`] and return the new group's ID.
+    pub fn deal(&self, threshold: u16) -> String {
+        let report =
+            trusted_dealer::run::<BluePallasSuite>(&self.trusted_dealer(threshold)).unwrap();
+        report["group_public_key"].as_str().unwrap().to_string()
+    }
+}
@@ -1,23 +1,62 @@
-use std::{collections::BTreeMap, error::Error, marker::PhantomData};
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    io::{BufRead, Write},
+    marker::PhantomData,
+};
 
 use eyre::{eyre, OptionExt};
 use itertools::izip;
-use rand::thread_rng;
+use rand::{rngs::ThreadRng, thread_rng};
 
 use frost_core::{keys::KeyPackage, Ciphersuite};
 
 use super::{
     args::Command,
+    ciphersuite_helper::{ciphersuite_helper, GroupInfo},
     config::{Config, Group, Participant},
     contact::Contact,
+    output::Report,
+    stdin, write_atomic,
 };
 
-use crate::trusted_dealer;
+use crate::{cancel::CancellationToken, trusted_dealer, BluePallasSuite};
 
 /// Type alias for participant extraction result
 type ParticipantExtractionResult =
     Result<(BTreeMap<String, Participant>, Vec<Contact>), Box<dyn Error>>;
 
+/// Signature of a trusted dealer run for one concrete ciphersuite.
+type TrustedDealerRunner = fn(&Command) -> Result<Report, Box<dyn Error>>;
+
+/// Ciphersuites the trusted dealer can generate keys for, by `--ciphersuite` name.
+const CIPHERSUITES: &[(&str, TrustedDealerRunner)] = &[("bluepallas", run::<BluePallasSuite>)];
+
+/// CLI entry point for trusted dealer key generation, dispatching to [`run`] for
+/// the ciphersuite selected with `--ciphersuite`.
+pub fn run_with_ciphersuite(args: &Command) -> Result<Report, Box<dyn Error>> {
+    let Command::TrustedDealer { ciphersuite, .. } = args else {
+        panic!("invalid Command");
+    };
+
+    let (_, runner) = CIPHERSUITES
+        .iter()
+        .find(|(name, _)| *name == ciphersuite.as_str())
+        .ok_or_else(|| {
+            let supported: Vec<&str> = CIPHERSUITES.iter().map(|(name, _)| *name).collect();
+            eyre!(
+                "unsupported ciphersuite {ciphersuite}, supported ciphersuites are: {}",
+                supported.join(", ")
+            )
+        })?;
+    runner(args)
+}
+
+/// Returned when the user refuses the group parameters; no keys have been generated.
+#[derive(Debug, thiserror::Error)]
+#[error("trusted dealer generation declined")]
+pub struct GenerationDeclined;
+
 /// CLI entry point for trusted dealer key generation
 ///
 /// Generates FROST key shares using ciphersuite and updates
@@ -25,27 +64,43 @@ type ParticipantExtractionResult =
 ///
 /// **TESTING ONLY** - See security warnings in `Command::TrustedDealer`.
 /// Trusted dealer key generation for a specific ciphersuite
-pub fn run<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
+pub fn run<C: Ciphersuite>(args: &Command) -> Result<Report, Box<dyn Error>> {
+    // Not a session command: Ctrl-C simply ends the process
+    let mut input = stdin::input(
+        None,
+        "a y/n answer to the group parameters prompt",
+        &CancellationToken::new(),
+    );
+    run_with_input::<C>(args, &mut input, &mut std::io::stderr())
+}
+
+/// Same as [`run`], reading the confirmation answer from `input` and writing
+/// the prompt to `output`.
+pub fn run_with_input<C: Ciphersuite>(
+    args: &Command,
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+) -> Result<Report, Box<dyn Error>> {
     let Command::TrustedDealer {
         config,
         description,
         threshold,
         names,
         server_url,
+        ciphersuite: _,
+        count,
+        yes,
+        key_package_dir,
     } = (*args).clone()
     else {
         panic!("invalid Command");
     };
 
     let num_signers = names.len() as u16;
-    // QUESTION: Should we make the user confirm after that?
-    println!(
+    writeln!(
+        output,
         "IMPORTANT: This command is for testing and demonstration purposes only. In production, use DKG."
-    );
-    println!(
-        "Running Trusted Dealer with {} participants and threshold {}",
-        num_signers, threshold
-    );
+    )?;
 
     if config.len() != num_signers as usize {
         return Err(
@@ -55,29 +110,141 @@ pub fn run<C: Ciphersuite>(args: &Command) -> Result<(), Box<dyn Error>> {
     if threshold > num_signers {
         return Err(eyre!("Threshold cannot be greater than the number of signers").into());
     }
+    if count == 0 {
+        return Err(eyre!("The `count` option must be at least 1").into());
+    }
+    if key_package_dir.is_some() && count != 1 {
+        return Err(eyre!("The `key_package_dir` option only supports a single group").into());
+    }
 
     let trusted_dealer_config = trusted_dealer::Config::new::<C>(threshold, num_signers)?;
+    confirm_parameters(input, output, threshold, num_signers, count, yes)?;
+
     let mut rng = thread_rng();
 
+    let mut groups = Vec::new();
+    for index in 1..=count {
+        let description = if count == 1 {
+            description.clone()
+        } else {
+            format!("{description} #{index}")
+        };
+        groups.push(generate_group::<C>(
+            &trusted_dealer_config,
+            &mut rng,
+            &config,
+            &names,
+            &description,
+            &server_url,
+            key_package_dir.as_deref(),
+        )?);
+    }
+
+    let mut report = Report::new();
+    if let [group] = groups.as_slice() {
+        report.insert(
+            "group_public_key".into(),
+            group.hex_verifying_key.clone().into(),
+        );
+        report.insert("address".into(), group.mina_verifying_key.clone().into());
+    } else {
+        let groups: Vec<serde_json::Value> = groups
+            .iter()
+            .map(|group| {
+                serde_json::json!({
+                    "group_public_key": group.hex_verifying_key,
+                    "address": group.mina_verifying_key,
+                })
+            })
+            .collect();
+        report.insert("groups".into(), groups.into());
+    }
+    report.insert("threshold".into(), threshold.into());
+    if let Some(dir) = &key_package_dir {
+        let files: Vec<String> = names
+            .iter()
+            .map(|name| key_package_path(dir, name))
+            .collect();
+        report.insert("key_package_files".into(), files.into());
+    }
+    report.insert("participants".into(), names.into());
+    Ok(report)
+}
+
+/// Echo the group parameters back in words and, unless `yes` is set, read a y/n answer.
+fn confirm_parameters(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    threshold: u16,
+    num_signers: u16,
+    count: u16,
+    yes: bool,
+) -> Result<(), Box<dyn Error>> {
+    let groups = if count == 1 {
+        format!("a {threshold}-of-{num_signers} group")
+    } else {
+        format!("{count} independent {threshold}-of-{num_signers} groups")
+    };
+    writeln!(
+        output,
+        "Generating {groups}: any {threshold} of {num_signers} participants can sign"
+    )?;
+    if yes {
+        return Ok(());
+    }
+
+    writeln!(output, "Do you want to continue? (y/n)")?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    if answer.trim() != "y" {
+        return Err(GenerationDeclined.into());
+    }
+    Ok(())
+}
+
+/// Path of the key file written for participant `name` with `--key-package-dir`.
+fn key_package_path(dir: &str, name: &str) -> String {
+    std::path::Path::new(dir)
+        .join(format!("{name}.json"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Generate one group's key shares and add the group to every participant's config file.
+fn generate_group<C: Ciphersuite + 'static>(
+    trusted_dealer_config: &trusted_dealer::Config,
+    rng: &mut ThreadRng,
+    config: &[String],
+    names: &[String],
+    description: &str,
+    server_url: &Option<String>,
+    key_package_dir: Option<&str>,
+) -> Result<GroupInfo, Box<dyn Error>> {
     // Generate key shares
-    let (shares, public_key_package) =
-        trusted_dealer::keygen::<C, _>(&trusted_dealer_config, &mut rng)?;
+    let (shares, public_key_package) = trusted_dealer::keygen::<C, _>(trusted_dealer_config, rng)?;
 
     // Extract participant information from config files
-    let (participants, contacts) = extract_participant_info(&shares, &config, &names)?;
+    let (participants, contacts) = extract_participant_info(&shares, config, names)?;
 
     // Update config files with group information
     update_config_files::<C>(
         &shares,
-        &config,
+        config,
+        names,
         &public_key_package,
-        &description,
+        description,
         &participants,
         &contacts,
-        &server_url,
+        server_url,
+        key_package_dir,
     )?;
 
-    Ok(())
+    let first_share = shares.values().next().ok_or_eyre("no shares generated")?;
+    let key_package: KeyPackage<C> = first_share.clone().try_into()?;
+    ciphersuite_helper::<C>().group_info(
+        &postcard::to_allocvec(&key_package)?,
+        &postcard::to_allocvec(&public_key_package)?,
+    )
 }
 
 /// Extract participant information from config files
@@ -99,11 +266,8 @@ fn extract_participant_info<C: Ciphersuite>(
             .ok_or_eyre("config not initialized")?
             .pubkey
             .clone();
-        let participant = Participant {
-            identifier: identifier.serialize(),
-            pubkey: pubkey.clone(),
-        };
-        participants.insert(hex::encode(identifier.serialize()), participant);
+        let participant = Participant::new(identifier, pubkey.clone());
+        participants.insert(Participant::map_key(identifier), participant);
         let contact = Contact {
             version: None,
             name: name.clone(),
@@ -119,16 +283,24 @@ fn extract_participant_info<C: Ciphersuite>(
 ///
 /// This function takes the generated key shares and updates each participant's config
 /// file with the group information, including their key package and all participants.
+/// With `key_package_dir`, key packages go to versioned key files in that directory
+/// instead and the config files hold none.
+#[allow(clippy::too_many_arguments)]
 fn update_config_files<C: Ciphersuite + 'static>(
     shares: &BTreeMap<frost_core::Identifier<C>, frost_core::keys::SecretShare<C>>,
     config_paths: &[String],
+    names: &[String],
     public_key_package: &frost_core::keys::PublicKeyPackage<C>,
     description: &str,
     participants: &BTreeMap<String, Participant>,
     contacts: &[Contact],
     server_url: &Option<String>,
+    key_package_dir: Option<&str>,
 ) -> Result<(), Box<dyn Error>> {
-    for (share, path) in shares.values().zip(config_paths.iter()) {
+    if let Some(dir) = key_package_dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    for (share, path, name) in izip!(shares.values(), config_paths.iter(), names.iter()) {
         let mut config = Config::read(Some(path.to_string()))?;
         // IMPORTANT: the TrustedDealer command is intended for tests only, see
         // comment in [`Command::TrustedDealer`]. If you're using this code as a
@@ -137,10 +309,18 @@ fn update_config_files<C: Ciphersuite + 'static>(
         // [`SecretShare::commitment()`] is the same for all participants using
         // a broadcast channel.
         let key_package: KeyPackage<C> = share.clone().try_into()?;
+        let encoded_key_package = match key_package_dir {
+            Some(dir) => {
+                let json = ciphersuite_helper::<C>().export_key_package(&key_package)?;
+                write_atomic::write_file(key_package_path(dir, name), json.as_bytes())?;
+                Vec::new()
+            }
+            None => postcard::to_allocvec(&key_package)?,
+        };
         let group = Group::<C> {
             _phantom: PhantomData,
             description: description.to_string(),
-            key_package: postcard::to_allocvec(&key_package)?,
+            key_package: encoded_key_package,
             public_key_package: postcard::to_allocvec(public_key_package)?,
             participant: participants.clone(),
             server_url: server_url.clone(),
@@ -157,3 +337,194 @@ fn update_config_files<C: Ciphersuite + 'static>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{output::render_json, tests::helpers::TestConfigs};
+    use crate::trusted_dealer::tests::helpers;
+
+    #[tokio::test]
+    async fn test_run_reports_group_as_json() {
+        let configs = TestConfigs::init(&["alice", "bob", "carol"], None).await;
+
+        let report = run::<BluePallasSuite>(&configs.trusted_dealer(2));
+        let value = render_json(&report);
+
+        assert_eq!(value["status"], "ok");
+        assert!(value["group_public_key"].is_string());
+        assert!(value["address"].as_str().unwrap().starts_with("B62"));
+        assert_eq!(value["threshold"], 2);
+        assert_eq!(value["participants"], serde_json::json!(configs.names));
+    }
+
+    #[tokio::test]
+    async fn test_key_package_dir_writes_versioned_key_files() {
+        let configs = TestConfigs::init(&["alice", "bob", "carol"], None).await;
+        let key_dir = configs.path("keys");
+        let command = |groups: u16| {
+            let mut command = configs.trusted_dealer(2);
+            if let Command::TrustedDealer {
+                count,
+                key_package_dir,
+                ..
+            } = &mut command
+            {
+                *count = groups;
+                *key_package_dir = Some(key_dir.clone());
+            }
+            command
+        };
+
+        assert!(run::<BluePallasSuite>(&command(2)).is_err());
+
+        let report = run::<BluePallasSuite>(&command(1)).unwrap();
+        let files = report["key_package_files"].as_array().unwrap();
+        assert_eq!(files.len(), configs.names.len());
+
+        for (name, (config, file)) in configs.names.iter().zip(configs.paths.iter().zip(files)) {
+            assert_eq!(file.as_str().unwrap(), key_package_path(&key_dir, name));
+            let config = Config::<BluePallasSuite>::read(Some(config.clone())).unwrap();
+            let group = config.group.values().next().unwrap();
+            assert!(group.key_package.is_empty());
+
+            let json = std::fs::read_to_string(file.as_str().unwrap()).unwrap();
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(value["version"], frost_bluepallas::keys::KEY_FILE_VERSION);
+            let key_package = ciphersuite_helper::<BluePallasSuite>()
+                .import_key_package(&json)
+                .unwrap();
+            let public_key_package: frost_core::keys::PublicKeyPackage<BluePallasSuite> =
+                postcard::from_bytes(&group.public_key_package).unwrap();
+            assert_eq!(
+                key_package.verifying_key(),
+                public_key_package.verifying_key()
+            );
+            assert_eq!(
+                Some(key_package.verifying_share()),
+                public_key_package
+                    .verifying_shares()
+                    .get(key_package.identifier())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_prompt() {
+        let configs = TestConfigs::init(&["alice", "bob", "carol", "dave", "erin"], None).await;
+        let command = |prompt_skipped: bool| {
+            let mut command = configs.trusted_dealer(3);
+            if let Command::TrustedDealer { yes, .. } = &mut command {
+                *yes = prompt_skipped;
+            }
+            command
+        };
+        let run = |yes: bool, answer: &str| {
+            let mut input = std::io::Cursor::new(answer.as_bytes().to_vec());
+            let mut output = Vec::new();
+            let result = run_with_input::<BluePallasSuite>(&command(yes), &mut input, &mut output);
+            (result, String::from_utf8(output).unwrap())
+        };
+        let group_count = || {
+            Config::<BluePallasSuite>::read(Some(configs.paths[0].clone()))
+                .unwrap()
+                .group
+                .len()
+        };
+
+        for answer in ["n\n", "\n", ""] {
+            let (result, output) = run(false, answer);
+            assert!(result
+                .unwrap_err()
+                .downcast_ref::<GenerationDeclined>()
+                .is_some());
+            assert!(output.contains("Generating a 3-of-5 group: any 3 of 5 participants can sign"));
+            assert!(output.contains("Do you want to continue? (y/n)"));
+        }
+        assert_eq!(group_count(), 0);
+
+        // With `--yes` nothing is read, so the empty input is not taken as a refusal
+        let (result, output) = run(true, "");
+        result.unwrap();
+        assert!(output.contains("Generating a 3-of-5 group"));
+        assert!(!output.contains("Do you want to continue?"));
+        assert_eq!(group_count(), 1);
+
+        run(false, "y\n").0.unwrap();
+        assert_eq!(group_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_ciphersuite_dispatches_by_name() {
+        let configs = TestConfigs::init(&["alice", "bob"], None).await;
+        let command = |name: &str| {
+            let mut command = configs.trusted_dealer(2);
+            if let Command::TrustedDealer { ciphersuite, .. } = &mut command {
+                *ciphersuite = name.to_string();
+            }
+            command
+        };
+
+        let err = run_with_ciphersuite(&command("ed25519")).unwrap_err();
+        assert!(err.to_string().contains("unsupported ciphersuite ed25519"));
+        assert!(err.to_string().contains("bluepallas"));
+
+        let report = run_with_ciphersuite(&command("bluepallas")).unwrap();
+        assert_eq!(report["threshold"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_with_count_generates_independent_groups() {
+        let configs = TestConfigs::init(&["alice", "bob", "carol"], None).await;
+        let mut command = configs.trusted_dealer(2);
+        if let Command::TrustedDealer {
+            description, count, ..
+        } = &mut command
+        {
+            *description = "treasury".to_string();
+            *count = 2;
+        }
+
+        let report = run::<BluePallasSuite>(&command).unwrap();
+        let groups = report["groups"].as_array().unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_ne!(groups[0]["group_public_key"], groups[1]["group_public_key"]);
+
+        let alice = Config::<BluePallasSuite>::read(Some(configs.paths[0].clone())).unwrap();
+        let bob = Config::<BluePallasSuite>::read(Some(configs.paths[1].clone())).unwrap();
+        assert_eq!(alice.group.len(), 2);
+        let mut descriptions: Vec<_> = alice
+            .group
+            .values()
+            .map(|g| g.description.clone())
+            .collect();
+        descriptions.sort();
+        assert_eq!(descriptions, ["treasury #1", "treasury #2"]);
+
+        let mut rng = thread_rng();
+        let message = b"independent groups";
+        for (group_key, group) in &alice.group {
+            let key_packages: BTreeMap<_, KeyPackage<BluePallasSuite>> =
+                [group, &bob.group[group_key]]
+                    .iter()
+                    .map(|g| {
+                        let key_package: KeyPackage<BluePallasSuite> =
+                            postcard::from_bytes(&g.key_package).unwrap();
+                        (*key_package.identifier(), key_package)
+                    })
+                    .collect();
+            let public_key_package: frost_core::keys::PublicKeyPackage<BluePallasSuite> =
+                postcard::from_bytes(&group.public_key_package).unwrap();
+
+            let (nonces, commitments) = helpers::round_1(2, &mut rng, &key_packages);
+            let (signing_package, shares) =
+                helpers::round_2(nonces, &key_packages, commitments, message);
+            let signature =
+                frost_core::aggregate(&signing_package, &shares, &public_key_package).unwrap();
+            public_key_package
+                .verifying_key()
+                .verify(signing_package.message(), &signature)
+                .unwrap();
+        }
+    }
+}
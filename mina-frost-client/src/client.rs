@@ -1,8 +1,15 @@
 //! Client for the frostd server.
+use std::{sync::Mutex, time::Duration};
+
+use rand::thread_rng;
+use reqwest::StatusCode;
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::api;
+use crate::{
+    api,
+    cipher::{self, PrivateKey, PublicKey},
+};
 
 /// A Client error.
 #[derive(Debug, Error)]
@@ -13,13 +20,67 @@ pub enum Error {
     ConnectionError(#[from] reqwest::Error),
     #[error("JSON parsing error: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("authentication error: {0}")]
+    AuthenticationError(#[from] cipher::Error),
+}
+
+/// How a [`Client`] retries calls after the connection to frostd drops.
+///
+/// The delay before retry `n` is `initial_backoff * 2^n`, capped at `max_backoff`.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Retries allowed per call before the error is returned.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+/// The communication key pair the client logged in with, kept to log in again.
+struct Identity {
+    privkey: PrivateKey,
+    pubkey: PublicKey,
 }
 
 /// A frostd Client that allows calling frostd API methods.
+///
+/// Calls that fail because the connection dropped are retried with exponential backoff
+/// according to the client's [`RetryPolicy`]. If the client logged in with
+/// [`Client::authenticate`] and the server no longer accepts its access token, e.g. after a
+/// restart, it logs in again before retrying.
 pub struct Client {
     host_port: String,
     client: reqwest::Client,
-    access_token: Option<Uuid>,
+    access_token: Mutex<Option<Uuid>>,
+    identity: Option<Identity>,
+    retry_policy: RetryPolicy,
 }
 
 impl Client {
@@ -29,11 +90,110 @@ impl Client {
         Self {
             host_port,
             client: reqwest::Client::new(),
-            access_token: None,
+            access_token: Mutex::new(None),
+            identity: None,
+            retry_policy: RetryPolicy::default(),
         }
     }
 
+    /// Replace the policy used to retry calls after the connection drops.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Call an idempotent method, retrying on any connection failure.
     async fn call<A, O>(&self, name: &str, args: &A) -> Result<O, Error>
+    where
+        A: serde::Serialize,
+        O: serde::de::DeserializeOwned,
+    {
+        self.call_with_retry(name, args, true).await
+    }
+
+    /// Call `name`, retrying with backoff when the connection drops.
+    ///
+    /// Non-idempotent calls are only retried when the request provably never reached the
+    /// server, so e.g. a signature share is never submitted twice.
+    async fn call_with_retry<A, O>(
+        &self,
+        name: &str,
+        args: &A,
+        idempotent: bool,
+    ) -> Result<O, Error>
+    where
+        A: serde::Serialize,
+        O: serde::de::DeserializeOwned,
+    {
+        let mut attempt = 0;
+        loop {
+            let err = match self.call_once(name, args).await {
+                Ok(output) => return Ok(output),
+                Err(err) => err,
+            };
+            let Error::ConnectionError(e) = &err else {
+                return Err(err);
+            };
+            let unauthorized = e.status() == Some(StatusCode::UNAUTHORIZED);
+            let retryable = if unauthorized {
+                self.identity.is_some()
+            } else {
+                e.is_connect() || (idempotent && (e.is_timeout() || e.is_request() || e.is_body()))
+            };
+            if !retryable || attempt >= self.retry_policy.max_retries {
+                return Err(err);
+            }
+
+            let delay = self.retry_policy.backoff(attempt);
+            eprintln!(
+                "Connection to the server failed ({e}); retrying in {}ms...",
+                delay.as_millis()
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            if unauthorized {
+                match self.relogin().await {
+                    // Still unreachable; the call fails to connect and is retried
+                    Err(Error::ConnectionError(e)) if e.is_connect() => {}
+                    result => result?,
+                }
+            }
+        }
+    }
+
+    /// Log in again with the stored identity, replacing the access token.
+    async fn relogin(&self) -> Result<(), Error> {
+        let Some(identity) = &self.identity else {
+            return Ok(());
+        };
+        let login_output = self.login_once(identity).await?;
+        *self
+            .access_token
+            .lock()
+            .expect("access token lock poisoned") = Some(login_output.access_token);
+        Ok(())
+    }
+
+    async fn login_once(&self, identity: &Identity) -> Result<api::LoginOutput, Error> {
+        let challenge = self
+            .call_once::<_, api::ChallengeOutput>("challenge", &())
+            .await?
+            .challenge;
+        let signature: [u8; 64] = identity
+            .privkey
+            .sign(challenge.as_bytes(), &mut thread_rng())?;
+        self.call_once(
+            "login",
+            &api::LoginArgs {
+                challenge,
+                pubkey: identity.pubkey.clone(),
+                signature: signature.to_vec(),
+            },
+        )
+        .await
+    }
+
+    async fn call_once<A, O>(&self, name: &str, args: &A) -> Result<O, Error>
     where
         A: serde::Serialize,
         O: serde::de::DeserializeOwned,
@@ -42,7 +202,11 @@ impl Client {
             .client
             .post(format!("{}/{}", self.host_port, name))
             .json(args);
-        let req = if let Some(token) = &self.access_token {
+        let token = *self
+            .access_token
+            .lock()
+            .expect("access token lock poisoned");
+        let req = if let Some(token) = token {
             req.bearer_auth(token.to_string())
         } else {
             req
@@ -75,22 +239,54 @@ impl Client {
     /// client so that other authenticated methods can be called.
     pub async fn login(&mut self, args: &api::LoginArgs) -> Result<api::LoginOutput, Error> {
         let login_output: api::LoginOutput = self.call("login", args).await?;
-        self.access_token = Some(login_output.access_token);
+        *self
+            .access_token
+            .get_mut()
+            .expect("access token lock poisoned") = Some(login_output.access_token);
+        Ok(login_output)
+    }
+
+    /// Login to the server by signing a fresh challenge with the communication key pair.
+    ///
+    /// The key pair is kept so the client can log in again on its own if the server stops
+    /// accepting the access token while a session is in progress.
+    pub async fn authenticate(
+        &mut self,
+        privkey: &PrivateKey,
+        pubkey: &PublicKey,
+    ) -> Result<api::LoginOutput, Error> {
+        let identity = Identity {
+            privkey: privkey.clone(),
+            pubkey: pubkey.clone(),
+        };
+        let login_output = self.login_once(&identity).await?;
+        *self
+            .access_token
+            .get_mut()
+            .expect("access token lock poisoned") = Some(login_output.access_token);
+        self.identity = Some(identity);
         Ok(login_output)
     }
 
     /// Log out from the server. This will clear the cached access token.
     pub async fn logout(&mut self) -> Result<(), Error> {
         self.call::<(), ()>("logout", &()).await?;
-        self.access_token = None;
+        *self
+            .access_token
+            .get_mut()
+            .expect("access token lock poisoned") = None;
+        self.identity = None;
         Ok(())
     }
 
+    /// Create a session. Like [`Client::send`], only retried when the request never reached
+    /// the server, so a lost response cannot create a second session.
     pub async fn create_new_session(
         &self,
         args: &api::CreateNewSessionArgs,
     ) -> Result<api::CreateNewSessionOutput, Error> {
-        self.call("create_new_session", args).await
+        self.call_with_retry("create_new_session", args, false)
+            .await
     }
 
     pub async fn list_sessions(&self) -> Result<api::ListSessionsOutput, Error> {
@@ -104,15 +300,105 @@ impl Client {
         self.call("get_session_info", args).await
     }
 
+    /// Send a message. Only retried when the request never reached the server, so a
+    /// message is never delivered twice.
     pub async fn send(&self, args: &api::SendArgs) -> Result<(), Error> {
-        self.call("send", args).await
+        self.call_with_retry("send", args, false).await
     }
 
+    /// Receive the pending messages. The server drops messages once they are returned, so
+    /// like [`Client::send`] this is only retried when the request never reached the server;
+    /// retrying after a lost response would lose them.
     pub async fn receive(&self, args: &api::ReceiveArgs) -> Result<api::ReceiveOutput, Error> {
-        self.call("receive", args).await
+        self.call_with_retry("receive", args, false).await
     }
 
     pub async fn close_session(&self, args: &api::CloseSessionArgs) -> Result<(), Error> {
         self.call("close_session", args).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    fn fast_retries() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        }
+    }
+
+    /// Serve `body` over HTTP, dropping the first connection after reading its request.
+    async fn flaky_server(body: &'static str) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let seen = connections.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let n = seen.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                if n == 0 {
+                    continue;
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (format!("http://{addr}"), connections)
+    }
+
+    #[tokio::test]
+    async fn idempotent_call_is_retried_after_dropped_connection() {
+        let (url, connections) = flaky_server(r#"{"session_ids":[]}"#).await;
+        let client = Client::new(url).with_retry_policy(fast_retries());
+
+        let output = client.list_sessions().await.unwrap();
+
+        assert!(output.session_ids.is_empty());
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn send_is_not_retried_once_delivered() {
+        let (url, connections) = flaky_server("").await;
+        let client = Client::new(url).with_retry_policy(fast_retries());
+
+        let result = client
+            .send(&api::SendArgs {
+                session_id: Uuid::new_v4(),
+                recipients: vec![],
+                msg: vec![1, 2, 3],
+            })
+            .await;
+
+        assert!(matches!(result, Err(Error::ConnectionError(_))));
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        let policy = fast_retries();
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(1), Duration::from_millis(20));
+        assert_eq!(policy.backoff(3), Duration::from_millis(50));
+        assert_eq!(policy.backoff(40), Duration::from_millis(50));
+    }
+}
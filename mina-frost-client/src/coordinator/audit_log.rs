@@ -0,0 +1,102 @@
+//! Append-only record of the signing sessions a coordinator ran.
+//!
+//! Each finished session, successful or not, is appended to the log as one JSON object
+//! per line. Records only hold public data: the participants' identifiers, the signed
+//! message and the aggregated signature.
+
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use frost_core::{Ciphersuite, Identifier};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// How a signing session ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditOutcome {
+    Completed,
+    Failed,
+}
+
+/// One line of the audit log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Seconds since the Unix epoch at which the session ended.
+    pub timestamp: u64,
+    /// The frostd session, when the session ran over a server.
+    pub session_id: Option<Uuid>,
+    pub outcome: AuditOutcome,
+    /// Hex-encoded identifiers of the signers whose commitments were used.
+    pub participants: Vec<String>,
+    /// Hex-encoded message that was signed, i.e. the serialized transaction.
+    pub message: String,
+    /// Hex-encoded aggregated signature, if the session completed.
+    pub signature: Option<String>,
+    /// Why the session failed, if it did.
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// Describe a session that ended with `result` after collecting commitments from
+    /// `participants`.
+    pub fn new<C: Ciphersuite>(
+        session_id: Option<Uuid>,
+        participants: &[Identifier<C>],
+        message: &[u8],
+        result: &Result<Vec<u8>, Box<dyn std::error::Error>>,
+    ) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let (outcome, signature, error) = match result {
+            Ok(signature) => (AuditOutcome::Completed, Some(hex::encode(signature)), None),
+            Err(e) => (AuditOutcome::Failed, None, Some(e.to_string())),
+        };
+        Self {
+            timestamp,
+            session_id,
+            outcome,
+            participants: participants
+                .iter()
+                .map(|id| hex::encode(id.serialize()))
+                .collect(),
+            message: hex::encode(message),
+            signature,
+            error,
+        }
+    }
+}
+
+/// An audit log file that records are only ever appended to.
+#[derive(Clone, Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `record` as a single line, creating the file if needed, and sync it to disk.
+    pub fn append(&self, record: &AuditRecord) -> io::Result<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+}
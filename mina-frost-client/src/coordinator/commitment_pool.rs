@@ -0,0 +1,108 @@
+//! Commitments collected ahead of time, so that signing a message needs only round 2.
+//!
+//! Each participant's commitments are bound to nonces the participant keeps, and
+//! signing twice with the same nonces leaks their signing share. The pool therefore
+//! hands out every commitment at most once and remembers every commitment it has ever
+//! held, so one that was already used can never be added back.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+
+use frost_core::{round1::SigningCommitments, Identifier};
+
+use crate::BluePallasSuite;
+
+/// Errors returned by [`CommitmentPool`].
+#[derive(Debug, thiserror::Error)]
+pub enum CommitmentPoolError {
+    #[error("commitments {0} were already added to the pool")]
+    AlreadyAdded(String),
+    #[error("no pre-distributed commitments left for participant {0}")]
+    Exhausted(String),
+    #[error("pre-distributed commitments are available for {available} signers, but {required} are required")]
+    NotEnoughSigners { available: usize, required: u16 },
+    #[error("failed to serialize commitments: {0}")]
+    Serialization(#[from] frost_core::Error<BluePallasSuite>),
+}
+
+/// Per-participant queues of unused round 1 commitments.
+#[derive(Debug, Default)]
+pub struct CommitmentPool {
+    available: BTreeMap<Identifier<BluePallasSuite>, VecDeque<SigningCommitments<BluePallasSuite>>>,
+    seen: HashSet<Vec<u8>>,
+}
+
+impl CommitmentPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue commitments sent by `identifier` in advance.
+    pub fn add(
+        &mut self,
+        identifier: Identifier<BluePallasSuite>,
+        commitments: SigningCommitments<BluePallasSuite>,
+    ) -> Result<(), CommitmentPoolError> {
+        let key = commitments.serialize()?;
+        if !self.seen.insert(key.clone()) {
+            return Err(CommitmentPoolError::AlreadyAdded(hex::encode(key)));
+        }
+        self.available
+            .entry(identifier)
+            .or_default()
+            .push_back(commitments);
+        Ok(())
+    }
+
+    /// How many unused commitments are left for `identifier`.
+    pub fn remaining(&self, identifier: &Identifier<BluePallasSuite>) -> usize {
+        self.available.get(identifier).map_or(0, VecDeque::len)
+    }
+
+    /// Take one commitment from each of `signers`, in the order they were added.
+    ///
+    /// Nothing is taken unless every signer still has a commitment left.
+    pub fn take(
+        &mut self,
+        signers: &BTreeSet<Identifier<BluePallasSuite>>,
+    ) -> Result<
+        BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+        CommitmentPoolError,
+    > {
+        if let Some(missing) = signers.iter().find(|id| self.remaining(id) == 0) {
+            return Err(CommitmentPoolError::Exhausted(hex::encode(
+                missing.serialize(),
+            )));
+        }
+        Ok(signers
+            .iter()
+            .filter_map(|id| {
+                let commitments = self.available.get_mut(id)?.pop_front()?;
+                Some((*id, commitments))
+            })
+            .collect())
+    }
+
+    /// Take commitments for the first `num_signers` of `allowed` that have any left.
+    pub fn take_quorum(
+        &mut self,
+        allowed: &BTreeSet<Identifier<BluePallasSuite>>,
+        num_signers: u16,
+    ) -> Result<
+        BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+        CommitmentPoolError,
+    > {
+        let ready: BTreeSet<_> = allowed
+            .iter()
+            .filter(|id| self.remaining(id) > 0)
+            .take(num_signers as usize)
+            .copied()
+            .collect();
+        if ready.len() < num_signers as usize {
+            return Err(CommitmentPoolError::NotEnoughSigners {
+                available: ready.len(),
+                required: num_signers,
+            });
+        }
+        self.take(&ready)
+    }
+}
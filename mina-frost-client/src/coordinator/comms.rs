@@ -10,6 +10,8 @@ use std::{
 
 use async_trait::async_trait;
 
+use crate::api::Uuid;
+
 use frost::{
     keys::PublicKeyPackage,
     round1::SigningCommitments,
@@ -54,6 +56,36 @@ pub trait Comms<C: Ciphersuite> {
         signing_package: &SigningPackage<C>,
     ) -> Result<BTreeMap<Identifier<C>, SignatureShare<C>>, Box<dyn Error>>;
 
+    /// Abandon the current signing round and ask the signers still in the session to
+    /// commit again with fresh nonces, giving `reason`. The next round starts with
+    /// [`Comms::get_signing_commitments`].
+    ///
+    /// Fails if too few signers are left to complete a new round.
+    async fn restart(&mut self, _reason: &str) -> Result<(), Box<dyn Error>> {
+        Err("this transport cannot restart a signing round".into())
+    }
+
+    /// Prepare to run only round 2, with `commitments` collected ahead of time instead
+    /// of through [`Comms::get_signing_commitments`]. A restarted round collects fresh
+    /// commitments as usual.
+    async fn use_pre_distributed_commitments(
+        &mut self,
+        _commitments: &BTreeMap<Identifier<C>, SigningCommitments<C>>,
+    ) -> Result<(), Box<dyn Error>> {
+        Err("this transport does not support pre-distributed commitments".into())
+    }
+
+    /// Create the frostd session this comms runs in, if that was not done yet, and
+    /// return its ID. [`Comms::get_signing_commitments`] does this itself when needed.
+    async fn open_session(&mut self) -> Result<Uuid, Box<dyn Error>> {
+        Err("this transport does not use frostd sessions".into())
+    }
+
+    /// The frostd session this comms is bound to, once it has been created.
+    fn session_id(&self) -> Option<Uuid> {
+        None
+    }
+
     /// Do any cleanups in case an error occurs during the protocol run.
     async fn cleanup_on_error(&mut self) -> Result<(), Box<dyn Error>> {
         Ok(())
@@ -15,14 +15,13 @@ use frost_core::{
     keys::PublicKeyPackage, round1::SigningCommitments, round2::SignatureShare, Ciphersuite,
     Identifier, SigningPackage,
 };
-use rand::thread_rng;
 use serde_json;
 
 use crate::cipher::Cipher;
 use crate::client::Client;
 use crate::{
     api::{self, PublicKey, SendSigningPackageArgs, Uuid},
-    session::CoordinatorSessionState,
+    session::{CoordinatorSessionState, ParticipantAborted},
 };
 
 use super::super::config::Config;
@@ -39,13 +38,23 @@ pub struct HTTPComms<C: Ciphersuite> {
     state: CoordinatorSessionState<C>,
     pubkeys: HashMap<PublicKey, Identifier<C>>,
     cipher: Option<Cipher>,
+    /// Participants that aborted the session; they take no part in a restarted round.
+    departed: HashSet<PublicKey>,
     _phantom: PhantomData<C>,
 }
 
 impl<C: Ciphersuite> HTTPComms<C> {
     pub fn new(config: &Config<C>) -> Result<Self, Box<dyn Error>> {
+        Self::with_client(
+            config,
+            Client::new(format!("https://{}:{}", config.ip, config.port)),
+        )
+    }
+
+    /// Same as [`HTTPComms::new`], but talking to the server through `client`.
+    pub(crate) fn with_client(config: &Config<C>, client: Client) -> Result<Self, Box<dyn Error>> {
         Ok(Self {
-            client: Client::new(format!("https://{}:{}", config.ip, config.port)),
+            client,
             session_id: None,
             config: config.clone(),
             state: CoordinatorSessionState::new(
@@ -55,9 +64,25 @@ impl<C: Ciphersuite> HTTPComms<C> {
             ),
             pubkeys: Default::default(),
             cipher: None,
+            departed: Default::default(),
             _phantom: Default::default(),
         })
     }
+
+    /// The cipher for the participants of the session. A restarted round keeps talking to
+    /// them over the Noise sessions of the abandoned one.
+    fn take_cipher(&mut self) -> Result<Cipher, Box<dyn Error>> {
+        if let Some(cipher) = self.cipher.take() {
+            return Ok(cipher);
+        }
+        let Some(comm_privkey) = &self.config.comm_privkey else {
+            return Err(eyre!("comm_privkey must be specified").into());
+        };
+        Ok(Cipher::new(
+            comm_privkey.clone(),
+            self.config.signers.keys().cloned().collect(),
+        )?)
+    }
 }
 
 #[async_trait(?Send)]
@@ -69,55 +94,8 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
         _pub_key_package: &PublicKeyPackage<C>,
         _num_signers: u16,
     ) -> Result<BTreeMap<Identifier<C>, SigningCommitments<C>>, Box<dyn Error>> {
-        let mut rng = thread_rng();
-
-        eprintln!("Logging in...");
-        let challenge = self.client.challenge().await?.challenge;
-
-        let signature: [u8; 64] = self
-            .config
-            .comm_privkey
-            .clone()
-            .ok_or_eyre("comm_privkey must be specified")?
-            .sign(challenge.as_bytes(), &mut rng)?;
-
-        self.client
-            .login(&api::LoginArgs {
-                challenge,
-                pubkey: self
-                    .config
-                    .comm_pubkey
-                    .clone()
-                    .ok_or_eyre("comm_pubkey must be specified")?,
-                signature: signature.to_vec(),
-            })
-            .await?;
-
-        eprintln!("Creating signing session...");
-        let r = self
-            .client
-            .create_new_session(&api::CreateNewSessionArgs {
-                pubkeys: self.config.signers.keys().cloned().collect(),
-                message_count: 1,
-            })
-            .await?;
-
-        eprintln!(
-            "Send the following session ID to participants: {}",
-            r.session_id
-        );
-        self.session_id = Some(r.session_id);
-
-        let Some(comm_privkey) = &self.config.comm_privkey else {
-            return Err(eyre!("comm_privkey must be specified").into());
-        };
-
-        // If encryption is enabled, create the Noise objects
-
-        let mut cipher = Cipher::new(
-            comm_privkey.clone(),
-            self.config.signers.keys().cloned().collect(),
-        )?;
+        let session_id = self.open_session().await?;
+        let mut cipher = self.take_cipher()?;
 
         eprint!("Waiting for participants to send their commitments...");
 
@@ -126,19 +104,11 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
             let r = self
                 .client
                 .receive(&api::ReceiveArgs {
-                    session_id: r.session_id,
+                    session_id,
                     as_coordinator: true,
                 })
                 .await?;
             for msg in r.msgs {
-                // A participant may rejoin with a fresh Noise context; warn and skip to avoid DoS.
-                if commitment_senders.contains(&msg.sender) {
-                    eprintln!(
-                        "Warning: participant {} attempted to rejoin the session; ignoring",
-                        msg.sender
-                    );
-                    continue;
-                }
                 let sender = msg.sender.clone();
                 // A malicious or broken participant must not be able to kill the coordinator.
                 let msg = match cipher.decrypt(msg) {
@@ -151,10 +121,26 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
                         continue;
                     }
                 };
+                // Past its first message, a participant may only abort. Anything else is a
+                // rejoin attempt; warn and skip to avoid DoS.
+                if commitment_senders.contains(&sender)
+                    && serde_json::from_slice::<api::AbortArgs>(&msg.msg).is_err()
+                {
+                    eprintln!(
+                        "Warning: participant {} attempted to rejoin the session; ignoring",
+                        sender
+                    );
+                    continue;
+                }
                 match self.state.recv(msg) {
                     Ok(()) => {
                         commitment_senders.insert(sender);
                     }
+                    Err(e) if e.is::<ParticipantAborted>() => {
+                        eprintln!();
+                        self.departed.insert(sender);
+                        return Err(e);
+                    }
                     Err(e) => {
                         eprintln!(
                             "Warning: ignoring invalid commitment from {}: {}",
@@ -248,14 +234,6 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
                 })
                 .await?;
             for msg in r.msgs {
-                // A participant may rejoin with a fresh Noise context; warn and skip to avoid DoS.
-                if seen_share_senders.contains(&msg.sender) {
-                    eprintln!(
-                        "Warning: participant {} attempted to rejoin the session; ignoring",
-                        msg.sender
-                    );
-                    continue;
-                }
                 let sender = msg.sender.clone();
                 // A malicious or broken participant must not be able to kill the coordinator.
                 let msg = match cipher.decrypt(msg) {
@@ -268,10 +246,26 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
                         continue;
                     }
                 };
+                // Past its first message, a participant may only abort. Anything else is a
+                // rejoin attempt; warn and skip to avoid DoS.
+                if seen_share_senders.contains(&sender)
+                    && serde_json::from_slice::<api::AbortArgs>(&msg.msg).is_err()
+                {
+                    eprintln!(
+                        "Warning: participant {} attempted to rejoin the session; ignoring",
+                        sender
+                    );
+                    continue;
+                }
                 match self.state.recv(msg) {
                     Ok(()) => {
                         seen_share_senders.insert(sender);
                     }
+                    Err(e) if e.is::<ParticipantAborted>() => {
+                        eprintln!();
+                        self.departed.insert(sender);
+                        return Err(e);
+                    }
                     Err(e) => {
                         eprintln!(
                             "Warning: ignoring invalid signature share from {}: {}",
@@ -303,6 +297,110 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
         Ok(signature_shares[0].clone())
     }
 
+    async fn use_pre_distributed_commitments(
+        &mut self,
+        commitments: &BTreeMap<Identifier<C>, SigningCommitments<C>>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.open_session().await?;
+        let cipher = self.take_cipher()?;
+        self.cipher = Some(cipher);
+
+        // The participants joining the session send no commitments; they wait for the
+        // signing package for the commitments they handed out ahead of time
+        self.state.use_commitments(
+            commitments
+                .iter()
+                .map(|(identifier, commitments)| (*identifier, vec![*commitments]))
+                .collect(),
+        )?;
+        self.pubkeys = self
+            .config
+            .signers
+            .iter()
+            .filter(|(_, identifier)| commitments.contains_key(identifier))
+            .map(|(pubkey, identifier)| (pubkey.clone(), *identifier))
+            .collect();
+        Ok(())
+    }
+
+    async fn restart(&mut self, reason: &str) -> Result<(), Box<dyn Error>> {
+        let (Some(session_id), Some(cipher)) = (self.session_id, self.cipher.as_mut()) else {
+            return Err(eyre!("there is no signing round to restart").into());
+        };
+        let remaining = self.config.signers.len() - self.departed.len();
+        if remaining < self.config.num_signers as usize {
+            return Err(eyre!(
+                "cannot restart the signing round: {} signers are required, only {} are left",
+                self.config.num_signers,
+                remaining
+            )
+            .into());
+        }
+        self.state.restart()?;
+
+        eprintln!("Restarting the signing round ({reason})...");
+        let restart = serde_json::to_vec(&api::RestartArgs {
+            restart: reason.to_string(),
+        })?;
+        // Only the participants of the abandoned round hold nonces for it
+        for recipient in self.pubkeys.keys() {
+            if self.departed.contains(recipient) {
+                continue;
+            }
+            let msg = cipher.encrypt(Some(recipient), restart.clone())?;
+            let _r = self
+                .client
+                .send(&api::SendArgs {
+                    session_id,
+                    recipients: vec![recipient.clone()],
+                    msg,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn open_session(&mut self) -> Result<Uuid, Box<dyn Error>> {
+        if let Some(session_id) = self.session_id {
+            return Ok(session_id);
+        }
+
+        eprintln!("Logging in...");
+        let comm_privkey = self
+            .config
+            .comm_privkey
+            .clone()
+            .ok_or_eyre("comm_privkey must be specified")?;
+        let comm_pubkey = self
+            .config
+            .comm_pubkey
+            .clone()
+            .ok_or_eyre("comm_pubkey must be specified")?;
+        self.client
+            .authenticate(&comm_privkey, &comm_pubkey)
+            .await?;
+
+        eprintln!("Creating signing session...");
+        let r = self
+            .client
+            .create_new_session(&api::CreateNewSessionArgs {
+                pubkeys: self.config.signers.keys().cloned().collect(),
+                message_count: 1,
+            })
+            .await?;
+
+        eprintln!(
+            "Send the following session ID to participants: {}",
+            r.session_id
+        );
+        self.session_id = Some(r.session_id);
+        Ok(r.session_id)
+    }
+
+    fn session_id(&self) -> Option<Uuid> {
+        self.session_id
+    }
+
     async fn cleanup_on_error(&mut self) -> Result<(), Box<dyn Error>> {
         if let Some(session_id) = self.session_id {
             let _r = self
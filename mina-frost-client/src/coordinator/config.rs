@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 
 use crate::cipher::{PrivateKey, PublicKey};
 use frost_core::{keys::PublicKeyPackage, Ciphersuite, Identifier};
@@ -30,4 +30,7 @@ pub struct Config<C: Ciphersuite> {
 
     /// The coordinator's communication public key for HTTP mode.
     pub comm_pubkey: Option<PublicKey>,
+
+    /// JSON-lines file that a record of every finished session is appended to.
+    pub audit_log: Option<PathBuf>,
 }
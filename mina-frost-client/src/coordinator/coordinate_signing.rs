@@ -1,15 +1,20 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{BufRead, Write};
 
+use frost_bluepallas::{errors::FrostBluePallasError, AggregateProgress};
 use frost_core::{
-    self, keys::PublicKeyPackage, round1::SigningCommitments, Ciphersuite, Identifier,
-    SigningPackage,
+    self, keys::PublicKeyPackage, round1::SigningCommitments, round2::SignatureShare, Ciphersuite,
+    Identifier, Signature, SigningPackage,
 };
-use mina_tx::TransactionEnvelope;
+use mina_tx::{errors::MinaTxError, pallas_message::PallasMessage, TransactionEnvelope};
 
+use super::audit_log::{AuditLog, AuditRecord};
+use super::commitment_pool::CommitmentPool;
 use super::comms::http::HTTPComms;
 use super::comms::Comms;
 use super::config::Config;
+use crate::cancel::{cancellable, CancellationToken};
+use crate::session::ParticipantAborted;
 use crate::BluePallasSuite;
 
 #[derive(Debug, PartialEq)]
@@ -18,77 +23,797 @@ pub struct ParticipantsConfig<C: Ciphersuite> {
     pub pub_key_package: PublicKeyPackage<C>,
 }
 
+/// Failure while turning collected signature shares into a broadcastable signature.
+///
+/// Aggregation failures come from frost-core (bad shares, mismatched commitments), while
+/// Mina verification failures mean frost-core accepted the signature but `mina_signer`
+/// did not, which points at an encoding bug rather than a misbehaving participant.
+#[derive(Debug, thiserror::Error)]
+pub enum AggregateError {
+    #[error("failed to aggregate signature shares")]
+    Aggregation(#[source] FrostBluePallasError<PallasMessage>),
+    #[error("aggregated signature failed Mina verification: {0}")]
+    MinaVerification(MinaTxError),
+}
+
+/// Progress of a signing session, reported to UIs through
+/// [`coordinate_signing_with_progress`]. Events only carry identifiers and counts, never
+/// nonces, shares or other secret material.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningProgress {
+    /// Commitments were received from `received` of the `required` signers
+    CommitmentsCollected { received: usize, required: u16 },
+    /// Signature shares were received from `received` of the `required` signers
+    SharesCollected { received: usize, required: u16 },
+    /// A participant aborted after the signing package was sent, and the signing round
+    /// starts over with fresh commitments
+    Restarted,
+    /// The signature share of this participant is being verified
+    VerifyingShare(Identifier<BluePallasSuite>),
+    /// The verified shares are being combined into the group signature
+    Aggregating,
+    /// The group signature is being checked with Mina's verifier
+    VerifyingSignature,
+}
+
+/// Check an aggregated signature against the transaction using Mina's own verifier.
+///
+/// A signature that only verifies for another network is reported as a network mismatch.
+pub fn verify_aggregated_signature(
+    transaction: &TransactionEnvelope,
+    signature: &Signature<BluePallasSuite>,
+    public_key_package: &PublicKeyPackage<BluePallasSuite>,
+) -> Result<(), AggregateError> {
+    transaction
+        .verify_mina_diagnostic(public_key_package.verifying_key(), signature)
+        .map_err(AggregateError::MinaVerification)
+}
+
+/// Aggregate signature shares over `transaction` and confirm the result verifies under Mina rules.
+pub fn aggregate_and_verify(
+    transaction: &TransactionEnvelope,
+    commitments: BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+    signature_shares: &BTreeMap<Identifier<BluePallasSuite>, SignatureShare<BluePallasSuite>>,
+    public_key_package: &PublicKeyPackage<BluePallasSuite>,
+) -> Result<Signature<BluePallasSuite>, Box<dyn std::error::Error>> {
+    aggregate_and_verify_with_progress(
+        transaction,
+        commitments,
+        signature_shares,
+        public_key_package,
+        &mut |_| {},
+    )
+}
+
+/// [`aggregate_and_verify`], reporting each step to `progress`.
+fn aggregate_and_verify_with_progress(
+    transaction: &TransactionEnvelope,
+    commitments: BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+    signature_shares: &BTreeMap<Identifier<BluePallasSuite>, SignatureShare<BluePallasSuite>>,
+    public_key_package: &PublicKeyPackage<BluePallasSuite>,
+    progress: &mut dyn FnMut(SigningProgress),
+) -> Result<Signature<BluePallasSuite>, Box<dyn std::error::Error>> {
+    let pallas_message_bytes = transaction.to_pallas_message().serialize()?;
+    let signing_package = SigningPackage::new(commitments, &pallas_message_bytes);
+    let signature = frost_bluepallas::aggregate_with_progress(
+        &signing_package,
+        signature_shares,
+        public_key_package,
+        &mut |event| match event {
+            AggregateProgress::VerifyingShare(identifier) => {
+                progress(SigningProgress::VerifyingShare(identifier))
+            }
+            AggregateProgress::Aggregating => progress(SigningProgress::Aggregating),
+        },
+    )
+    .map_err(|e| AggregateError::Aggregation(e.into()))?;
+    progress(SigningProgress::VerifyingSignature);
+    verify_aggregated_signature(transaction, &signature, public_key_package)?;
+    Ok(signature)
+}
+
+/// Drop entries from identifiers outside the session's signer set, warning about each one.
+///
+/// Commitments and shares are only ever accepted from the signers the session was created
+/// for, so a signer excluded with `--participants-file` cannot contribute even if the
+/// server relays its messages.
+pub fn retain_allowed<T>(
+    entries: BTreeMap<Identifier<BluePallasSuite>, T>,
+    allowed: &BTreeSet<Identifier<BluePallasSuite>>,
+) -> BTreeMap<Identifier<BluePallasSuite>, T> {
+    entries
+        .into_iter()
+        .filter(|(identifier, _)| {
+            let keep = allowed.contains(identifier);
+            if !keep {
+                eprintln!(
+                    "Warning: ignoring message from excluded participant {}",
+                    hex::encode(identifier.serialize())
+                );
+            }
+            keep
+        })
+        .collect()
+}
+
 // This function orchestrates the signing process for a given ciphersuite.
 // It handles the communication with the signers, collects their commitments,
 // sends the signing package, and aggregates the signatures.
 // It returns the final aggregated signature as a byte vector.
+//
+// Cancelling `shutdown` aborts the round in progress and closes the session.
 pub async fn coordinate_signing(
     config: &Config<BluePallasSuite>,
     reader: &mut impl BufRead,
     logger: &mut impl Write,
+    shutdown: &CancellationToken,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    coordinate_signing_with_progress(config, reader, logger, shutdown, &mut |_| {}).await
+}
+
+/// [`coordinate_signing`], reporting each step of the session to `progress`.
+pub async fn coordinate_signing_with_progress(
+    config: &Config<BluePallasSuite>,
+    reader: &mut impl BufRead,
+    logger: &mut impl Write,
+    shutdown: &CancellationToken,
+    progress: &mut dyn FnMut(SigningProgress),
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let mut comms: Box<dyn Comms<BluePallasSuite>> = Box::new(HTTPComms::new(config)?);
+    coordinate_signing_with(comms.as_mut(), config, reader, logger, shutdown, progress).await
+}
 
-    // Round 1 - Get commitments
-    let commitments_list = comms
-        .get_signing_commitments(
-            reader,
-            logger,
-            &config.public_key_package,
-            config.num_signers,
-        )
-        .await;
+/// Run a signing session over the given `comms`, see [`coordinate_signing`].
+///
+/// If a participant aborts after the signing package was sent, the round is restarted
+/// with fresh commitments through [`Comms::restart`], as long as enough signers are left.
+pub(crate) async fn coordinate_signing_with(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    reader: &mut impl BufRead,
+    logger: &mut impl Write,
+    shutdown: &CancellationToken,
+    progress: &mut dyn FnMut(SigningProgress),
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    run_signing(comms, config, None, reader, logger, shutdown, progress).await
+}
+
+/// Run a signing session over the given `comms` with only round 2, using commitments taken
+/// from `pool`, see [`coordinate_signing`].
+///
+/// The signers are the first `config.num_signers` of `config.signers` with commitments
+/// left in the pool. Their commitments are taken out of the pool before the session
+/// starts, so none is ever used twice, even if the session fails. The participants sign
+/// with the nonces behind the commitments they handed out, see
+/// [`sign_pre_committed`](crate::participant::sign_pre_committed). A restarted round
+/// collects fresh commitments as usual.
+pub async fn coordinate_signing_from_pool(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    pool: &mut CommitmentPool,
+    reader: &mut impl BufRead,
+    logger: &mut impl Write,
+    shutdown: &CancellationToken,
+    progress: &mut dyn FnMut(SigningProgress),
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let allowed: BTreeSet<_> = config.signers.values().copied().collect();
+    let commitments = pool.take_quorum(&allowed, config.num_signers)?;
+    run_signing(
+        comms,
+        config,
+        Some(commitments),
+        reader,
+        logger,
+        shutdown,
+        progress,
+    )
+    .await
+}
+
+/// A signing session whose first round uses `pre_distributed` commitments, if any.
+async fn run_signing(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    mut pre_distributed: Option<
+        BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+    >,
+    reader: &mut impl BufRead,
+    logger: &mut impl Write,
+    shutdown: &CancellationToken,
+    progress: &mut dyn FnMut(SigningProgress),
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let allowed: BTreeSet<_> = config.signers.values().copied().collect();
+    let mut participants = Vec::new();
+
+    let result = async {
+        loop {
+            // Round 1 - Get commitments, unless they were handed out ahead of time
+            let commitments_list = match pre_distributed.take() {
+                Some(commitments) => cancellable(
+                    shutdown,
+                    "signing session",
+                    comms.use_pre_distributed_commitments(&commitments),
+                )
+                .await
+                .map(|()| commitments),
+                None => {
+                    cancellable(
+                        shutdown,
+                        "signing session",
+                        comms.get_signing_commitments(
+                            reader,
+                            logger,
+                            &config.public_key_package,
+                            config.num_signers,
+                        ),
+                    )
+                    .await
+                }
+            };
+
+            let commitments = match commitments_list {
+                Ok(commitments) => retain_allowed(commitments, &allowed),
+                Err(e) => {
+                    let _ = comms.cleanup_on_error().await;
+                    return Err(e);
+                }
+            };
+            participants.clear();
+            participants.extend(commitments.keys().copied());
+            progress(SigningProgress::CommitmentsCollected {
+                received: commitments.len(),
+                required: config.num_signers,
+            });
+
+            // Round 2 - Create signing package and get signature shares
+            let signing_package = SigningPackage::new(commitments.clone(), &config.message);
+
+            let signatures_list = cancellable(
+                shutdown,
+                "signing session",
+                comms.send_signing_package_and_get_signature_shares(
+                    reader,
+                    logger,
+                    &signing_package,
+                ),
+            )
+            .await;
+
+            let signatures = match signatures_list {
+                Ok(signatures) => retain_allowed(signatures, &allowed),
+                Err(e) => {
+                    // The other signers may hold a signing package for the abandoned
+                    // round, so they are asked for fresh commitments instead
+                    if e.is::<ParticipantAborted>() && comms.restart(&e.to_string()).await.is_ok() {
+                        progress(SigningProgress::Restarted);
+                        continue;
+                    }
+                    let _ = comms.cleanup_on_error().await;
+                    return Err(e);
+                }
+            };
+            progress(SigningProgress::SharesCollected {
+                received: signatures.len(),
+                required: config.num_signers,
+            });
 
-    let commitments = match commitments_list {
-        Ok(commitments) => commitments,
-        Err(e) => {
-            let _ = comms.cleanup_on_error().await;
-            return Err(e);
+            // Aggregate signatures using frost_bluepallas modified behaviour, then make sure a
+            // Mina node would accept the result before it is handed back for broadcast.
+            let transaction = TransactionEnvelope::deserialize(signing_package.message())?;
+            let group_signature = aggregate_and_verify_with_progress(
+                &transaction,
+                commitments,
+                &signatures,
+                &config.public_key_package,
+                progress,
+            );
+
+            let signature_bytes_result = match group_signature {
+                Ok(signature) => signature.serialize(),
+                Err(e) => {
+                    let _ = comms.cleanup_on_error().await;
+                    return Err(e);
+                }
+            };
+
+            let signature_bytes = match signature_bytes_result {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    let _ = comms.cleanup_on_error().await;
+                    return Err(e.into());
+                }
+            };
+
+            return Ok(signature_bytes);
         }
+    }
+    .await;
+
+    record_session(config, comms.session_id(), &participants, result)
+}
+
+/// Append the outcome of a session to the configured audit log, if any.
+///
+/// A session that produced a signature is reported as failed if its record cannot be
+/// written, so that no signature leaves the coordinator without an audit trail.
+fn record_session(
+    config: &Config<BluePallasSuite>,
+    session_id: Option<uuid::Uuid>,
+    participants: &[Identifier<BluePallasSuite>],
+    result: Result<Vec<u8>, Box<dyn std::error::Error>>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let Some(path) = &config.audit_log else {
+        return result;
     };
+    let record = AuditRecord::new(session_id, participants, &config.message, &result);
+    match AuditLog::new(path).append(&record) {
+        Err(e) if result.is_ok() => {
+            Err(format!("failed to write audit log {}: {}", path.display(), e).into())
+        }
+        _ => result,
+    }
+}
 
-    // Round 2 - Create signing package and get signature shares
-    let signing_package = SigningPackage::new(commitments.clone(), &config.message);
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
 
-    let signatures_list = comms
-        .send_signing_package_and_get_signature_shares(reader, logger, &signing_package)
-        .await;
+    use frost_bluepallas::keys::generate_key_packages_with_dealer;
+    use frost_core::{
+        keys::{IdentifierList, PublicKeyPackage},
+        round1::SigningCommitments,
+        round2::SignatureShare,
+        Identifier,
+    };
+    use mina_tx::{
+        legacy_tx::LegacyTransaction,
+        network_id::NetworkId,
+        pallas_message::{translate_pk, PallasMessage},
+        TransactionEnvelope,
+    };
+    use rand::thread_rng;
 
-    let signatures = match signatures_list {
-        Ok(signatures) => signatures,
-        Err(e) => {
-            let _ = comms.cleanup_on_error().await;
-            return Err(e);
-        }
+    use super::{
+        aggregate_and_verify, coordinate_signing_from_pool, coordinate_signing_with,
+        retain_allowed, verify_aggregated_signature, AggregateError, SigningProgress,
     };
+    use crate::cancel::{CancellationToken, Cancelled};
+    use crate::coordinator::audit_log::{AuditOutcome, AuditRecord};
+    use crate::coordinator::commitment_pool::{CommitmentPool, CommitmentPoolError};
+    use crate::coordinator::{comms::Comms, Config};
+    use crate::BluePallasSuite;
 
-    // Aggregate signatures using frost_bluepallas modified behaviour
-    let group_signature = {
-        let transaction = TransactionEnvelope::deserialize(signing_package.message())?;
-        let pallas_message_bytes = transaction.to_pallas_message().serialize()?;
-        let signing_package_for_crypto = SigningPackage::new(commitments, &pallas_message_bytes);
-        frost_bluepallas::aggregate(
-            &signing_package_for_crypto,
-            &signatures,
-            &config.public_key_package,
+    type Commitments = BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>;
+    type Shares = BTreeMap<Identifier<BluePallasSuite>, SignatureShare<BluePallasSuite>>;
+
+    /// Run both FROST rounds for a self-payment from the group key, without aggregating.
+    fn sign_payment(
+        network_id: NetworkId,
+    ) -> (
+        TransactionEnvelope,
+        Commitments,
+        Shares,
+        PublicKeyPackage<BluePallasSuite>,
+    ) {
+        let mut rng = thread_rng();
+        let (key_packages, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            IdentifierList::Default,
+            &mut rng,
         )
-    };
+        .unwrap();
+        let signers: Vec<_> = key_packages.values().take(2).collect();
+
+        let group_pk = translate_pk(pubkey_package.verifying_key()).unwrap();
+        let transaction = TransactionEnvelope::new_legacy(
+            network_id,
+            LegacyTransaction::new_payment(group_pk.clone(), group_pk, 1_000, 1_000, 0),
+        );
+
+        let (nonces, commitments): (BTreeMap<_, _>, Commitments) = signers
+            .iter()
+            .map(|key_package| {
+                let id = *key_package.identifier();
+                let (n, c) =
+                    frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+                ((id, n), (id, c))
+            })
+            .unzip();
+
+        let message = transaction.to_pallas_message().serialize().unwrap();
+        let signing_package = frost_core::SigningPackage::new(commitments.clone(), &message);
+        let signature_shares = signers
+            .iter()
+            .map(|key_package| {
+                let id = key_package.identifier();
+                let share =
+                    frost_bluepallas::round2::sign(&signing_package, &nonces[id], key_package)
+                        .unwrap();
+                (*id, share)
+            })
+            .collect();
+
+        (transaction, commitments, signature_shares, pubkey_package)
+    }
+
+    #[test]
+    fn test_aggregate_and_verify_accepts_valid_signature() {
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let signature =
+            aggregate_and_verify(&transaction, commitments, &shares, &pubkey_package).unwrap();
+        assert!(verify_aggregated_signature(&transaction, &signature, &pubkey_package).is_ok());
+    }
 
-    let signature_bytes_result = match group_signature {
-        Ok(signature) => signature.serialize(),
-        Err(e) => {
-            let _ = comms.cleanup_on_error().await;
-            return Err(e.into());
+    #[test]
+    fn test_mina_verification_failure_is_distinct_from_aggregation_failure() {
+        // A signature frost-core accepted for testnet must not pass Mina checks on mainnet.
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let signature =
+            aggregate_and_verify(&transaction, commitments.clone(), &shares, &pubkey_package)
+                .unwrap();
+        let mainnet_tx = TransactionEnvelope::new(NetworkId::Mainnet, transaction.inner().clone());
+        let err =
+            verify_aggregated_signature(&mainnet_tx, &signature, &pubkey_package).unwrap_err();
+        assert!(matches!(err, AggregateError::MinaVerification(_)));
+
+        // A missing share fails inside frost-core aggregation instead.
+        let mut missing_share = shares;
+        missing_share.pop_first();
+        let err = aggregate_and_verify(&transaction, commitments, &missing_share, &pubkey_package)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AggregateError>(),
+            Some(AggregateError::Aggregation(_))
+        ));
+    }
+
+    #[test]
+    fn test_shares_from_excluded_identifiers_are_ignored() {
+        let (transaction, commitments, mut shares, pubkey_package) =
+            sign_payment(NetworkId::Testnet);
+        let allowed: BTreeSet<_> = shares.keys().copied().collect();
+
+        // Participant 3 is not part of the session but sends a share anyway
+        let excluded = Identifier::try_from(3).unwrap();
+        let forged = *shares.values().next().unwrap();
+        shares.insert(excluded, forged);
+
+        let shares = retain_allowed(shares, &allowed);
+        assert!(!shares.contains_key(&excluded));
+        assert!(aggregate_and_verify(&transaction, commitments, &shares, &pubkey_package).is_ok());
+    }
+
+    /// Comms that answer round 1 and then stall in round 2 until the session is cancelled.
+    struct StallingComms {
+        commitments: Commitments,
+        shutdown: CancellationToken,
+        cleaned_up: bool,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl Comms<BluePallasSuite> for StallingComms {
+        async fn get_signing_commitments(
+            &mut self,
+            _input: &mut dyn std::io::BufRead,
+            _output: &mut dyn std::io::Write,
+            _pub_key_package: &PublicKeyPackage<BluePallasSuite>,
+            _num_of_participants: u16,
+        ) -> Result<Commitments, Box<dyn std::error::Error>> {
+            Ok(self.commitments.clone())
         }
-    };
 
-    let signature_bytes = match signature_bytes_result {
-        Ok(bytes) => bytes,
-        Err(e) => {
-            let _ = comms.cleanup_on_error().await;
-            return Err(e.into());
+        async fn send_signing_package_and_get_signature_shares(
+            &mut self,
+            _input: &mut dyn std::io::BufRead,
+            _output: &mut dyn std::io::Write,
+            _signing_package: &frost_core::SigningPackage<BluePallasSuite>,
+        ) -> Result<Shares, Box<dyn std::error::Error>> {
+            self.shutdown.cancel();
+            std::future::pending().await
         }
-    };
 
-    Ok(signature_bytes)
+        async fn cleanup_on_error(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+            self.cleaned_up = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_session_mid_round() {
+        let (transaction, commitments, _, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = config_for(&commitments, &transaction, pubkey_package);
+        let shutdown = CancellationToken::new();
+        let mut comms = StallingComms {
+            commitments,
+            shutdown: shutdown.clone(),
+            cleaned_up: false,
+        };
+
+        let err = coordinate_signing_with(
+            &mut comms,
+            &config,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &shutdown,
+            &mut |_| {},
+        )
+        .await
+        .unwrap_err();
+        assert!(err.downcast_ref::<Cancelled>().is_some());
+        assert!(comms.cleaned_up);
+    }
+
+    /// Comms that hand back pre-computed commitments and shares.
+    struct ScriptedComms {
+        commitments: Commitments,
+        shares: Shares,
+    }
+
+    #[async_trait::async_trait(?Send)]
+    impl Comms<BluePallasSuite> for ScriptedComms {
+        async fn get_signing_commitments(
+            &mut self,
+            _input: &mut dyn std::io::BufRead,
+            _output: &mut dyn std::io::Write,
+            _pub_key_package: &PublicKeyPackage<BluePallasSuite>,
+            _num_of_participants: u16,
+        ) -> Result<Commitments, Box<dyn std::error::Error>> {
+            Ok(self.commitments.clone())
+        }
+
+        async fn send_signing_package_and_get_signature_shares(
+            &mut self,
+            _input: &mut dyn std::io::BufRead,
+            _output: &mut dyn std::io::Write,
+            _signing_package: &frost_core::SigningPackage<BluePallasSuite>,
+        ) -> Result<Shares, Box<dyn std::error::Error>> {
+            Ok(self.shares.clone())
+        }
+
+        async fn use_pre_distributed_commitments(
+            &mut self,
+            _commitments: &Commitments,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+    }
+
+    fn config_for(
+        commitments: &Commitments,
+        transaction: &TransactionEnvelope,
+        pubkey_package: PublicKeyPackage<BluePallasSuite>,
+    ) -> Config<BluePallasSuite> {
+        Config {
+            signers: commitments
+                .keys()
+                .map(|id| (crate::cipher::Cipher::generate_keypair().unwrap().1, *id))
+                .collect(),
+            num_signers: 2,
+            public_key_package: pubkey_package,
+            message: transaction.serialize().unwrap(),
+            ip: "127.0.0.1".to_string(),
+            port: 443,
+            comm_privkey: None,
+            comm_pubkey: None,
+            audit_log: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_events_for_two_of_three_sign() {
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = config_for(&commitments, &transaction, pubkey_package);
+        let ids: Vec<_> = commitments.keys().copied().collect();
+        let mut comms = ScriptedComms {
+            commitments,
+            shares,
+        };
+
+        let mut events = Vec::new();
+        coordinate_signing_with(
+            &mut comms,
+            &config,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &CancellationToken::new(),
+            &mut |event| events.push(event),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                SigningProgress::CommitmentsCollected {
+                    received: 2,
+                    required: 2
+                },
+                SigningProgress::SharesCollected {
+                    received: 2,
+                    required: 2
+                },
+                SigningProgress::VerifyingShare(ids[0]),
+                SigningProgress::VerifyingShare(ids[1]),
+                SigningProgress::Aggregating,
+                SigningProgress::VerifyingSignature,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_one_round_sign_from_pre_distributed_commitments() {
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = config_for(&commitments, &transaction, pubkey_package.clone());
+        let mut pool = CommitmentPool::new();
+        for (id, commitment) in &commitments {
+            pool.add(*id, *commitment).unwrap();
+        }
+        let mut comms = ScriptedComms {
+            commitments: Default::default(),
+            shares,
+        };
+
+        let mut events = Vec::new();
+        let signature = coordinate_signing_from_pool(
+            &mut comms,
+            &config,
+            &mut pool,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &CancellationToken::new(),
+            &mut |event| events.push(event),
+        )
+        .await
+        .unwrap();
+
+        let signature = frost_core::Signature::<BluePallasSuite>::deserialize(&signature).unwrap();
+        assert!(verify_aggregated_signature(&transaction, &signature, &pubkey_package).is_ok());
+        assert!(commitments.keys().all(|id| pool.remaining(id) == 0));
+        assert_eq!(
+            events[0],
+            SigningProgress::CommitmentsCollected {
+                received: 2,
+                required: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pre_distributed_commitments_are_never_reused() {
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = config_for(&commitments, &transaction, pubkey_package);
+        let mut pool = CommitmentPool::new();
+        for (id, commitment) in &commitments {
+            pool.add(*id, *commitment).unwrap();
+        }
+        let mut comms = ScriptedComms {
+            commitments: Default::default(),
+            shares,
+        };
+        for expected_ok in [true, false] {
+            let result = coordinate_signing_from_pool(
+                &mut comms,
+                &config,
+                &mut pool,
+                &mut std::io::empty(),
+                &mut std::io::sink(),
+                &CancellationToken::new(),
+                &mut |_| {},
+            )
+            .await;
+            assert_eq!(result.is_ok(), expected_ok);
+            if let Err(err) = result {
+                assert!(matches!(
+                    err.downcast_ref::<CommitmentPoolError>(),
+                    Some(CommitmentPoolError::NotEnoughSigners {
+                        available: 0,
+                        required: 2
+                    })
+                ));
+            }
+        }
+
+        // The used commitments can neither be handed out again nor re-added
+        for (id, commitment) in &commitments {
+            assert!(matches!(
+                pool.add(*id, *commitment),
+                Err(CommitmentPoolError::AlreadyAdded(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pool_commitments_are_consumed_when_transport_refuses() {
+        let (transaction, commitments, _, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = config_for(&commitments, &transaction, pubkey_package);
+        let mut pool = CommitmentPool::new();
+        for (id, commitment) in &commitments {
+            pool.add(*id, *commitment).unwrap();
+        }
+        let shutdown = CancellationToken::new();
+        // StallingComms keeps the default, which refuses pre-distributed commitments
+        let mut comms = StallingComms {
+            commitments: commitments.clone(),
+            shutdown: shutdown.clone(),
+            cleaned_up: false,
+        };
+
+        assert!(coordinate_signing_from_pool(
+            &mut comms,
+            &config,
+            &mut pool,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &shutdown,
+            &mut |_| {},
+        )
+        .await
+        .is_err());
+        assert!(commitments.keys().all(|id| pool.remaining(id) == 0));
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_completed_and_failed_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit_path = dir.path().join("audit.jsonl");
+        let (transaction, commitments, shares, pubkey_package) = sign_payment(NetworkId::Testnet);
+        let config = Config {
+            audit_log: Some(audit_path.clone()),
+            ..config_for(&commitments, &transaction, pubkey_package)
+        };
+        let ids: Vec<String> = commitments
+            .keys()
+            .map(|id| hex::encode(id.serialize()))
+            .collect();
+
+        let mut comms = ScriptedComms {
+            commitments,
+            shares,
+        };
+        let signature = coordinate_signing_with(
+            &mut comms,
+            &config,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &CancellationToken::new(),
+            &mut |_| {},
+        )
+        .await
+        .unwrap();
+
+        // A second session whose signers never send their shares
+        comms.shares.clear();
+        assert!(coordinate_signing_with(
+            &mut comms,
+            &config,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            &CancellationToken::new(),
+            &mut |_| {},
+        )
+        .await
+        .is_err());
+
+        let log = std::fs::read_to_string(&audit_path).unwrap();
+        let records: Vec<AuditRecord> = log
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        let completed = &records[0];
+        assert_eq!(completed.outcome, AuditOutcome::Completed);
+        assert_eq!(completed.participants, ids);
+        assert_eq!(completed.message, hex::encode(&config.message));
+        assert_eq!(completed.signature, Some(hex::encode(&signature)));
+        assert_eq!(completed.error, None);
+        assert_eq!(completed.session_id, None);
+
+        let failed = &records[1];
+        assert_eq!(failed.outcome, AuditOutcome::Failed);
+        assert_eq!(failed.participants, ids);
+        assert_eq!(failed.signature, None);
+        assert!(failed.error.is_some());
+    }
 }
@@ -1,6 +1,15 @@
+pub mod audit_log;
+pub mod commitment_pool;
 pub mod comms;
 pub mod config;
 pub mod coordinate_signing;
+pub mod registry;
 
+pub use audit_log::{AuditLog, AuditOutcome, AuditRecord};
+pub use commitment_pool::{CommitmentPool, CommitmentPoolError};
 pub use config::Config;
-pub use coordinate_signing::coordinate_signing;
+pub use coordinate_signing::{
+    coordinate_signing, coordinate_signing_from_pool, coordinate_signing_with_progress,
+    SigningProgress,
+};
+pub use registry::{SessionOutcome, SessionRegistry};
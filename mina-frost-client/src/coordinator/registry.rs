@@ -0,0 +1,279 @@
+//! Running several signing sessions concurrently from one coordinator process.
+//!
+//! Every registered signing gets its own [`Comms`], which owns the frostd session, the
+//! collected commitments and the signature shares. frostd routes each message by session
+//! ID and each comms only polls its own session, so shares always reach the signing they
+//! were produced for, even when the same signers take part in several sessions at once.
+
+use std::{collections::HashMap, error::Error};
+
+use tokio::task::LocalSet;
+
+use super::comms::{http::HTTPComms, Comms};
+use super::config::Config;
+use super::coordinate_signing::coordinate_signing_with;
+use crate::api::Uuid;
+use crate::cancel::CancellationToken;
+use crate::BluePallasSuite;
+
+/// Result of one signing run by a [`SessionRegistry`]: the serialized group signature, as
+/// returned by [`coordinate_signing`](super::coordinate_signing).
+pub type SessionOutcome = Result<Vec<u8>, Box<dyn Error>>;
+
+struct RegisteredSession {
+    config: Config<BluePallasSuite>,
+    comms: Box<dyn Comms<BluePallasSuite>>,
+}
+
+/// Signing sessions to run side by side, keyed by the ID of the frostd session each one
+/// runs in.
+#[derive(Default)]
+pub struct SessionRegistry {
+    sessions: HashMap<Uuid, RegisteredSession>,
+}
+
+impl SessionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a signing of `config.message` to run over HTTP with frostd.
+    ///
+    /// The frostd session is created right away, so its ID can be handed to the
+    /// participants before [`SessionRegistry::run`].
+    pub async fn register(
+        &mut self,
+        config: Config<BluePallasSuite>,
+    ) -> Result<Uuid, Box<dyn Error>> {
+        let comms = Box::new(HTTPComms::new(&config)?);
+        self.register_with(config, comms).await
+    }
+
+    /// Add a signing that runs over the given `comms`.
+    pub(crate) async fn register_with(
+        &mut self,
+        config: Config<BluePallasSuite>,
+        mut comms: Box<dyn Comms<BluePallasSuite>>,
+    ) -> Result<Uuid, Box<dyn Error>> {
+        let session_id = comms.open_session().await?;
+        self.sessions
+            .insert(session_id, RegisteredSession { config, comms });
+        Ok(session_id)
+    }
+
+    /// The IDs of the registered sessions.
+    pub fn session_ids(&self) -> impl Iterator<Item = &Uuid> {
+        self.sessions.keys()
+    }
+
+    pub fn contains(&self, session_id: &Uuid) -> bool {
+        self.sessions.contains_key(session_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Run all registered signings concurrently and return their outcomes by session ID.
+    ///
+    /// A failing signing does not stop the others, and one that panics ends with an error
+    /// outcome of its own. Cancelling `shutdown` aborts every
+    /// session still in progress and closes it on the server.
+    pub async fn run(self, shutdown: &CancellationToken) -> HashMap<Uuid, SessionOutcome> {
+        // Comms are not `Send`, so the signings share this task instead of being spawned
+        let local = LocalSet::new();
+        let running: Vec<_> = self
+            .sessions
+            .into_iter()
+            .map(|(session_id, RegisteredSession { config, mut comms })| {
+                let shutdown = shutdown.clone();
+                let handle = local.spawn_local(async move {
+                    coordinate_signing_with(
+                        comms.as_mut(),
+                        &config,
+                        &mut std::io::empty(),
+                        &mut std::io::sink(),
+                        &shutdown,
+                        &mut |_| {},
+                    )
+                    .await
+                });
+                (session_id, handle)
+            })
+            .collect();
+
+        local
+            .run_until(async {
+                let mut outcomes = HashMap::new();
+                for (session_id, handle) in running {
+                    let outcome = handle
+                        .await
+                        .unwrap_or_else(|err| Err(format!("signing task failed: {err}").into()));
+                    outcomes.insert(session_id, outcome);
+                }
+                outcomes
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+        io::{BufRead, Write},
+    };
+
+    use async_trait::async_trait;
+    use frost_core::{
+        keys::PublicKeyPackage, round1::SigningCommitments, round2::SignatureShare, Identifier,
+        SigningPackage,
+    };
+
+    use super::SessionRegistry;
+    use crate::{
+        api::Uuid,
+        cancel::CancellationToken,
+        cipher::Cipher,
+        coordinator::comms::{http::HTTPComms, Comms},
+        mock_frostd::{MockFrostd, TestGroup},
+        BluePallasSuite,
+    };
+
+    /// Comms whose signing panics as soon as round one starts.
+    struct PanickingComms;
+
+    #[async_trait(?Send)]
+    impl Comms<BluePallasSuite> for PanickingComms {
+        async fn get_signing_commitments(
+            &mut self,
+            _input: &mut dyn BufRead,
+            _output: &mut dyn Write,
+            _pub_key_package: &PublicKeyPackage<BluePallasSuite>,
+            _num_of_participants: u16,
+        ) -> Result<
+            BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+            Box<dyn Error>,
+        > {
+            panic!("comms broke down");
+        }
+
+        async fn send_signing_package_and_get_signature_shares(
+            &mut self,
+            _input: &mut dyn BufRead,
+            _output: &mut dyn Write,
+            _signing_package: &SigningPackage<BluePallasSuite>,
+        ) -> Result<
+            BTreeMap<Identifier<BluePallasSuite>, SignatureShare<BluePallasSuite>>,
+            Box<dyn Error>,
+        > {
+            unreachable!("round one never completes")
+        }
+
+        async fn open_session(&mut self) -> Result<Uuid, Box<dyn Error>> {
+            Ok(Uuid::new_v4())
+        }
+    }
+
+    #[tokio::test]
+    async fn overlapping_sessions_produce_their_own_signatures() {
+        let server = MockFrostd::start().await;
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let groups = [TestGroup::new(1_000), TestGroup::new(2_000)];
+
+        let mut registry = SessionRegistry::new();
+        let mut session_ids = Vec::new();
+        for group in &groups {
+            let config = group.coordinator_config(&coordinator_keys);
+            let comms = HTTPComms::with_client(&config, server.client()).unwrap();
+            session_ids.push(
+                registry
+                    .register_with(config, Box::new(comms))
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert_eq!(registry.len(), 2);
+        assert_ne!(session_ids[0], session_ids[1]);
+        assert!(session_ids.iter().all(|id| registry.contains(id)));
+
+        let shutdown = CancellationToken::new();
+        let mut signers = groups
+            .iter()
+            .flat_map(|group| group.signers.iter().cloned())
+            .map(|signer| {
+                server.participate(
+                    server.client(),
+                    signer,
+                    coordinator_keys.1.clone(),
+                    None,
+                    &shutdown,
+                )
+            });
+        let (outcomes, first, second, third, fourth) = tokio::join!(
+            registry.run(&shutdown),
+            signers.next().unwrap(),
+            signers.next().unwrap(),
+            signers.next().unwrap(),
+            signers.next().unwrap(),
+        );
+
+        for participated in [first, second, third, fourth] {
+            participated.unwrap();
+        }
+        assert_eq!(outcomes.len(), 2);
+        for (group, session_id) in groups.iter().zip(&session_ids) {
+            group.assert_signed(outcomes[session_id].as_ref().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn panicking_session_ends_with_its_own_error() {
+        let server = MockFrostd::start().await;
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let group = TestGroup::new(1_000);
+        let broken = TestGroup::new(2_000);
+
+        let mut registry = SessionRegistry::new();
+        let config = group.coordinator_config(&coordinator_keys);
+        let comms = HTTPComms::with_client(&config, server.client()).unwrap();
+        let session_id = registry
+            .register_with(config, Box::new(comms))
+            .await
+            .unwrap();
+        let broken_id = registry
+            .register_with(
+                broken.coordinator_config(&coordinator_keys),
+                Box::new(PanickingComms),
+            )
+            .await
+            .unwrap();
+
+        let shutdown = CancellationToken::new();
+        let mut signers = group.signers.iter().cloned().map(|signer| {
+            server.participate(
+                server.client(),
+                signer,
+                coordinator_keys.1.clone(),
+                None,
+                &shutdown,
+            )
+        });
+        let (outcomes, first, second) = tokio::join!(
+            registry.run(&shutdown),
+            signers.next().unwrap(),
+            signers.next().unwrap(),
+        );
+
+        first.unwrap();
+        second.unwrap();
+        group.assert_signed(outcomes[&session_id].as_ref().unwrap());
+        let err = outcomes[&broken_id].as_ref().unwrap_err();
+        assert!(err.to_string().contains("signing task failed"), "{err}");
+    }
+}
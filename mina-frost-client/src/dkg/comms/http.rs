@@ -41,7 +41,6 @@ use crate::{
     session::DKGSessionState,
 };
 use crate::{cipher::Cipher, dkg::config::Config};
-use rand::thread_rng;
 
 /// This struct manages HTTP client connections to a coordination server,
 /// handling authentication, session management, and secure message exchange
@@ -87,21 +86,13 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
         _input: &mut dyn BufRead,
         _output: &mut dyn Write,
     ) -> Result<(Identifier<C>, u16), Box<dyn Error>> {
-        // Initialize cryptographically secure random number generator
-        let mut rng = thread_rng();
-
         // --------------- Authentication with Challenge-Response-----------------
         eprintln!("Logging in...");
-        // Request authentication challenge from server
-        let challenge = self.client.challenge().await?.challenge;
-
-        // Sign the challenge with our private communication key
-        let signature: [u8; 64] = self
+        let comm_privkey = self
             .config
             .comm_privkey
             .clone()
-            .ok_or_eyre("comm_privkey must be specified")?
-            .sign(challenge.as_bytes(), &mut rng)?;
+            .ok_or_eyre("comm_privkey must be specified")?;
 
         // Get our public key for server verification
         let comm_pubkey = self
@@ -110,13 +101,9 @@ impl<C: Ciphersuite + 'static> Comms<C> for HTTPComms<C> {
             .clone()
             .ok_or_eyre("comm_pubkey must be specified")?;
 
-        // Submit signed challenge to complete authentication
+        // Sign a fresh challenge; the client keeps the key pair to log in again if needed
         self.client
-            .login(&api::LoginArgs {
-                challenge,
-                pubkey: comm_pubkey.clone(),
-                signature: signature.to_vec(),
-            })
+            .authenticate(&comm_privkey, &comm_pubkey)
             .await?;
 
         // -------------- Session Management --------------------
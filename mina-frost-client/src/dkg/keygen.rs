@@ -1,6 +1,7 @@
 use frost_core::keys::{KeyPackage, PublicKeyPackage};
 use frost_core::{self as frost, Ciphersuite, Identifier};
 
+use crate::cancel::{cancellable, CancellationToken};
 use crate::cipher::PublicKey;
 use rand::thread_rng;
 use std::collections::HashMap;
@@ -24,6 +25,7 @@ use super::config::Config;
 /// * `config` - DKG configuration including network settings, participant info, and threshold
 /// * `input` - Input stream for user interaction (e.g., confirmations)
 /// * `logger` - Output stream for logging progress and status messages
+/// * `shutdown` - Cancelling it aborts the protocol and closes the session
 ///
 /// # Returns
 ///
@@ -43,6 +45,7 @@ pub async fn keygen<C: Ciphersuite + 'static>(
     config: Config,
     input: &mut impl BufRead,
     logger: &mut impl Write,
+    shutdown: &CancellationToken,
 ) -> Result<
     (
         KeyPackage<C>,
@@ -78,7 +81,7 @@ pub async fn keygen<C: Ciphersuite + 'static>(
         Ok((key_package, public_key_package, pubkey_map))
     };
 
-    let result = doit.await;
+    let result = cancellable(shutdown, "key generation", doit).await;
     if result.is_err() {
         let _ = comms.cleanup_on_error().await;
     }
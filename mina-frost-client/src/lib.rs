@@ -5,13 +5,17 @@ use frost_bluepallas::BluePallas;
 use mina_tx::pallas_message::PallasMessage;
 
 pub mod api;
+pub mod cancel;
 pub mod cipher;
 pub mod cli;
 pub mod client;
 pub mod coordinator;
 pub mod dkg;
+#[cfg(test)]
+pub(crate) mod mock_frostd;
 pub mod participant;
 pub mod session;
+pub mod signing_request;
 pub mod trusted_dealer;
 
 pub type BluePallasSuite = BluePallas<PallasMessage>;
@@ -1,8 +1,10 @@
 use std::error::Error;
 
 use clap::Parser;
+use mina_frost_client::cancel::CancellationToken;
 use mina_frost_client::cli;
 use mina_frost_client::cli::args::{Args, Command};
+use mina_frost_client::cli::output::{error_chain, render_json, OutputFormat, Report};
 use mina_frost_client::BluePallasSuite;
 
 #[tokio::main]
@@ -10,26 +12,74 @@ async fn main() -> Result<(), Box<dyn Error>> {
     stable_eyre::install()?;
     let args = Args::parse();
 
-    match args.command {
+    // Ctrl-C cancels a running session so it can be closed on the server instead of
+    // killing the process. Other commands keep the default behaviour.
+    let shutdown = CancellationToken::new();
+    if matches!(
+        args.command,
+        Command::Sessions { .. }
+            | Command::Dkg { .. }
+            | Command::Coordinator { .. }
+            | Command::Participant { .. }
+    ) {
+        tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    shutdown.cancel();
+                }
+            }
+        });
+    }
+
+    let result: Result<Report, Box<dyn Error>> = match args.command {
         Command::Init { .. } => cli::init::init::<BluePallasSuite>(&args.command).await,
         Command::Export { .. } => cli::contact::export::<BluePallasSuite>(&args.command),
         Command::Import { .. } => cli::contact::import::<BluePallasSuite>(&args.command),
+        Command::ImportBulk { .. } => {
+            cli::contact::import_bulk::<BluePallasSuite>(&args.command).map(|s| s.to_report())
+        }
         Command::Contacts { .. } => cli::contact::list::<BluePallasSuite>(&args.command),
         Command::RemoveContact { .. } => cli::contact::remove::<BluePallasSuite>(&args.command),
         Command::Groups { .. } => cli::group::list::<BluePallasSuite>(&args.command),
         Command::RemoveGroup { .. } => cli::group::remove::<BluePallasSuite>(&args.command),
-        Command::Sessions { .. } => cli::session::list::<BluePallasSuite>(&args.command).await,
-        Command::TrustedDealer { .. } => cli::trusted_dealer::run::<BluePallasSuite>(&args.command),
-        Command::Dkg { .. } => cli::dkg::run::<BluePallasSuite>(&args.command).await,
+        Command::Address { .. } => cli::group::address(&args.command, args.output),
+        Command::Sessions { .. } => {
+            cli::session::list::<BluePallasSuite>(&args.command, &shutdown).await
+        }
+        Command::TrustedDealer { .. } => cli::trusted_dealer::run_with_ciphersuite(&args.command),
+        Command::Dkg { .. } => cli::dkg::run::<BluePallasSuite>(&args.command, &shutdown).await,
         // Coordinator implicitly assumes within the run() function that we use BluePallas
-        Command::Coordinator { .. } => cli::coordinator::run_bluepallas(&args.command).await,
+        Command::Coordinator { .. } => {
+            cli::coordinator::run_bluepallas(&args.command, args.output, &shutdown).await
+        }
         // Participant implicitly assumes within the run() function that we use BluePallas
-        Command::Participant { .. } => cli::participant::run_bluepallas(&args.command).await,
+        Command::Participant { .. } => {
+            cli::participant::run_bluepallas(&args.command, &shutdown).await
+        }
+        Command::OfflineCommit { .. } => cli::offline::commit(&args.command),
+        Command::OfflineSign { .. } => cli::offline::sign(&args.command),
+        Command::OfflineAggregate { .. } => cli::offline::aggregate(&args.command, args.output),
         Command::GraphqlBuild { .. } => cli::graphql::graphql_build_command(&args.command),
         Command::GraphqlBroadcast { .. } => {
             cli::graphql::graphql_broadcast_command(&args.command).await
         }
-    }?;
+    };
 
-    Ok(())
+    match args.output {
+        OutputFormat::Text => {
+            if let Err(e) = result {
+                eprintln!("Error: {}", error_chain(e.as_ref()));
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        OutputFormat::Json => {
+            println!("{}", render_json(&result));
+            if result.is_err() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,931 @@
+//! An in-process stand-in for `frostd`, used to run signing sessions end to end
+//! in tests without spawning the real server.
+//!
+//! It speaks plain HTTP/1.1 on a loopback port and implements the calls made by
+//! [`Client`]. Login signatures are not checked: any challenge handed out by
+//! the server is accepted for any public key.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    net::SocketAddr,
+    path::Path,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+use frost_bluepallas::keys::generate_with_dealer;
+use frost_core::{
+    keys::{IdentifierList, KeyPackage, PublicKeyPackage},
+    round1::{SigningCommitments, SigningNonces},
+    Signature,
+};
+use mina_tx::{
+    legacy_tx::LegacyTransaction,
+    network_id::NetworkId,
+    pallas_message::{translate_pk, PallasMessage},
+    TransactionEnvelope,
+};
+use rand::thread_rng;
+
+use crate::{
+    api::{self, Msg},
+    cancel::CancellationToken,
+    cipher::{Cipher, PrivateKey, PublicKey},
+    client::Client,
+    coordinator::{self, coordinate_signing::coordinate_signing_with, SigningProgress},
+    participant::{self, sign::sign_over, Confirmation},
+    BluePallasSuite,
+};
+
+struct Session {
+    coordinator: PublicKey,
+    pubkeys: Vec<PublicKey>,
+    message_count: u8,
+    coordinator_queue: VecDeque<Msg>,
+    queues: HashMap<PublicKey, VecDeque<Msg>>,
+}
+
+impl Session {
+    fn is_member(&self, pubkey: &PublicKey) -> bool {
+        self.coordinator == *pubkey || self.pubkeys.contains(pubkey)
+    }
+}
+
+#[derive(Default)]
+struct State {
+    challenges: HashSet<Uuid>,
+    tokens: HashMap<Uuid, PublicKey>,
+    sessions: HashMap<Uuid, Session>,
+}
+
+/// A running mock server. It stops when dropped.
+pub(crate) struct MockFrostd {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    server: Mutex<JoinHandle<()>>,
+}
+
+impl MockFrostd {
+    /// Start serving on a random loopback port.
+    pub(crate) async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("loopback port must be available");
+        let addr = listener.local_addr().expect("listener must be bound");
+        let state = Arc::new(Mutex::new(State::default()));
+        let server = Mutex::new(serve(listener, state.clone()));
+        Self {
+            addr,
+            state,
+            server,
+        }
+    }
+
+    /// Simulate the server going away for `downtime`: connections are refused meanwhile,
+    /// and every access token is forgotten so clients have to log in again. Sessions and
+    /// their queued messages are kept.
+    pub(crate) async fn restart(&self, downtime: Duration) {
+        self.server.lock().unwrap().abort();
+        self.state.lock().unwrap().tokens.clear();
+        tokio::time::sleep(downtime).await;
+        let listener = TcpListener::bind(self.addr)
+            .await
+            .expect("loopback port must be available again");
+        *self.server.lock().unwrap() = serve(listener, self.state.clone());
+    }
+
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A client pointed at this server.
+    pub(crate) fn client(&self) -> Client {
+        Client::new(self.url())
+    }
+
+    /// Wait until a coordinator has created a session and return its ID.
+    pub(crate) async fn wait_for_session(&self) -> Uuid {
+        self.wait_for_matching_session(|_| true).await
+    }
+
+    /// Wait until a coordinator has created a session including `pubkey` and return its ID.
+    pub(crate) async fn wait_for_session_of(&self, pubkey: &PublicKey) -> Uuid {
+        self.wait_for_matching_session(|session| session.pubkeys.contains(pubkey))
+            .await
+    }
+
+    /// Whether `pubkey` holds an access token, i.e. logged in and did not log out.
+    pub(crate) fn is_logged_in(&self, pubkey: &PublicKey) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .tokens
+            .values()
+            .any(|logged_in| logged_in == pubkey)
+    }
+
+    async fn wait_for_matching_session(&self, matches: impl Fn(&Session) -> bool) -> Uuid {
+        loop {
+            let session_id = self
+                .state
+                .lock()
+                .unwrap()
+                .sessions
+                .iter()
+                .find(|(_, session)| matches(session))
+                .map(|(session_id, _)| *session_id);
+            if let Some(session_id) = session_id {
+                return session_id;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+}
+
+impl Drop for MockFrostd {
+    fn drop(&mut self) {
+        self.server.lock().unwrap().abort();
+    }
+}
+
+fn serve(listener: TcpListener, state: Arc<Mutex<State>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_connection(stream, state.clone()));
+        }
+    })
+}
+
+struct Request {
+    method: String,
+    token: Option<Uuid>,
+    body: Vec<u8>,
+}
+
+/// Answer a single request. Connections are not kept alive, so that once the listener
+/// is gone in [`MockFrostd::restart`] every later call fails to connect.
+async fn serve_connection(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let mut stream = BufReader::new(stream);
+    let Ok(Some(request)) = read_request(&mut stream).await else {
+        return;
+    };
+    let (status, body) = handle(&state, &request);
+    let head = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        body.len()
+    );
+    let socket = stream.get_mut();
+    if socket.write_all(head.as_bytes()).await.is_ok() {
+        let _ = socket.write_all(&body).await;
+    }
+}
+
+/// Read one `POST /<method>` request, or `None` once the peer closed the connection.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> std::io::Result<Option<Request>> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let method = line
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or_default()
+        .trim_start_matches('/')
+        .to_string();
+
+    let mut content_length = 0;
+    let mut token = None;
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.parse().unwrap_or(0),
+            "authorization" => {
+                token = value
+                    .strip_prefix("Bearer ")
+                    .and_then(|t| Uuid::parse_str(t).ok())
+            }
+            _ => {}
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    stream.read_exact(&mut body).await?;
+    Ok(Some(Request {
+        method,
+        token,
+        body,
+    }))
+}
+
+/// Answer a request the way frostd does: errors are a 500 carrying a [`api::LowError`].
+fn handle(state: &Mutex<State>, request: &Request) -> (&'static str, Vec<u8>) {
+    let mut state = state.lock().unwrap();
+    match dispatch(&mut state, request) {
+        Ok(body) => ("200 OK", body),
+        Err(api::Error::Unauthorized) => ("401 Unauthorized", Vec::new()),
+        Err(err) => (
+            "500 Internal Server Error",
+            serde_json::to_vec(&api::LowError::from(err)).expect("LowError must serialize"),
+        ),
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, api::Error> {
+    serde_json::from_slice(body).map_err(|e| api::Error::InvalidArgument(e.to_string()))
+}
+
+fn reply<T: Serialize>(output: &T) -> Result<Vec<u8>, api::Error> {
+    Ok(serde_json::to_vec(output).expect("API output must serialize"))
+}
+
+fn dispatch(state: &mut State, request: &Request) -> Result<Vec<u8>, api::Error> {
+    match request.method.as_str() {
+        "challenge" => {
+            let challenge = Uuid::new_v4();
+            state.challenges.insert(challenge);
+            return reply(&api::ChallengeOutput { challenge });
+        }
+        "login" => {
+            let args: api::LoginArgs = parse(&request.body)?;
+            if !state.challenges.remove(&args.challenge) {
+                return Err(api::Error::Unauthorized);
+            }
+            let access_token = Uuid::new_v4();
+            state.tokens.insert(access_token, args.pubkey);
+            return reply(&api::LoginOutput { access_token });
+        }
+        _ => {}
+    }
+
+    let token = request.token.ok_or(api::Error::Unauthorized)?;
+    let pubkey = state
+        .tokens
+        .get(&token)
+        .cloned()
+        .ok_or(api::Error::Unauthorized)?;
+
+    match request.method.as_str() {
+        "logout" => {
+            state.tokens.remove(&token);
+            Ok(Vec::new())
+        }
+        "create_new_session" => {
+            let args: api::CreateNewSessionArgs = parse(&request.body)?;
+            let session_id = Uuid::new_v4();
+            state.sessions.insert(
+                session_id,
+                Session {
+                    coordinator: pubkey,
+                    queues: args
+                        .pubkeys
+                        .iter()
+                        .map(|pubkey| (pubkey.clone(), VecDeque::new()))
+                        .collect(),
+                    pubkeys: args.pubkeys,
+                    message_count: args.message_count,
+                    coordinator_queue: VecDeque::new(),
+                },
+            );
+            reply(&api::CreateNewSessionOutput { session_id })
+        }
+        "list_sessions" => {
+            let session_ids = state
+                .sessions
+                .iter()
+                .filter(|(_, session)| session.is_member(&pubkey))
+                .map(|(session_id, _)| *session_id)
+                .collect();
+            reply(&api::ListSessionsOutput { session_ids })
+        }
+        "get_session_info" => {
+            let args: api::GetSessionInfoArgs = parse(&request.body)?;
+            let session = member_session(state, &args.session_id, &pubkey)?;
+            reply(&api::GetSessionInfoOutput {
+                message_count: session.message_count,
+                pubkeys: session.pubkeys.clone(),
+                coordinator_pubkey: session.coordinator.clone(),
+            })
+        }
+        "send" => {
+            let args: api::SendArgs = parse(&request.body)?;
+            let session = member_session(state, &args.session_id, &pubkey)?;
+            let msg = Msg {
+                sender: pubkey,
+                msg: args.msg,
+            };
+            if args.recipients.is_empty() {
+                session.coordinator_queue.push_back(msg);
+            } else {
+                for recipient in &args.recipients {
+                    session
+                        .queues
+                        .get_mut(recipient)
+                        .ok_or(api::Error::NotInSession)?
+                        .push_back(msg.clone());
+                }
+            }
+            Ok(Vec::new())
+        }
+        "receive" => {
+            let args: api::ReceiveArgs = parse(&request.body)?;
+            let session = member_session(state, &args.session_id, &pubkey)?;
+            let queue = if args.as_coordinator {
+                if session.coordinator != pubkey {
+                    return Err(api::Error::NotCoordinator);
+                }
+                &mut session.coordinator_queue
+            } else {
+                session
+                    .queues
+                    .get_mut(&pubkey)
+                    .ok_or(api::Error::NotInSession)?
+            };
+            reply(&api::ReceiveOutput {
+                msgs: queue.drain(..).collect(),
+            })
+        }
+        "close_session" => {
+            let args: api::CloseSessionArgs = parse(&request.body)?;
+            let session = member_session(state, &args.session_id, &pubkey)?;
+            if session.coordinator != pubkey {
+                return Err(api::Error::NotCoordinator);
+            }
+            state.sessions.remove(&args.session_id);
+            Ok(Vec::new())
+        }
+        other => Err(api::Error::InvalidArgument(format!(
+            "unknown method {other}"
+        ))),
+    }
+}
+
+fn member_session<'a>(
+    state: &'a mut State,
+    session_id: &Uuid,
+    pubkey: &PublicKey,
+) -> Result<&'a mut Session, api::Error> {
+    let session = state
+        .sessions
+        .get_mut(session_id)
+        .ok_or(api::Error::SessionNotFound)?;
+    if !session.is_member(pubkey) {
+        return Err(api::Error::NotInSession);
+    }
+    Ok(session)
+}
+
+/// A fresh 2-of-3 group with a payment to sign, and the signers taking part in its
+/// sessions along with their communication keys.
+pub(crate) struct TestGroup {
+    pub(crate) pubkey_package: PublicKeyPackage<BluePallasSuite>,
+    pub(crate) signers: Vec<(KeyPackage<BluePallasSuite>, (PrivateKey, PublicKey))>,
+    pub(crate) transaction: TransactionEnvelope,
+}
+
+impl TestGroup {
+    /// Generate the group with two signers, paying `amount` from the group's account to
+    /// itself.
+    pub(crate) fn new(amount: u64) -> Self {
+        Self::with_signers(amount, 2)
+    }
+
+    /// Same as [`TestGroup::new`], but with `count` of the three members as signers.
+    pub(crate) fn with_signers(amount: u64, count: usize) -> Self {
+        let (shares, pubkey_package) = generate_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            IdentifierList::Default,
+            &mut thread_rng(),
+        )
+        .unwrap();
+        let key_packages = shares
+            .into_values()
+            .take(count)
+            .map(|share| KeyPackage::try_from(share).unwrap());
+        Self::from_key_packages(pubkey_package, key_packages, amount)
+    }
+
+    /// A group with existing keys, with `key_packages` as its signers, paying `amount` from
+    /// the group's account to itself.
+    pub(crate) fn from_key_packages(
+        pubkey_package: PublicKeyPackage<BluePallasSuite>,
+        key_packages: impl IntoIterator<Item = KeyPackage<BluePallasSuite>>,
+        amount: u64,
+    ) -> Self {
+        let signers = key_packages
+            .into_iter()
+            .map(|key_package| (key_package, Cipher::generate_keypair().unwrap()))
+            .collect();
+        let group_pk = translate_pk(pubkey_package.verifying_key()).unwrap();
+        let transaction = TransactionEnvelope::new_legacy(
+            NetworkId::Testnet,
+            LegacyTransaction::new_payment(group_pk.clone(), group_pk, amount, 1_000, 0),
+        );
+        Self {
+            pubkey_package,
+            signers,
+            transaction,
+        }
+    }
+
+    /// The signers' communication public keys.
+    pub(crate) fn signer_pubkeys(&self) -> Vec<PublicKey> {
+        self.signers
+            .iter()
+            .map(|(_, (_, pubkey))| pubkey.clone())
+            .collect()
+    }
+
+    /// A coordinator config for signing the transaction with any two of the signers, as
+    /// the coordinator holding `comm_keys`.
+    pub(crate) fn coordinator_config(
+        &self,
+        (privkey, pubkey): &(PrivateKey, PublicKey),
+    ) -> coordinator::Config<BluePallasSuite> {
+        coordinator::Config {
+            signers: self
+                .signers
+                .iter()
+                .map(|(key_package, (_, pubkey))| (pubkey.clone(), *key_package.identifier()))
+                .collect::<HashMap<_, _>>(),
+            num_signers: 2,
+            public_key_package: self.pubkey_package.clone(),
+            message: self.transaction.serialize().unwrap(),
+            ip: "127.0.0.1".to_string(),
+            port: 0,
+            comm_privkey: Some(privkey.clone()),
+            comm_pubkey: Some(pubkey.clone()),
+            audit_log: None,
+        }
+    }
+
+    /// Assert that `signature` is the group's signature of the transaction.
+    pub(crate) fn assert_signed(&self, signature: &[u8]) {
+        let signature = Signature::<BluePallasSuite>::deserialize(signature).unwrap();
+        let message = self.transaction.to_pallas_message().serialize().unwrap();
+        assert!(self
+            .pubkey_package
+            .verifying_key()
+            .verify(&message, &signature)
+            .is_ok());
+    }
+}
+
+impl MockFrostd {
+    /// Coordinate a signing with `config` through `client`.
+    pub(crate) async fn coordinate(
+        &self,
+        client: Client,
+        config: &coordinator::Config<BluePallasSuite>,
+        shutdown: &CancellationToken,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.coordinate_with_progress(client, config, shutdown, &mut |_| {})
+            .await
+    }
+
+    /// [`MockFrostd::coordinate`], reporting each step of the session to `progress`.
+    pub(crate) async fn coordinate_with_progress(
+        &self,
+        client: Client,
+        config: &coordinator::Config<BluePallasSuite>,
+        shutdown: &CancellationToken,
+        progress: &mut dyn FnMut(SigningProgress),
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut comms = coordinator::comms::http::HTTPComms::with_client(config, client)?;
+        coordinate_signing_with(
+            &mut comms,
+            config,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            shutdown,
+            progress,
+        )
+        .await
+    }
+
+    /// Wait for a session including `signer` and take part in it through `client`.
+    ///
+    /// With an `answer`, the signer is prompted to confirm and reads `answer`; without
+    /// one, it signs without asking.
+    pub(crate) async fn participate(
+        &self,
+        client: Client,
+        signer: (KeyPackage<BluePallasSuite>, (PrivateKey, PublicKey)),
+        coordinator_pubkey: PublicKey,
+        answer: Option<&str>,
+        shutdown: &CancellationToken,
+    ) -> Result<(), Box<dyn Error>> {
+        self.participate_with(client, signer, coordinator_pubkey, answer, None, shutdown)
+            .await
+    }
+
+    /// [`MockFrostd::participate`], recording used nonces at `used_nonces_path` if given.
+    pub(crate) async fn participate_with(
+        &self,
+        client: Client,
+        signer: (KeyPackage<BluePallasSuite>, (PrivateKey, PublicKey)),
+        coordinator_pubkey: PublicKey,
+        answer: Option<&str>,
+        used_nonces_path: Option<&Path>,
+        shutdown: &CancellationToken,
+    ) -> Result<(), Box<dyn Error>> {
+        let config = self
+            .participant_config(signer, coordinator_pubkey, used_nonces_path)
+            .await;
+        let (nonces, commitments) =
+            frost_bluepallas::round1::commit(config.key_package.signing_share(), &mut thread_rng());
+        let mut comms = participant::comms::http::HTTPComms::with_client(&config, client)?;
+        let confirmation = match answer {
+            Some(_) => Confirmation::Prompt,
+            None => Confirmation::Skip,
+        };
+        sign_over(
+            &mut comms,
+            &config,
+            &nonces,
+            commitments,
+            &mut answer.unwrap_or_default().as_bytes(),
+            &mut std::io::sink(),
+            confirmation,
+            shutdown,
+        )
+        .await
+    }
+
+    /// [`MockFrostd::participate`] in a session run from pre-distributed commitments,
+    /// signing without asking with the `nonces` behind the commitments the signer handed
+    /// out ahead of time.
+    pub(crate) async fn participate_pre_committed(
+        &self,
+        client: Client,
+        signer: (KeyPackage<BluePallasSuite>, (PrivateKey, PublicKey)),
+        coordinator_pubkey: PublicKey,
+        (nonces, commitments): (
+            SigningNonces<BluePallasSuite>,
+            SigningCommitments<BluePallasSuite>,
+        ),
+        shutdown: &CancellationToken,
+    ) -> Result<(), Box<dyn Error>> {
+        let config = self
+            .participant_config(signer, coordinator_pubkey, None)
+            .await;
+        let mut comms =
+            participant::comms::http::HTTPComms::with_client(&config, client)?.pre_committed();
+        sign_over(
+            &mut comms,
+            &config,
+            &nonces,
+            commitments,
+            &mut std::io::empty(),
+            &mut std::io::sink(),
+            Confirmation::Skip,
+            shutdown,
+        )
+        .await
+    }
+
+    /// Wait for a session including `signer` and return its participant config.
+    async fn participant_config(
+        &self,
+        (key_package, (privkey, pubkey)): (KeyPackage<BluePallasSuite>, (PrivateKey, PublicKey)),
+        coordinator_pubkey: PublicKey,
+        used_nonces_path: Option<&Path>,
+    ) -> participant::Config<BluePallasSuite> {
+        let session_id = self.wait_for_session_of(&pubkey).await;
+        participant::Config {
+            key_package,
+            ip: "127.0.0.1".to_string(),
+            port: 0,
+            session_id: session_id.to_string(),
+            comm_privkey: Some(privkey),
+            comm_pubkey: Some(pubkey),
+            comm_coordinator_pubkey_getter: Some(Rc::new(move |pubkey: &PublicKey| {
+                (*pubkey == coordinator_pubkey).then(|| pubkey.clone())
+            })),
+            used_nonces_path: used_nonces_path.map(Path::to_path_buf),
+            network_check: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::thread_rng;
+    use tokio::sync::Notify;
+
+    use super::{MockFrostd, TestGroup};
+    use crate::{
+        cancel::{CancellationToken, Cancelled},
+        cipher::Cipher,
+        client::RetryPolicy,
+        coordinator::{
+            comms::http::HTTPComms, coordinate_signing_from_pool, CommitmentPool, SigningProgress,
+        },
+        participant::SigningDeclined,
+        session::ParticipantAborted,
+    };
+
+    #[tokio::test]
+    async fn two_of_three_signing_session_over_mock_frostd() {
+        let server = MockFrostd::start().await;
+        let group = TestGroup::new(1_000);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+
+        let shutdown = CancellationToken::new();
+        let participate = |index: usize| {
+            server.participate(
+                server.client(),
+                group.signers[index].clone(),
+                coordinator_keys.1.clone(),
+                None,
+                &shutdown,
+            )
+        };
+        let (signature, first, second) = tokio::join!(
+            server.coordinate(server.client(), &config, &shutdown),
+            participate(0),
+            participate(1),
+        );
+
+        first.unwrap();
+        second.unwrap();
+        group.assert_signed(&signature.unwrap());
+    }
+
+    #[tokio::test]
+    async fn signing_session_survives_a_mock_frostd_restart() {
+        let server = MockFrostd::start().await;
+        let retry_policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(500),
+        };
+        let client = || server.client().with_retry_policy(retry_policy.clone());
+        let group = TestGroup::new(1_000);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+
+        let shutdown = CancellationToken::new();
+        let participate = |index: usize| {
+            server.participate(
+                client(),
+                group.signers[index].clone(),
+                coordinator_keys.1.clone(),
+                None,
+                &shutdown,
+            )
+        };
+
+        // Go away for longer than a polling interval once all parties are in the session,
+        // so that every party hits a refused connection and then a stale access token
+        let pubkeys = group.signer_pubkeys();
+        let restart = async {
+            while !pubkeys.iter().all(|pubkey| server.is_logged_in(pubkey)) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            server.restart(Duration::from_millis(2500)).await;
+        };
+
+        let (signature, first, second, ()) = tokio::join!(
+            server.coordinate(client(), &config, &shutdown),
+            participate(0),
+            participate(1),
+            restart,
+        );
+
+        first.unwrap();
+        second.unwrap();
+        group.assert_signed(&signature.unwrap());
+    }
+
+    #[tokio::test]
+    async fn cancelled_participant_logs_out_of_mock_frostd() {
+        let server = MockFrostd::start().await;
+        let group = TestGroup::new(1_000);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+        let pubkey = group.signer_pubkeys()[0].clone();
+
+        // Only the first signer joins, so the coordinator keeps waiting for commitments
+        let coordinator_shutdown = CancellationToken::new();
+        let participant_shutdown = CancellationToken::new();
+        let cancel = async {
+            while !server.is_logged_in(&pubkey) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            participant_shutdown.cancel();
+            while server.is_logged_in(&pubkey) {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            coordinator_shutdown.cancel();
+        };
+
+        let (coordinated, participated, ()) = tokio::join!(
+            server.coordinate(server.client(), &config, &coordinator_shutdown),
+            server.participate(
+                server.client(),
+                group.signers[0].clone(),
+                coordinator_keys.1.clone(),
+                None,
+                &participant_shutdown,
+            ),
+            cancel,
+        );
+        let err = participated.unwrap_err();
+        assert!(err.downcast_ref::<Cancelled>().is_some());
+        assert!(!server.is_logged_in(&pubkey));
+        assert!(coordinated.is_err());
+        // The coordinator closed the session it was left waiting on
+        assert!(server.state.lock().unwrap().sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn declining_participant_aborts_the_session() {
+        let server = MockFrostd::start().await;
+        let group = TestGroup::new(1_000);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+
+        let shutdown = CancellationToken::new();
+        let participate = |index: usize, answer: &'static str| {
+            server.participate(
+                server.client(),
+                group.signers[index].clone(),
+                coordinator_keys.1.clone(),
+                Some(answer),
+                &shutdown,
+            )
+        };
+        let (coordinated, accepted, declined) = tokio::join!(
+            server.coordinate(server.client(), &config, &shutdown),
+            participate(0, "y\n"),
+            participate(1, "n\n"),
+        );
+
+        accepted.unwrap();
+        assert!(declined
+            .unwrap_err()
+            .downcast_ref::<SigningDeclined>()
+            .is_some());
+        let err = coordinated.unwrap_err();
+        let aborted = err.downcast_ref::<ParticipantAborted>().unwrap();
+        assert_eq!(aborted.reason, "signing declined");
+        // The coordinator closed the session on the abort
+        assert!(server.state.lock().unwrap().sessions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn abort_after_signing_package_restarts_round_with_fresh_nonces() {
+        let server = MockFrostd::start().await;
+        let group = TestGroup::with_signers(1_000, 3);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+        let dir = tempfile::tempdir().unwrap();
+        let used_nonces = dir.path().join("used-nonces.json");
+
+        let shutdown = CancellationToken::new();
+        let restarted = Notify::new();
+        let mut events = Vec::new();
+        let mut on_progress = |event: SigningProgress| {
+            if event == SigningProgress::Restarted {
+                restarted.notify_one();
+            }
+            events.push(event);
+        };
+
+        // The first two signers make up the round and the second one declines. The third
+        // only joins once the round was restarted.
+        let first = server.participate_with(
+            server.client(),
+            group.signers[0].clone(),
+            coordinator_keys.1.clone(),
+            Some("y\ny\n"),
+            Some(&used_nonces),
+            &shutdown,
+        );
+        let others = async {
+            let declined = server
+                .participate(
+                    server.client(),
+                    group.signers[1].clone(),
+                    coordinator_keys.1.clone(),
+                    Some("n\n"),
+                    &shutdown,
+                )
+                .await;
+            restarted.notified().await;
+            let joined = server
+                .participate(
+                    server.client(),
+                    group.signers[2].clone(),
+                    coordinator_keys.1.clone(),
+                    None,
+                    &shutdown,
+                )
+                .await;
+            (declined, joined)
+        };
+        let (signature, first, (declined, joined)) = tokio::join!(
+            server.coordinate_with_progress(server.client(), &config, &shutdown, &mut on_progress),
+            first,
+            others,
+        );
+
+        first.unwrap();
+        joined.unwrap();
+        assert!(declined
+            .unwrap_err()
+            .downcast_ref::<SigningDeclined>()
+            .is_some());
+        group.assert_signed(&signature.unwrap());
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| **event == SigningProgress::Restarted)
+                .count(),
+            1
+        );
+        // The first signer signed in both rounds, never twice with the same nonces
+        let used: Vec<String> =
+            serde_json::from_str(&std::fs::read_to_string(&used_nonces).unwrap()).unwrap();
+        assert_eq!(used.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn one_round_signing_session_from_pre_distributed_commitments() {
+        let server = MockFrostd::start().await;
+        let group = TestGroup::new(1_000);
+        let coordinator_keys = Cipher::generate_keypair().unwrap();
+        let config = group.coordinator_config(&coordinator_keys);
+
+        // Every signer hands out a commitment ahead of time and keeps its nonces
+        let mut pool = CommitmentPool::new();
+        let pre_committed: Vec<_> = group
+            .signers
+            .iter()
+            .map(|(key_package, _)| {
+                let (nonces, commitments) = frost_bluepallas::round1::commit(
+                    key_package.signing_share(),
+                    &mut thread_rng(),
+                );
+                pool.add(*key_package.identifier(), commitments).unwrap();
+                (nonces, commitments)
+            })
+            .collect();
+
+        let shutdown = CancellationToken::new();
+        let coordinate = async {
+            let mut comms = HTTPComms::with_client(&config, server.client()).unwrap();
+            coordinate_signing_from_pool(
+                &mut comms,
+                &config,
+                &mut pool,
+                &mut std::io::empty(),
+                &mut std::io::sink(),
+                &shutdown,
+                &mut |_| {},
+            )
+            .await
+        };
+        let participate = |index: usize| {
+            server.participate_pre_committed(
+                server.client(),
+                group.signers[index].clone(),
+                coordinator_keys.1.clone(),
+                pre_committed[index].clone(),
+                &shutdown,
+            )
+        };
+        let (signature, first, second) = tokio::join!(coordinate, participate(0), participate(1));
+
+        first.unwrap();
+        second.unwrap();
+        group.assert_signed(&signature.unwrap());
+        assert!(group
+            .signers
+            .iter()
+            .all(|(key_package, _)| pool.remaining(key_package.identifier()) == 0));
+    }
+}
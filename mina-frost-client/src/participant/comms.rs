@@ -40,10 +40,90 @@ pub enum Message<C: Ciphersuite> {
     SignatureShare(SignatureShare<C>),
 }
 
+/// How a participant approves a signing package before producing its share.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Sign without asking (`--yes`).
+    Skip,
+    /// Show the decoded transaction and ask for a y/n answer.
+    Prompt,
+    /// Like [`Confirmation::Prompt`], but also show the exact message bytes that will be
+    /// signed and every signer's commitments (`--confirm`).
+    Detailed,
+}
+
+/// Returned when the user refuses to sign; no signature share has been sent.
+#[derive(Debug, thiserror::Error)]
+#[error("signing declined")]
+pub struct SigningDeclined;
+
+/// Returned when the coordinator abandoned the signing round and asks for commitments to
+/// fresh nonces.
+#[derive(Debug, thiserror::Error)]
+#[error("the coordinator restarted the signing round: {reason}")]
+pub struct RestartRequested {
+    /// The reason given by the coordinator.
+    pub reason: String,
+}
+
+/// Show the signing package as selected by `confirmation` and read a y/n answer.
+pub(crate) fn prompt_confirmation<C: Ciphersuite>(
+    input: &mut dyn BufRead,
+    output: &mut dyn Write,
+    signing_package: &SendSigningPackageArgs<C>,
+    confirmation: Confirmation,
+) -> Result<(), Box<dyn Error>> {
+    if confirmation == Confirmation::Skip {
+        return Ok(());
+    }
+
+    let signing_package = signing_package
+        .signing_package
+        .first()
+        .ok_or_else(|| eyre!("No signing package found"))?;
+
+    let transaction =
+        TransactionEnvelope::deserialize(signing_package.message()).map_err(|err| {
+            eyre!(
+                "failed to decode signing payload as TransactionEnvelope: {err}. \
+             expected coordinator payload format is serialized TransactionEnvelope JSON bytes"
+            )
+        })?;
+    writeln!(output, "Message to be signed (json):\n{}", transaction)?;
+
+    if confirmation == Confirmation::Detailed {
+        let message_bytes = transaction.to_pallas_message().serialize()?;
+        writeln!(
+            output,
+            "Message bytes (hex): {}",
+            hex::encode(message_bytes)
+        )?;
+        writeln!(output, "Signing commitments:")?;
+        for (identifier, commitments) in signing_package.signing_commitments() {
+            writeln!(
+                output,
+                "  {}: {}",
+                hex::encode(identifier.serialize()),
+                hex::encode(commitments.serialize()?)
+            )?;
+        }
+    }
+    writeln!(output, "Do you want to sign it? (y/n)\n")?;
+
+    let mut sign_it = String::new();
+    input.read_line(&mut sign_it)?;
+    if sign_it.trim() != "y" {
+        return Err(SigningDeclined.into());
+    }
+    Ok(())
+}
+
 /// Trait for communication with the server in the FROST protocol.
 #[async_trait(?Send)]
 pub trait Comms<C: Ciphersuite> {
-    /// Get the signing package from the server.
+    /// Send `commitments` and get the signing package from the server.
+    ///
+    /// Fails with [`RestartRequested`] if the coordinator restarts the round instead.
     async fn get_signing_package(
         &mut self,
         input: &mut dyn BufRead,
@@ -55,7 +135,8 @@ pub trait Comms<C: Ciphersuite> {
     /// Ask the user if they want to sign the message.
     ///
     /// Implementations should show the message to the user (or auxiliary data
-    /// that maps to the message) and ask for confirmation.
+    /// that maps to the message) and ask for confirmation, returning
+    /// [`SigningDeclined`] if the user refuses.
     ///
     /// The default implementation prints the message to output and reads
     /// confirmation from input.
@@ -64,42 +145,127 @@ pub trait Comms<C: Ciphersuite> {
         input: &mut dyn BufRead,
         output: &mut dyn Write,
         signing_package: &SendSigningPackageArgs<C>,
-        yes: bool,
+        confirmation: Confirmation,
     ) -> Result<(), Box<dyn Error>> {
-        if yes {
-            return Ok(());
-        }
-
-        let payload_bytes = signing_package
-            .signing_package
-            .first()
-            .ok_or_else(|| eyre!("No signing package found"))?
-            .message();
-
-        let transaction = TransactionEnvelope::deserialize(payload_bytes).map_err(|err| {
-            eyre!(
-                "failed to decode signing payload as TransactionEnvelope: {err}. \
-                 expected coordinator payload format is serialized TransactionEnvelope JSON bytes"
-            )
-        })?;
-        writeln!(
-            output,
-            "Message to be signed (json):\n{}\nDo you want to sign it? (y/n)\n",
-            transaction
-        )?;
-
-        let mut sign_it = String::new();
-        input.read_line(&mut sign_it)?;
-        if sign_it.trim() != "y" {
-            return Err(eyre!("signing cancelled").into());
-        }
-        Ok(())
+        prompt_confirmation(input, output, signing_package, confirmation)
     }
 
     /// Send the signature share to the server.
+    ///
+    /// Fails with [`RestartRequested`] if the coordinator restarts the round before it
+    /// completes the session.
     async fn send_signature_share(
         &mut self,
         identifier: Identifier<C>,
         signature_share: SignatureShare<C>,
     ) -> Result<(), Box<dyn Error>>;
+
+    /// Do any cleanups in case an error occurs, the user declines to sign or the session
+    /// is cancelled before the signature share is sent. `reason` describes what happened,
+    /// for the coordinator.
+    async fn cleanup_on_error(&mut self, _reason: &str) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, io::Cursor};
+
+    use frost_bluepallas::keys::generate_with_dealer;
+    use frost_core::keys::{IdentifierList, KeyPackage};
+    use mina_tx::{legacy_tx::LegacyTransaction, pallas_message::PallasMessage, NetworkId};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::BluePallasSuite;
+
+    fn signing_package_args() -> SendSigningPackageArgs<BluePallasSuite> {
+        let mut rng = thread_rng();
+        let (shares, _) =
+            generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng)
+                .unwrap();
+        let commitments = shares
+            .into_iter()
+            .take(2)
+            .map(|(id, share)| {
+                let key_package = KeyPackage::try_from(share).unwrap();
+                let (_, c) =
+                    frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+                (id, c)
+            })
+            .collect::<BTreeMap<_, _>>();
+        let pub_key = mina_signer::PubKey::from_address(
+            "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+        )
+        .unwrap();
+        let transaction = TransactionEnvelope::new_legacy(
+            NetworkId::Testnet,
+            LegacyTransaction::new_payment(pub_key.clone(), pub_key, 1_000, 1_000, 0),
+        );
+        SendSigningPackageArgs {
+            signing_package: vec![frost::SigningPackage::new(
+                commitments,
+                &transaction.serialize().unwrap(),
+            )],
+            aux_msg: vec![],
+        }
+    }
+
+    fn prompt(answer: &str, confirmation: Confirmation) -> (Result<(), Box<dyn Error>>, String) {
+        let mut input = Cursor::new(answer.as_bytes().to_vec());
+        let mut output = Vec::new();
+        let result = prompt_confirmation(
+            &mut input,
+            &mut output,
+            &signing_package_args(),
+            confirmation,
+        );
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn detailed_confirmation_shows_message_bytes_and_commitments() {
+        let (result, output) = prompt("y\n", Confirmation::Detailed);
+
+        result.unwrap();
+        assert!(output.contains("Message to be signed (json):"));
+        assert!(output.contains("Message bytes (hex): "));
+        let commitments = output.split("Signing commitments:\n").nth(1).unwrap();
+        assert_eq!(
+            commitments
+                .lines()
+                .take_while(|line| line.starts_with("  "))
+                .count(),
+            2
+        );
+        assert!(output.contains("Do you want to sign it? (y/n)"));
+    }
+
+    #[test]
+    fn declining_returns_signing_declined() {
+        for answer in ["n\n", "\n", "yes please\n"] {
+            let (result, _) = prompt(answer, Confirmation::Detailed);
+            assert!(result
+                .unwrap_err()
+                .downcast_ref::<SigningDeclined>()
+                .is_some());
+        }
+    }
+
+    #[test]
+    fn plain_prompt_hides_message_bytes() {
+        let (result, output) = prompt("y\n", Confirmation::Prompt);
+
+        result.unwrap();
+        assert!(!output.contains("Message bytes (hex): "));
+    }
+
+    #[test]
+    fn skip_reads_no_input() {
+        let (result, output) = prompt("n\n", Confirmation::Skip);
+
+        result.unwrap();
+        assert!(output.is_empty());
+    }
 }
@@ -1,6 +1,7 @@
 //! HTTP implementation of the Comms trait.
 
 use std::{
+    collections::VecDeque,
     error::Error,
     io::{BufRead, Write},
     marker::PhantomData,
@@ -10,18 +11,17 @@ use std::{
 use async_trait::async_trait;
 use eyre::{eyre, OptionExt};
 use frost_core::{round1::SigningCommitments, round2::SignatureShare, Ciphersuite, Identifier};
-use rand::thread_rng;
 use snow::{HandshakeState, TransportState};
 
 use crate::cipher::Cipher;
-use crate::client::Client;
+use crate::client::{self, Client};
 use crate::{
     api::{self, SendSigningPackageArgs, Uuid},
     participant::comms::CHUNK_HEADER_LEN,
 };
 
 use super::super::config::Config;
-use super::Comms;
+use super::{Comms, RestartRequested};
 
 /// A Noise state.
 ///
@@ -107,9 +107,15 @@ impl Noise {
 pub struct HTTPComms<C: Ciphersuite> {
     client: Client,
     session_id: Option<Uuid>,
-    access_token: Option<String>,
     args: Config<C>,
     cipher: Option<Cipher>,
+    /// Messages from the coordinator that were received but not read yet.
+    inbox: VecDeque<api::Msg>,
+    /// Whether the signature share of the current round was sent.
+    share_sent: bool,
+    /// Whether the coordinator got the commitments of the next round ahead of time, so
+    /// that they are not sent.
+    pre_committed: bool,
     _phantom: PhantomData<C>,
 }
 
@@ -119,62 +125,55 @@ where
     C: Ciphersuite,
 {
     pub fn new(args: &Config<C>) -> Result<Self, Box<dyn Error>> {
+        Self::with_client(
+            args,
+            Client::new(format!("https://{}:{}", args.ip, args.port)),
+        )
+    }
+
+    /// Same as [`HTTPComms::new`], but talking to the server through `client`.
+    pub(crate) fn with_client(args: &Config<C>, client: Client) -> Result<Self, Box<dyn Error>> {
         Ok(Self {
-            client: Client::new(format!("https://{}:{}", args.ip, args.port)),
+            client,
             session_id: Uuid::parse_str(&args.session_id).ok(),
-            access_token: None,
             args: args.clone(),
             cipher: None,
+            inbox: Default::default(),
+            share_sent: false,
+            pre_committed: false,
             _phantom: Default::default(),
         })
     }
-}
 
-#[async_trait(?Send)]
-impl<C> Comms<C> for HTTPComms<C>
-where
-    C: Ciphersuite + 'static,
-{
-    async fn get_signing_package(
-        &mut self,
-        _input: &mut dyn BufRead,
-        _output: &mut dyn Write,
-        commitments: SigningCommitments<C>,
-        _identifier: Identifier<C>,
-    ) -> Result<SendSigningPackageArgs<C>, Box<dyn Error>> {
-        let mut rng = thread_rng();
+    /// Take part in a session the coordinator runs from pre-distributed commitments: the
+    /// commitments of the first round were handed to the coordinator ahead of time and are
+    /// not sent again. A restarted round sends fresh commitments as usual.
+    pub fn pre_committed(mut self) -> Self {
+        self.pre_committed = true;
+        self
+    }
 
+    /// Log in, join the session and set up encryption to its coordinator.
+    async fn join(&mut self) -> Result<(), Box<dyn Error>> {
         eprintln!("Logging in...");
-        let challenge = self.client.challenge().await?.challenge;
-
-        let signature: [u8; 64] = self
+        let comm_privkey = self
             .args
             .comm_privkey
             .clone()
-            .ok_or_eyre("comm_privkey must be specified")?
-            .sign(challenge.as_bytes(), &mut rng)?;
-
-        self.access_token = Some(
-            self.client
-                .login(&api::LoginArgs {
-                    challenge,
-                    pubkey: self
-                        .args
-                        .comm_pubkey
-                        .clone()
-                        .ok_or_eyre("comm_pubkey must be specified")?,
-                    signature: signature.to_vec(),
-                })
-                .await?
-                .access_token
-                .to_string(),
-        );
+            .ok_or_eyre("comm_privkey must be specified")?;
+        let comm_pubkey = self
+            .args
+            .comm_pubkey
+            .clone()
+            .ok_or_eyre("comm_pubkey must be specified")?;
+        self.client
+            .authenticate(&comm_privkey, &comm_pubkey)
+            .await?;
 
         eprintln!("Joining signing session...");
         let session_id = self
             .session_id
             .ok_or_else(|| eyre!("session ID is required; use `-S` to specify it"))?;
-        self.session_id = Some(session_id);
 
         let (Some(comm_privkey), Some(comm_coordinator_pubkey_getter)) = (
             &self.args.comm_privkey,
@@ -196,27 +195,14 @@ where
 
         let cipher = Cipher::new(comm_privkey.clone(), vec![comm_coordinator_pubkey.clone()])?;
         self.cipher = Some(cipher);
-        let cipher = self.cipher.as_mut().expect("was just set");
-
-        // Send Commitments to Server
-        eprintln!("Sending commitments to coordinator...");
-        let send_commitments_args = vec![commitments];
-        let msg = cipher.encrypt(None, serde_json::to_vec(&send_commitments_args)?)?;
-        self.client
-            .send(&api::SendArgs {
-                session_id,
-                // Empty recipients: Coordinator
-                recipients: vec![],
-                msg,
-            })
-            .await?;
-
-        eprint!("Waiting for coordinator to send signing package...");
+        Ok(())
+    }
 
-        // The coordinator sends a 4-byte big-endian chunk count header, then N
-        // encrypted chunks. Poll until the header arrives, capturing any chunks
-        // that arrived in the same batch as leftovers.
-        let (num_chunks, leftover) = loop {
+    /// Wait for the next message from the coordinator and decrypt it. Messages that
+    /// arrive together are kept for the following calls.
+    async fn receive_next(&mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let session_id = self.session_id.ok_or_eyre("not in a session")?;
+        while self.inbox.is_empty() {
             let r = self
                 .client
                 .receive(&api::ReceiveArgs {
@@ -227,53 +213,79 @@ where
             if r.msgs.is_empty() {
                 tokio::time::sleep(Duration::from_secs(2)).await;
                 eprint!(".");
-            } else {
-                let msg = cipher.decrypt(r.msgs[0].clone())?;
-                let header: [u8; CHUNK_HEADER_LEN] = msg
-                    .msg
-                    .as_slice()
-                    .try_into()
-                    .map_err(|_| eyre::eyre!("invalid chunk count header"))?;
-                break (u32::from_be_bytes(header) as usize, r.msgs[1..].to_vec());
-            }
-        };
-
-        // Collect all chunks: drain any that arrived with the header, then poll
-        // for the rest.
-        let mut reassembled = Vec::new();
-        let mut collected = 0;
-
-        for m in leftover {
-            let decrypted = cipher.decrypt(m)?;
-            reassembled.extend_from_slice(&decrypted.msg);
-            collected += 1;
-            if collected == num_chunks {
-                break;
             }
+            self.inbox.extend(r.msgs);
         }
+        let msg = self.inbox.pop_front().expect("inbox is not empty");
+        let cipher = self.cipher.as_mut().ok_or_eyre("not in a session")?;
+        Ok(cipher.decrypt(msg)?.msg)
+    }
+}
 
-        while collected < num_chunks {
-            let r = self
-                .client
-                .receive(&api::ReceiveArgs {
+/// The restart request in `msg`, if it is one.
+fn restart_requested(msg: &[u8]) -> Option<RestartRequested> {
+    serde_json::from_slice::<api::RestartArgs>(msg)
+        .ok()
+        .map(|restart| RestartRequested {
+            reason: restart.restart,
+        })
+}
+
+#[async_trait(?Send)]
+impl<C> Comms<C> for HTTPComms<C>
+where
+    C: Ciphersuite + 'static,
+{
+    async fn get_signing_package(
+        &mut self,
+        _input: &mut dyn BufRead,
+        _output: &mut dyn Write,
+        commitments: SigningCommitments<C>,
+        _identifier: Identifier<C>,
+    ) -> Result<SendSigningPackageArgs<C>, Box<dyn Error>> {
+        // A restarted round is run in the session already joined
+        if self.cipher.is_none() {
+            self.join().await?;
+        }
+        let session_id = self.session_id.expect("set when joining");
+        let cipher = self.cipher.as_mut().expect("set when joining");
+
+        // Send Commitments to Server, unless the coordinator already has them
+        if !std::mem::take(&mut self.pre_committed) {
+            eprintln!("Sending commitments to coordinator...");
+            let send_commitments_args = vec![commitments];
+            let msg = cipher.encrypt(None, serde_json::to_vec(&send_commitments_args)?)?;
+            self.client
+                .send(&api::SendArgs {
                     session_id,
-                    as_coordinator: false,
+                    // Empty recipients: Coordinator
+                    recipients: vec![],
+                    msg,
                 })
                 .await?;
-            for m in r.msgs {
-                let decrypted = cipher.decrypt(m)?;
-                reassembled.extend_from_slice(&decrypted.msg);
-                collected += 1;
-                if collected == num_chunks {
-                    break;
-                }
-            }
-            if collected < num_chunks {
-                tokio::time::sleep(Duration::from_secs(2)).await;
-                eprint!(".");
-            }
         }
-        eprintln!("\nSigning package received ({} chunks)", collected);
+        self.share_sent = false;
+
+        eprint!("Waiting for coordinator to send signing package...");
+
+        // The coordinator sends a 4-byte big-endian chunk count header, then N
+        // encrypted chunks, unless it restarts the round instead.
+        let header = self.receive_next().await?;
+        if let Some(restart) = restart_requested(&header) {
+            eprintln!();
+            return Err(restart.into());
+        }
+        let header: [u8; CHUNK_HEADER_LEN] = header
+            .as_slice()
+            .try_into()
+            .map_err(|_| eyre::eyre!("invalid chunk count header"))?;
+        let num_chunks = u32::from_be_bytes(header) as usize;
+
+        let mut reassembled = Vec::new();
+        for _ in 0..num_chunks {
+            reassembled.extend_from_slice(&self.receive_next().await?);
+        }
+        eprintln!("\nSigning package received ({} chunks)", num_chunks);
 
         let r: SendSigningPackageArgs<C> = serde_json::from_slice(&reassembled)?;
         Ok(r)
@@ -303,9 +315,65 @@ where
                 msg,
             })
             .await?;
+        // The share is out; there is nothing left for `cleanup_on_error` to abort
+        self.share_sent = true;
+
+        // Another participant may still abort, in which case the coordinator restarts the
+        // round; stay until it closes the session instead.
+        eprint!("Waiting for coordinator to close the session...");
+        loop {
+            match self.receive_next().await {
+                Ok(msg) => {
+                    if let Some(restart) = restart_requested(&msg) {
+                        eprintln!();
+                        return Err(restart.into());
+                    }
+                    eprintln!("\nWarning: ignoring unexpected message from the coordinator");
+                }
+                Err(e)
+                    if matches!(
+                        e.downcast_ref::<client::Error>(),
+                        Some(client::Error::ServerError(api::Error::SessionNotFound))
+                    ) =>
+                {
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        eprintln!();
 
         let _r = self.client.logout().await?;
 
         Ok(())
     }
+
+    async fn cleanup_on_error(&mut self, reason: &str) -> Result<(), Box<dyn Error>> {
+        // Only the coordinator may close the session on frostd, so ask it to before
+        // leaving. Nothing was sent yet if there is no cipher, and nothing is left to
+        // abort once the signature share is sent.
+        let (Some(cipher), Some(session_id)) = (self.cipher.as_mut(), self.session_id) else {
+            return Ok(());
+        };
+        if self.share_sent {
+            let _r = self.client.logout().await?;
+            return Ok(());
+        }
+        let abort = api::AbortArgs {
+            abort: reason.to_string(),
+        };
+        let msg = cipher.encrypt(None, serde_json::to_vec(&abort)?)?;
+        let sent = self
+            .client
+            .send(&api::SendArgs {
+                session_id,
+                // Empty recipients: Coordinator
+                recipients: vec![],
+                msg,
+            })
+            .await;
+        let _r = self.client.logout().await?;
+        sent?;
+        Ok(())
+    }
 }
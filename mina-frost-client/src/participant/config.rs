@@ -1,10 +1,11 @@
 // Suppress warning on `comm_coordinator_pubkey_getter` due to zeroize(skip) macro
 #![allow(unused_assignments)]
 
-use std::rc::Rc;
+use std::{error::Error, path::PathBuf, rc::Rc};
 
 use crate::cipher::{PrivateKey, PublicKey};
 use frost_core::{keys::KeyPackage, Ciphersuite};
+use mina_tx::network_id::NetworkId;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 /// Configuration for the participant in the FROST signing protocol.
@@ -38,6 +39,18 @@ pub struct Config<C: Ciphersuite> {
     #[allow(clippy::type_complexity)]
     #[zeroize(skip)]
     pub comm_coordinator_pubkey_getter: Option<Rc<dyn Fn(&PublicKey) -> Option<PublicKey>>>,
+
+    /// File recording the nonces this participant has used, so that they are refused
+    /// in later runs too. If None, they are only tracked for this process.
+    #[zeroize(skip)]
+    pub used_nonces_path: Option<PathBuf>,
+
+    /// Called with the network of the transaction to sign, before the user is asked to
+    /// confirm it; returning an error refuses the signing package. If None, any network
+    /// is accepted.
+    #[allow(clippy::type_complexity)]
+    #[zeroize(skip)]
+    pub network_check: Option<Rc<dyn Fn(&NetworkId) -> Result<(), Box<dyn Error>>>>,
 }
 
 impl<C> ZeroizeOnDrop for Config<C> where C: Ciphersuite {}
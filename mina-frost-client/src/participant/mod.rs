@@ -1,6 +1,8 @@
 pub mod comms;
 pub mod config;
+pub mod nonce_guard;
 pub mod sign;
 
+pub use comms::{Confirmation, RestartRequested, SigningDeclined};
 pub use config::Config;
-pub use sign::sign;
+pub use sign::{sign, sign_pre_committed, sign_with_nonces};
@@ -0,0 +1,257 @@
+//! Guard against signing twice with the same round 1 nonces.
+//!
+//! Producing two signature shares with the same `SigningNonces` over different signing
+//! packages reveals the participant's signing share, so every signature made through
+//! [`UsedNonceStore::sign`] is recorded against the commitments of the nonces it used.
+//!
+//! A store opened with [`UsedNonceStore::open`] keeps that record in a file, so nonces
+//! kept across runs (e.g. for offline signing) are refused after a restart too.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::{Path, PathBuf},
+};
+
+use frost_bluepallas::errors::FrostBluePallasError;
+use frost_core::{
+    keys::{KeyPackage, SigningShare},
+    round1::{SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+    SigningPackage,
+};
+use mina_tx::pallas_message::PallasMessage;
+use rand::{CryptoRng, RngCore};
+
+use crate::{cli::write_atomic, BluePallasSuite};
+
+/// Errors returned by [`UsedNonceStore::sign`].
+#[derive(Debug, thiserror::Error)]
+pub enum NonceGuardError {
+    #[error("refusing to sign: nonces with commitment {0} were already used")]
+    NonceReused(String),
+    #[error("failed to create signature share: {0}")]
+    Signing(#[from] FrostBluePallasError<PallasMessage>),
+    #[error("failed to access the used nonce record at {}: {reason}", .path.display())]
+    Storage { path: PathBuf, reason: String },
+}
+
+/// Set of nonce commitments that have already produced a signature share.
+#[derive(Debug, Default)]
+pub struct UsedNonceStore {
+    used: HashSet<Vec<u8>>,
+    /// File the set is saved to after every change, if any.
+    path: Option<PathBuf>,
+}
+
+impl UsedNonceStore {
+    /// A store that only lives in memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the store saved at `path`, or an empty one if the file does not exist yet.
+    /// Every nonce used afterwards is written back to `path` before its signature share
+    /// is returned.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NonceGuardError> {
+        let path = path.as_ref().to_path_buf();
+        let storage_error = |reason: String| NonceGuardError::Storage {
+            path: path.clone(),
+            reason,
+        };
+
+        let used: HashSet<Vec<u8>> = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str::<Vec<String>>(&contents)
+                .map_err(|e| storage_error(e.to_string()))?
+                .iter()
+                .map(hex::decode)
+                .collect::<Result<_, _>>()
+                .map_err(|e| storage_error(e.to_string()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(storage_error(e.to_string())),
+        };
+
+        Ok(Self {
+            used,
+            path: Some(path),
+        })
+    }
+
+    /// Whether these nonces have already been used for a signature share.
+    pub fn is_used(
+        &self,
+        nonces: &SigningNonces<BluePallasSuite>,
+    ) -> Result<bool, NonceGuardError> {
+        Ok(self.used.contains(&Self::key(nonces)?))
+    }
+
+    /// Sign with `nonces` unless they were used before, then remember them.
+    pub fn sign(
+        &mut self,
+        signing_package: &SigningPackage<BluePallasSuite>,
+        nonces: &SigningNonces<BluePallasSuite>,
+        key_package: &KeyPackage<BluePallasSuite>,
+    ) -> Result<SignatureShare<BluePallasSuite>, NonceGuardError> {
+        let key = Self::key(nonces)?;
+        if self.used.contains(&key) {
+            return Err(NonceGuardError::NonceReused(hex::encode(&key)));
+        }
+
+        let share = frost_bluepallas::round2::sign(signing_package, nonces, key_package)?;
+        self.used.insert(key);
+        // The share must not leave this function unless the use is on record
+        self.save()?;
+        Ok(share)
+    }
+
+    /// Answer a coordinator restart: mark `stale` as used, whether or not it produced a
+    /// signature share, and commit to fresh nonces for the restarted round.
+    ///
+    /// After a restart the coordinator may already hold a signing package for `stale`, so
+    /// the old nonces must never sign again.
+    pub fn recommit<R: RngCore + CryptoRng>(
+        &mut self,
+        stale: &SigningNonces<BluePallasSuite>,
+        signing_share: &SigningShare<BluePallasSuite>,
+        rng: &mut R,
+    ) -> Result<
+        (
+            SigningNonces<BluePallasSuite>,
+            SigningCommitments<BluePallasSuite>,
+        ),
+        NonceGuardError,
+    > {
+        self.used.insert(Self::key(stale)?);
+        self.save()?;
+        Ok(frost_bluepallas::round1::commit(signing_share, rng))
+    }
+
+    /// Write the set to the store's file, if it has one, replacing it atomically.
+    fn save(&self) -> Result<(), NonceGuardError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        // Sorted so that the file does not change needlessly between saves
+        let used: BTreeSet<String> = self.used.iter().map(hex::encode).collect();
+        let json = serde_json::to_vec(&used).expect("a set of strings always serializes");
+        write_atomic::write_file(path, &json).map_err(|e| NonceGuardError::Storage {
+            path: path.clone(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Nonces are identified by their public commitments so no secret material is stored.
+    fn key(nonces: &SigningNonces<BluePallasSuite>) -> Result<Vec<u8>, NonceGuardError> {
+        nonces
+            .commitments()
+            .serialize()
+            .map_err(|e| NonceGuardError::Signing(e.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use frost_bluepallas::keys::generate_with_dealer;
+    use frost_core::keys::IdentifierList;
+    use mina_hasher::ROInput;
+    use mina_tx::NetworkId;
+    use rand::thread_rng;
+
+    use super::*;
+
+    #[test]
+    fn test_signing_twice_with_same_nonces_is_rejected() {
+        let mut rng = thread_rng();
+        let (shares, _) =
+            generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng)
+                .unwrap();
+        let (id, share) = shares.into_iter().next().unwrap();
+        let key_package = KeyPackage::try_from(share).unwrap();
+        let (nonces, commitments) =
+            frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+
+        let package_for = |payload: &[u8]| {
+            let message = PallasMessage::from_parts(
+                ROInput::new().append_bytes(payload),
+                NetworkId::Testnet,
+                true,
+            )
+            .serialize()
+            .unwrap();
+            SigningPackage::new(BTreeMap::from([(id, commitments)]), &message)
+        };
+
+        let mut store = UsedNonceStore::new();
+        assert!(!store.is_used(&nonces).unwrap());
+        store
+            .sign(&package_for(b"first"), &nonces, &key_package)
+            .unwrap();
+        assert!(store.is_used(&nonces).unwrap());
+
+        let err = store
+            .sign(&package_for(b"second"), &nonces, &key_package)
+            .unwrap_err();
+        assert!(matches!(err, NonceGuardError::NonceReused(_)));
+
+        // Fresh nonces are still accepted.
+        let (fresh_nonces, fresh_commitments) =
+            frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+        let message = PallasMessage::from_parts(
+            ROInput::new().append_bytes(b"third"),
+            NetworkId::Testnet,
+            true,
+        )
+        .serialize()
+        .unwrap();
+        let package = SigningPackage::new(BTreeMap::from([(id, fresh_commitments)]), &message);
+        assert!(store.sign(&package, &fresh_nonces, &key_package).is_ok());
+    }
+
+    #[test]
+    fn test_used_nonces_are_refused_after_reopening_the_store() {
+        let mut rng = thread_rng();
+        let (shares, _) =
+            generate_with_dealer::<PallasMessage, _>(3, 2, IdentifierList::Default, &mut rng)
+                .unwrap();
+        let (id, share) = shares.into_iter().next().unwrap();
+        let key_package = KeyPackage::try_from(share).unwrap();
+        let (nonces, commitments) =
+            frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+        let (stale, _) = frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+        let message = PallasMessage::from_parts(
+            ROInput::new().append_bytes(b"payload"),
+            NetworkId::Testnet,
+            true,
+        )
+        .serialize()
+        .unwrap();
+        let package = SigningPackage::new(BTreeMap::from([(id, commitments)]), &message);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("used-nonces.json");
+
+        let mut store = UsedNonceStore::open(&path).unwrap();
+        assert!(!store.is_used(&nonces).unwrap());
+        store.sign(&package, &nonces, &key_package).unwrap();
+        store
+            .recommit(&stale, key_package.signing_share(), &mut rng)
+            .unwrap();
+        drop(store);
+
+        // A later run loads the record and refuses both sets of nonces
+        let mut store = UsedNonceStore::open(&path).unwrap();
+        assert!(store.is_used(&nonces).unwrap());
+        assert!(store.is_used(&stale).unwrap());
+        assert!(matches!(
+            store.sign(&package, &nonces, &key_package),
+            Err(NonceGuardError::NonceReused(_))
+        ));
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(matches!(
+            UsedNonceStore::open(&path),
+            Err(NonceGuardError::Storage { .. })
+        ));
+    }
+}
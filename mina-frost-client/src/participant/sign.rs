@@ -2,55 +2,273 @@ use super::config::Config;
 
 use super::comms::http::HTTPComms;
 
-use super::comms::Comms;
+use super::comms::{Comms, Confirmation, RestartRequested};
+use super::nonce_guard::UsedNonceStore;
 
+use super::nonce_guard::NonceGuardError;
+use crate::cancel::{cancellable, CancellationToken};
 use crate::BluePallasSuite;
+use frost_core::{
+    keys::{KeyPackage, SigningShare},
+    round1::{SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+    SigningPackage,
+};
 use mina_tx::TransactionEnvelope;
 use rand::thread_rng;
-use std::io::{BufRead, Write};
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    sync::{Mutex, OnceLock},
+};
 use zeroize::Zeroizing;
 
+/// Nonces used by any signing session in this process.
+pub(crate) fn used_nonces() -> &'static Mutex<UsedNonceStore> {
+    static USED_NONCES: OnceLock<Mutex<UsedNonceStore>> = OnceLock::new();
+    USED_NONCES.get_or_init(|| Mutex::new(UsedNonceStore::new()))
+}
+
+/// Sign through the used nonce record at `used_nonces_path`, or through the in-process
+/// [`used_nonces`] if there is none.
+pub(crate) fn sign_guarded(
+    used_nonces_path: Option<&Path>,
+    signing_package: &SigningPackage<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    key_package: &KeyPackage<BluePallasSuite>,
+) -> Result<SignatureShare<BluePallasSuite>, NonceGuardError> {
+    // Held while the file is read and rewritten, so that sessions in this process
+    // cannot interleave their updates
+    let mut in_process = used_nonces().lock().expect("nonce store poisoned");
+    match used_nonces_path {
+        Some(path) => UsedNonceStore::open(path)?.sign(signing_package, nonces, key_package),
+        None => in_process.sign(signing_package, nonces, key_package),
+    }
+}
+
+/// Mark `stale` as used and commit to fresh nonces through the used nonce record at
+/// `used_nonces_path`, or through the in-process [`used_nonces`] if there is none.
+pub(crate) fn recommit_guarded(
+    used_nonces_path: Option<&Path>,
+    stale: &SigningNonces<BluePallasSuite>,
+    signing_share: &SigningShare<BluePallasSuite>,
+) -> Result<
+    (
+        SigningNonces<BluePallasSuite>,
+        SigningCommitments<BluePallasSuite>,
+    ),
+    NonceGuardError,
+> {
+    let mut in_process = used_nonces().lock().expect("nonce store poisoned");
+    let mut rng = thread_rng();
+    match used_nonces_path {
+        Some(path) => UsedNonceStore::open(path)?.recommit(stale, signing_share, &mut rng),
+        None => in_process.recommit(stale, signing_share, &mut rng),
+    }
+}
+
 /// Implementation of the participation in the FROST protocol.
 /// This function handles the signing process for a participant.
 /// The signing process needs to be started by a coordinator first.
+/// Cancelling `shutdown` aborts the session before any signature share is sent.
 pub async fn sign(
     config: Config<BluePallasSuite>,
     input: &mut impl BufRead,
     logger: &mut impl Write,
-    yes: bool,
+    confirmation: Confirmation,
+    shutdown: &CancellationToken,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let mut comms: Box<dyn Comms<BluePallasSuite>> = Box::new(HTTPComms::new(&config)?);
-
     // Round 1
 
-    let key_package = &config.key_package;
-
     let mut rng = thread_rng();
     let (ret_nonces, commitments) =
-        frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+        frost_bluepallas::round1::commit(config.key_package.signing_share(), &mut rng);
     let nonces = Zeroizing::new(ret_nonces);
 
+    sign_with_nonces(
+        config,
+        &nonces,
+        commitments,
+        input,
+        logger,
+        confirmation,
+        shutdown,
+    )
+    .await
+}
+
+/// Same as [`sign`], but using round 1 nonces generated externally, e.g. by a
+/// secure enclave. The nonces are rejected if `commitments` is not their commitment.
+pub async fn sign_with_nonces(
+    config: Config<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    commitments: SigningCommitments<BluePallasSuite>,
+    input: &mut impl BufRead,
+    logger: &mut impl Write,
+    confirmation: Confirmation,
+    shutdown: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut comms = HTTPComms::new(&config)?;
+    sign_over(
+        &mut comms,
+        &config,
+        nonces,
+        commitments,
+        input,
+        logger,
+        confirmation,
+        shutdown,
+    )
+    .await
+}
+
+/// Same as [`sign_with_nonces`], in a session the coordinator runs from pre-distributed
+/// commitments, see
+/// [`coordinate_signing_from_pool`](crate::coordinator::coordinate_signing_from_pool).
+/// `commitments` were handed to the coordinator ahead of time and are not sent again.
+pub async fn sign_pre_committed(
+    config: Config<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    commitments: SigningCommitments<BluePallasSuite>,
+    input: &mut impl BufRead,
+    logger: &mut impl Write,
+    confirmation: Confirmation,
+    shutdown: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut comms = HTTPComms::new(&config)?.pre_committed();
+    sign_over(
+        &mut comms,
+        &config,
+        nonces,
+        commitments,
+        input,
+        logger,
+        confirmation,
+        shutdown,
+    )
+    .await
+}
+
+/// Round 2 of [`sign_with_nonces`], run over the given `comms`.
+///
+/// If the coordinator restarts the round, the nonces are marked as used and the round is
+/// run again with fresh ones. If the session fails, is declined or is cancelled before the
+/// signature share is sent, `comms` is given the chance to clean up, e.g. by asking the
+/// coordinator to abort.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn sign_over(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    commitments: SigningCommitments<BluePallasSuite>,
+    input: &mut dyn BufRead,
+    logger: &mut dyn Write,
+    confirmation: Confirmation,
+    shutdown: &CancellationToken,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let result = cancellable(
+        shutdown,
+        "signing session",
+        sign_round2(
+            comms,
+            config,
+            nonces,
+            commitments,
+            input,
+            logger,
+            confirmation,
+        ),
+    )
+    .await;
+    if let Err(err) = &result {
+        let _ = comms.cleanup_on_error(&err.to_string()).await;
+    }
+    result
+}
+
+async fn sign_round2(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    commitments: SigningCommitments<BluePallasSuite>,
+    input: &mut dyn BufRead,
+    logger: &mut dyn Write,
+    confirmation: Confirmation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut nonces = Zeroizing::new(frost_bluepallas::round1::import_nonces(
+        nonces,
+        &commitments,
+    )?);
+    let mut commitments = commitments;
+
+    loop {
+        let result = sign_once(
+            comms,
+            config,
+            &nonces,
+            commitments,
+            input,
+            logger,
+            confirmation,
+        )
+        .await;
+        match result {
+            Err(e) if e.is::<RestartRequested>() => {
+                writeln!(logger, "{e}; committing to fresh nonces")?;
+                let (fresh_nonces, fresh_commitments) = recommit_guarded(
+                    config.used_nonces_path.as_deref(),
+                    &nonces,
+                    config.key_package.signing_share(),
+                )?;
+                nonces = Zeroizing::new(fresh_nonces);
+                commitments = fresh_commitments;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// One signing round of [`sign_round2`], with nonces that were not used yet.
+async fn sign_once(
+    comms: &mut dyn Comms<BluePallasSuite>,
+    config: &Config<BluePallasSuite>,
+    nonces: &SigningNonces<BluePallasSuite>,
+    commitments: SigningCommitments<BluePallasSuite>,
+    input: &mut dyn BufRead,
+    logger: &mut dyn Write,
+    confirmation: Confirmation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let key_package = &config.key_package;
+
     // Round 2 - Sign
 
     let round_2_config = comms
         .get_signing_package(input, logger, commitments, *key_package.identifier())
         .await?;
 
+    let signing_package = round_2_config.signing_package.first().unwrap();
+    let transaction = TransactionEnvelope::deserialize(signing_package.message())?;
+    if let Some(network_check) = &config.network_check {
+        network_check(&transaction.network_id())?;
+    }
+
     comms
-        .confirm_message(input, logger, &round_2_config, yes)
+        .confirm_message(input, logger, &round_2_config, confirmation)
         .await?;
 
-    let signing_package = round_2_config.signing_package.first().unwrap();
-    let transaction = TransactionEnvelope::deserialize(signing_package.message())?;
     let pallas_message_bytes = transaction.to_pallas_message().serialize()?;
     let signing_package_for_crypto = frost_core::SigningPackage::new(
         signing_package.signing_commitments().clone(),
         &pallas_message_bytes,
     );
 
-    // Use frost_bluepallas modified sign behaviour
-    let signature =
-        frost_bluepallas::round2::sign(&signing_package_for_crypto, &nonces, key_package)?;
+    // Use frost_bluepallas modified sign behaviour, refusing to ever reuse a nonce
+    let signature = sign_guarded(
+        config.used_nonces_path.as_deref(),
+        &signing_package_for_crypto,
+        nonces,
+        key_package,
+    )?;
 
     comms
         .send_signature_share(*key_package.identifier(), signature)
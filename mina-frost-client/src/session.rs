@@ -10,7 +10,37 @@ use eyre::{eyre, OptionExt};
 use frost_core::keys::dkg::{round1, round2};
 use frost_core::{round1::SigningCommitments, round2::SignatureShare, Ciphersuite, Identifier};
 
-use crate::api::{Msg, PublicKey};
+use crate::api::{AbortArgs, Msg, PublicKey};
+
+/// A participant sent signature shares that differ from the ones it already submitted in
+/// the same session. Its first submission is kept.
+#[derive(Debug, thiserror::Error)]
+#[error("participant {identifier} sent conflicting signature shares; keeping its first submission")]
+pub struct ConflictingSignatureShares {
+    /// Hex-encoded identifier of the participant.
+    pub identifier: String,
+}
+
+/// A participant sent commitments that were invalidated by
+/// [`CoordinatorSessionState::restart`]. Their nonces may already have been used, so the
+/// participant must commit again with fresh nonces.
+#[derive(Debug, thiserror::Error)]
+#[error("participant {identifier} sent commitments invalidated by a restart; fresh commitments are required")]
+pub struct StaleCommitments {
+    /// Hex-encoded identifier of the participant.
+    pub identifier: String,
+}
+
+/// A participant aborted the session with an [`AbortArgs`] message, so the signature can
+/// no longer be produced.
+#[derive(Debug, thiserror::Error)]
+#[error("participant {identifier} aborted the session: {reason}")]
+pub struct ParticipantAborted {
+    /// Hex-encoded identifier of the participant.
+    pub identifier: String,
+    /// The reason given by the participant.
+    pub reason: String,
+}
 
 /// Arguments for the coordinator session state.
 #[derive(Clone, Debug)]
@@ -33,6 +63,8 @@ pub enum CoordinatorSessionState<C: Ciphersuite> {
         /// signed.
         commitments: HashMap<Identifier<C>, Vec<SigningCommitments<C>>>,
         pubkeys: HashMap<PublicKey, Identifier<C>>,
+        /// Commitments received before a restart, which are refused from then on.
+        invalidated: Vec<SigningCommitments<C>>,
     },
     /// Commitments have been sent by all participants. Coordinator can create
     /// SigningPackage and send to participants. Waiting for participants to
@@ -47,6 +79,8 @@ pub enum CoordinatorSessionState<C: Ciphersuite> {
         /// Signature shares sent by participants so far, for each message being
         /// signed.
         signature_shares: HashMap<Identifier<C>, Vec<SignatureShare<C>>>,
+        /// Commitments received before a restart, which are refused from then on.
+        invalidated: Vec<SigningCommitments<C>>,
     },
     /// SignatureShares have been sent by all participants; ready to be fetched
     /// by the coordinator.
@@ -73,7 +107,92 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
             args,
             commitments: Default::default(),
             pubkeys,
+            invalidated: Default::default(),
+        }
+    }
+
+    /// Abandon the current signing round and wait for fresh commitments from every
+    /// participant.
+    ///
+    /// Used when a session aborts after participants committed: their nonces may have
+    /// been consumed, so every commitment received so far is invalidated and refused with
+    /// [`StaleCommitments`] if it is sent again. The participants are told with an
+    /// [`api::RestartArgs`](crate::api::RestartArgs) message and answer it with
+    /// [`UsedNonceStore::recommit`](crate::participant::nonce_guard::UsedNonceStore::recommit).
+    /// Signature shares already received are discarded.
+    pub fn restart(&mut self) -> Result<(), Box<dyn Error>> {
+        match self {
+            CoordinatorSessionState::WaitingForCommitments {
+                args,
+                commitments,
+                pubkeys,
+                invalidated,
+            }
+            | CoordinatorSessionState::WaitingForSignatureShares {
+                args,
+                commitments,
+                pubkeys,
+                invalidated,
+                ..
+            } => {
+                invalidated.extend(commitments.drain().flat_map(|(_, c)| c));
+                *self = CoordinatorSessionState::WaitingForCommitments {
+                    args: args.clone(),
+                    commitments: Default::default(),
+                    pubkeys: std::mem::take(pubkeys),
+                    invalidated: std::mem::take(invalidated),
+                };
+                Ok(())
+            }
+            CoordinatorSessionState::SignatureSharesReady { .. } => {
+                Err(eyre!("cannot restart a session that has all signature shares").into())
+            }
+        }
+    }
+
+    /// Skip round 1 with `commitments` that were collected ahead of time, so that the
+    /// session waits for signature shares right away.
+    ///
+    /// Only possible before any participant sent commitments in this session. Commitments
+    /// invalidated by a [`Self::restart`] are refused with [`StaleCommitments`].
+    pub fn use_commitments(
+        &mut self,
+        commitments: HashMap<Identifier<C>, Vec<SigningCommitments<C>>>,
+    ) -> Result<(), Box<dyn Error>> {
+        let CoordinatorSessionState::WaitingForCommitments {
+            args,
+            commitments: received,
+            pubkeys,
+            invalidated,
+        } = self
+        else {
+            return Err(eyre!("commitments were already collected in this session").into());
+        };
+        if !received.is_empty() {
+            return Err(eyre!("participants already sent commitments in this session").into());
         }
+        if commitments.len() != args.num_signers {
+            return Err(eyre!("wrong number of signers").into());
+        }
+        for (identifier, commitments) in &commitments {
+            if commitments.len() != args.num_messages {
+                return Err(eyre!("wrong number of commitments").into());
+            }
+            if commitments.iter().any(|c| invalidated.contains(c)) {
+                return Err(StaleCommitments {
+                    identifier: hex::encode(identifier.serialize()),
+                }
+                .into());
+            }
+        }
+        *self = CoordinatorSessionState::WaitingForSignatureShares {
+            args: args.clone(),
+            commitments,
+            pubkeys: std::mem::take(pubkeys),
+            signature_shares: Default::default(),
+            invalidated: std::mem::take(invalidated),
+        };
+        Ok(())
     }
 
     /// Handle a Msg received from a participant.
@@ -82,7 +201,13 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
     /// returns true, and after the SigningPackage is sent to the participants,
     /// it should be called for new Msgs until [`Self::has_signature_shares`]
     /// returns true.
+    ///
+    /// An [`AbortArgs`] message from a participant of the session fails with
+    /// [`ParticipantAborted`].
     pub fn recv(&mut self, msg: Msg) -> Result<(), Box<dyn Error>> {
+        if let Ok(AbortArgs { abort }) = serde_json::from_slice(&msg.msg) {
+            return Err(self.participant_aborted(&msg.sender, abort));
+        }
         match self {
             CoordinatorSessionState::WaitingForCommitments { .. } => {
                 let send_commitments_args: Vec<SigningCommitments<C>> =
@@ -99,6 +224,24 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
         Ok(())
     }
 
+    fn participant_aborted(&self, pubkey: &PublicKey, reason: String) -> Box<dyn Error> {
+        let pubkeys = match self {
+            CoordinatorSessionState::WaitingForCommitments { pubkeys, .. }
+            | CoordinatorSessionState::WaitingForSignatureShares { pubkeys, .. } => pubkeys,
+            CoordinatorSessionState::SignatureSharesReady { .. } => {
+                return eyre!("received message during wrong state").into()
+            }
+        };
+        match pubkeys.get(pubkey) {
+            Some(identifier) => ParticipantAborted {
+                identifier: hex::encode(identifier.serialize()),
+                reason,
+            }
+            .into(),
+            None => eyre!("unknown participant").into(),
+        }
+    }
+
     /// Handle commitments sent by a participant.
     fn handle_commitments(
         &mut self,
@@ -109,12 +252,19 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
             args,
             commitments: commitments_map,
             pubkeys,
+            invalidated,
         } = self
         {
             if commitments.len() != args.num_messages {
                 return Err(eyre!("wrong number of commitments").into());
             }
             let identifier = *pubkeys.get(&pubkey).ok_or(eyre!("unknown participant"))?;
+            if commitments.iter().any(|c| invalidated.contains(c)) {
+                return Err(StaleCommitments {
+                    identifier: hex::encode(identifier.serialize()),
+                }
+                .into());
+            }
 
             // Add commitment to map.
             // Currently ignores the possibility of overwriting previous values
@@ -129,6 +279,7 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
                     commitments: commitments_map.clone(),
                     pubkeys: pubkeys.clone(),
                     signature_shares: Default::default(),
+                    invalidated: std::mem::take(invalidated),
                 }
             }
             Ok(())
@@ -197,6 +348,7 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
             commitments,
             signature_shares: signature_shares_map,
             pubkeys,
+            ..
         } = self
         {
             if signature_shares.len() != args.num_messages {
@@ -207,9 +359,19 @@ impl<C: Ciphersuite> CoordinatorSessionState<C> {
                 return Err(eyre!("invalid identifier").into());
             }
 
-            // Currently ignoring the possibility of overwriting previous values
-            // (it seems better to ignore overwrites, which could be caused by
-            // poor networking connectivity leading to retries)
+            // The first submission wins. A retry after poor connectivity resends the same
+            // shares and is accepted as a no-op, while different shares under the same
+            // identifier are rejected so they cannot replace the ones already received.
+            match signature_shares_map.get(&identifier) {
+                Some(existing) if *existing == signature_shares => return Ok(()),
+                Some(_) => {
+                    return Err(ConflictingSignatureShares {
+                        identifier: hex::encode(identifier.serialize()),
+                    }
+                    .into())
+                }
+                None => {}
+            }
             signature_shares_map.insert(identifier, signature_shares);
             // If complete, advance to next state
             if signature_shares_map.keys().cloned().collect::<HashSet<_>>()
@@ -593,3 +755,224 @@ impl<C: Ciphersuite> DKGSessionState<C> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use frost_bluepallas::keys::generate_key_packages_with_dealer;
+    use frost_core::{
+        keys::{IdentifierList, KeyPackage, PublicKeyPackage},
+        round1::SigningNonces,
+    };
+    use mina_hasher::ROInput;
+    use mina_tx::{pallas_message::PallasMessage, NetworkId};
+    use rand::thread_rng;
+
+    use super::*;
+    use crate::{
+        participant::nonce_guard::{NonceGuardError, UsedNonceStore},
+        BluePallasSuite,
+    };
+
+    type State = CoordinatorSessionState<BluePallasSuite>;
+
+    fn share(value: u8) -> SignatureShare<BluePallasSuite> {
+        let mut bytes = [0u8; 32];
+        bytes[0] = value;
+        SignatureShare::deserialize(&bytes).unwrap()
+    }
+
+    fn msg(sender: &PublicKey, shares: &[SignatureShare<BluePallasSuite>]) -> Msg {
+        Msg {
+            sender: sender.clone(),
+            msg: serde_json::to_vec(shares).unwrap(),
+        }
+    }
+
+    /// A signer of [`waiting_for_shares`] and what it kept from round 1.
+    struct CommittedSigner {
+        pubkey: PublicKey,
+        key_package: KeyPackage<BluePallasSuite>,
+        nonces: SigningNonces<BluePallasSuite>,
+        commitments: SigningCommitments<BluePallasSuite>,
+    }
+
+    /// A 2-of-3 session that has all commitments and waits for signature shares.
+    fn waiting_for_shares() -> (
+        State,
+        [CommittedSigner; 2],
+        PublicKeyPackage<BluePallasSuite>,
+    ) {
+        let mut rng = thread_rng();
+        let (key_packages, pubkey_package) = generate_key_packages_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            IdentifierList::Default,
+            &mut rng,
+        )
+        .unwrap();
+        let mut key_packages = key_packages.into_values();
+        let signers = [0u8, 1].map(|i| {
+            let key_package = key_packages.next().unwrap();
+            let (nonces, commitments) =
+                frost_bluepallas::round1::commit(key_package.signing_share(), &mut rng);
+            CommittedSigner {
+                pubkey: PublicKey(vec![i]),
+                key_package,
+                nonces,
+                commitments,
+            }
+        });
+        let pubkeys = signers
+            .iter()
+            .map(|signer| (signer.pubkey.clone(), *signer.key_package.identifier()))
+            .collect();
+
+        let mut state = State::new(1, 2, pubkeys);
+        for signer in &signers {
+            state
+                .recv(commitments_msg(&signer.pubkey, signer.commitments))
+                .unwrap();
+        }
+        assert!(state.has_commitments());
+        (state, signers, pubkey_package)
+    }
+
+    #[test]
+    fn test_identical_share_resubmission_is_accepted() {
+        let (mut state, [alice, bob], _) = waiting_for_shares();
+        let (alice, bob) = (alice.pubkey, bob.pubkey);
+        state.recv(msg(&alice, &[share(1)])).unwrap();
+        state.recv(msg(&alice, &[share(1)])).unwrap();
+        assert!(!state.has_signature_shares());
+
+        state.recv(msg(&bob, &[share(2)])).unwrap();
+        assert!(state.has_signature_shares());
+        let shares = state.signature_shares().unwrap();
+        assert!(shares[0].values().any(|s| *s == share(1)));
+    }
+
+    #[test]
+    fn test_abort_from_a_signer_fails_the_session() {
+        let (mut state, [alice, _], _) = waiting_for_shares();
+        let alice = alice.pubkey;
+        let abort = |sender: &PublicKey| Msg {
+            sender: sender.clone(),
+            msg: serde_json::to_vec(&AbortArgs {
+                abort: "signing declined".to_string(),
+            })
+            .unwrap(),
+        };
+
+        // Someone outside the session cannot abort it
+        let err = state.recv(abort(&PublicKey(vec![9]))).unwrap_err();
+        assert!(err.downcast_ref::<ParticipantAborted>().is_none());
+
+        let err = state.recv(abort(&alice)).unwrap_err();
+        let aborted = err.downcast_ref::<ParticipantAborted>().unwrap();
+        assert_eq!(aborted.reason, "signing declined");
+    }
+
+    #[test]
+    fn test_conflicting_share_resubmission_is_rejected() {
+        let (mut state, [alice, bob], _) = waiting_for_shares();
+        let (alice, bob) = (alice.pubkey, bob.pubkey);
+        state.recv(msg(&alice, &[share(1)])).unwrap();
+
+        let err = state.recv(msg(&alice, &[share(3)])).unwrap_err();
+        assert!(err.downcast_ref::<ConflictingSignatureShares>().is_some());
+
+        state.recv(msg(&bob, &[share(2)])).unwrap();
+        let shares = state.signature_shares().unwrap();
+        assert!(shares[0].values().any(|s| *s == share(1)));
+        assert!(!shares[0].values().any(|s| *s == share(3)));
+    }
+
+    fn commitments_msg(
+        sender: &PublicKey,
+        commitments: SigningCommitments<BluePallasSuite>,
+    ) -> Msg {
+        Msg {
+            sender: sender.clone(),
+            msg: serde_json::to_vec(&[commitments]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_pre_distributed_commitments_skip_round_one_once() {
+        let (mut state, _, _) = waiting_for_shares();
+        let (commitments, _) = state.commitments().unwrap();
+        let commitments: HashMap<_, _> = commitments[0]
+            .iter()
+            .map(|(identifier, commitments)| (*identifier, vec![*commitments]))
+            .collect();
+
+        // Round 1 already ran in this session
+        assert!(state.use_commitments(commitments.clone()).is_err());
+
+        // After a restart, the commitments of the abandoned round are stale
+        state.restart().unwrap();
+        let err = state.use_commitments(commitments).unwrap_err();
+        assert!(err.downcast_ref::<StaleCommitments>().is_some());
+        assert!(!state.has_commitments());
+    }
+
+    #[test]
+    fn test_restart_requires_fresh_commitments() {
+        let mut rng = thread_rng();
+        let (mut state, signers, pubkey_package) = waiting_for_shares();
+        let mut stores: Vec<UsedNonceStore> =
+            signers.iter().map(|_| UsedNonceStore::new()).collect();
+
+        // The session aborts after round 1
+        state.restart().unwrap();
+        assert!(!state.has_commitments());
+
+        // Stale commitments are refused, fresh ones complete the round
+        let err = state
+            .recv(commitments_msg(&signers[0].pubkey, signers[0].commitments))
+            .unwrap_err();
+        assert!(err.downcast_ref::<StaleCommitments>().is_some());
+        let mut fresh = Vec::new();
+        for (store, signer) in stores.iter_mut().zip(&signers) {
+            let (nonces, commitments) = store
+                .recommit(&signer.nonces, signer.key_package.signing_share(), &mut rng)
+                .unwrap();
+            state
+                .recv(commitments_msg(&signer.pubkey, commitments))
+                .unwrap();
+            fresh.push(nonces);
+        }
+        assert!(state.has_commitments());
+
+        let message = PallasMessage::from_parts(
+            ROInput::new().append_bytes(b"restarted"),
+            NetworkId::Testnet,
+            true,
+        )
+        .serialize()
+        .unwrap();
+        let (commitments, _) = state.commitments().unwrap();
+        let signing_package = frost_core::SigningPackage::new(commitments[0].clone(), &message);
+
+        // The participant refuses to sign with the nonces consumed before the restart
+        let err = stores[0]
+            .sign(
+                &signing_package,
+                &signers[0].nonces,
+                &signers[0].key_package,
+            )
+            .unwrap_err();
+        assert!(matches!(err, NonceGuardError::NonceReused(_)));
+
+        let mut signature_shares = BTreeMap::new();
+        for ((store, signer), nonces) in stores.iter_mut().zip(&signers).zip(&fresh) {
+            let share = store
+                .sign(&signing_package, nonces, &signer.key_package)
+                .unwrap();
+            state.recv(msg(&signer.pubkey, &[share])).unwrap();
+            signature_shares.insert(*signer.key_package.identifier(), share);
+        }
+        assert!(state.has_signature_shares());
+        frost_bluepallas::aggregate(&signing_package, &signature_shares, &pubkey_package).unwrap();
+    }
+}
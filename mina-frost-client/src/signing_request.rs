@@ -0,0 +1,309 @@
+//! Portable signing requests for signers on air-gapped machines.
+//!
+//! Instead of relaying the signing package through frostd, a coordinator can export a
+//! [`SigningRequest`] built from commitments the signers produced offline. Each signer
+//! imports the request on their own machine and signs it with the nonces they kept
+//! from round 1. The request records the network and ciphersuite it is for, so a signer
+//! refuses a request meant for another network before looking at the transaction.
+
+use std::{collections::BTreeMap, path::Path};
+
+use frost_bluepallas::errors::FrostBluePallasError;
+use frost_core::{
+    keys::KeyPackage,
+    round1::{SigningCommitments, SigningNonces},
+    round2::SignatureShare,
+    Ciphersuite, Identifier, SigningPackage,
+};
+use mina_tx::{
+    errors::MinaTxError, network_id::NetworkId, pallas_message::PallasMessage, TransactionEnvelope,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::participant::{nonce_guard::NonceGuardError, sign::sign_guarded};
+use crate::BluePallasSuite;
+
+/// Format version written by [`SigningRequest::to_json`].
+pub const SIGNING_REQUEST_VERSION: u8 = 1;
+
+/// Errors from exporting, importing or signing a [`SigningRequest`].
+#[derive(Debug, thiserror::Error)]
+pub enum SigningRequestError {
+    #[error("unsupported signing request version {0}")]
+    UnsupportedVersion(u8),
+    #[error("signing request is for ciphersuite {0}, not {expected}", expected = <BluePallasSuite as Ciphersuite>::ID)]
+    WrongCiphersuite(String),
+    #[error("signing request is for network {requested:?}, but network {expected:?} was expected")]
+    WrongNetwork {
+        requested: NetworkId,
+        expected: NetworkId,
+    },
+    #[error(
+        "signing request declares network {declared:?}, but its transaction is for {actual:?}"
+    )]
+    InconsistentNetwork {
+        declared: NetworkId,
+        actual: NetworkId,
+    },
+    #[error("signing request participants do not match its commitments")]
+    InconsistentParticipants,
+    #[error("participant {0} is not part of this signing request")]
+    NotAParticipant(String),
+    #[error("nonces do not match the commitments recorded for participant {0}")]
+    CommitmentMismatch(String),
+    #[error("invalid transaction in signing request: {0}")]
+    Transaction(#[from] MinaTxError),
+    #[error("invalid signing request: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Frost(#[from] FrostBluePallasError<PallasMessage>),
+    #[error(transparent)]
+    NonceGuard(#[from] NonceGuardError),
+}
+
+impl From<frost_core::Error<BluePallasSuite>> for SigningRequestError {
+    fn from(e: frost_core::Error<BluePallasSuite>) -> Self {
+        SigningRequestError::Frost(e.into())
+    }
+}
+
+/// Everything a signer needs to produce a signature share offline.
+///
+/// The message of `signing_package` is the serialized [`TransactionEnvelope`], exactly
+/// as sent by the coordinator over frostd.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningRequest {
+    pub version: u8,
+    pub ciphersuite: String,
+    pub network_id: NetworkId,
+    pub participants: Vec<Identifier<BluePallasSuite>>,
+    pub signing_package: SigningPackage<BluePallasSuite>,
+}
+
+impl SigningRequest {
+    /// Build a request to sign `transaction` with the signers that sent `commitments`.
+    pub fn new(
+        transaction: &TransactionEnvelope,
+        commitments: BTreeMap<Identifier<BluePallasSuite>, SigningCommitments<BluePallasSuite>>,
+    ) -> Result<Self, SigningRequestError> {
+        let participants = commitments.keys().copied().collect();
+        Ok(Self {
+            version: SIGNING_REQUEST_VERSION,
+            ciphersuite: <BluePallasSuite as Ciphersuite>::ID.to_string(),
+            network_id: transaction.network_id(),
+            participants,
+            signing_package: SigningPackage::new(commitments, &transaction.serialize()?),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String, SigningRequestError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse an exported request, rejecting it unless it is internally consistent.
+    pub fn from_json(json: &str) -> Result<Self, SigningRequestError> {
+        let request: Self = serde_json::from_str(json)?;
+        if request.version != SIGNING_REQUEST_VERSION {
+            return Err(SigningRequestError::UnsupportedVersion(request.version));
+        }
+        if request.ciphersuite != <BluePallasSuite as Ciphersuite>::ID {
+            return Err(SigningRequestError::WrongCiphersuite(request.ciphersuite));
+        }
+        let actual = request.transaction()?.network_id();
+        if actual != request.network_id {
+            return Err(SigningRequestError::InconsistentNetwork {
+                declared: request.network_id,
+                actual,
+            });
+        }
+        if !request
+            .participants
+            .iter()
+            .eq(request.signing_package.signing_commitments().keys())
+        {
+            return Err(SigningRequestError::InconsistentParticipants);
+        }
+        Ok(request)
+    }
+
+    /// The transaction to be signed.
+    pub fn transaction(&self) -> Result<TransactionEnvelope, SigningRequestError> {
+        Ok(TransactionEnvelope::deserialize(
+            self.signing_package.message(),
+        )?)
+    }
+
+    /// Produce this signer's share, refusing requests for any network but `network_id`.
+    ///
+    /// `nonces` must be the ones whose commitments the signer handed to the coordinator.
+    /// Like online signing, the same nonces are never used twice: in this process, and
+    /// also across runs if `used_nonces_path` names the record to check them against.
+    pub fn sign(
+        &self,
+        network_id: &NetworkId,
+        key_package: &KeyPackage<BluePallasSuite>,
+        nonces: &SigningNonces<BluePallasSuite>,
+        used_nonces_path: Option<&Path>,
+    ) -> Result<SignatureShare<BluePallasSuite>, SigningRequestError> {
+        if self.network_id != *network_id {
+            return Err(SigningRequestError::WrongNetwork {
+                requested: self.network_id.clone(),
+                expected: network_id.clone(),
+            });
+        }
+
+        let identifier = key_package.identifier();
+        let commitments = self
+            .signing_package
+            .signing_commitment(identifier)
+            .ok_or_else(|| {
+                SigningRequestError::NotAParticipant(hex::encode(identifier.serialize()))
+            })?;
+        if commitments != *nonces.commitments() {
+            return Err(SigningRequestError::CommitmentMismatch(hex::encode(
+                identifier.serialize(),
+            )));
+        }
+
+        let pallas_message_bytes = self.transaction()?.to_pallas_message().serialize()?;
+        let signing_package = SigningPackage::new(
+            self.signing_package.signing_commitments().clone(),
+            &pallas_message_bytes,
+        );
+        Ok(sign_guarded(
+            used_nonces_path,
+            &signing_package,
+            nonces,
+            key_package,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use frost_bluepallas::keys::generate_with_dealer;
+    use frost_core::keys::{IdentifierList, KeyPackage, PublicKeyPackage};
+    use mina_tx::{
+        legacy_tx::LegacyTransaction,
+        network_id::NetworkId,
+        pallas_message::{translate_pk, PallasMessage},
+        TransactionEnvelope,
+    };
+    use rand::thread_rng;
+
+    use super::{SigningRequest, SigningRequestError};
+    use crate::coordinator::coordinate_signing::aggregate_and_verify;
+    use crate::BluePallasSuite;
+
+    fn group() -> (
+        Vec<KeyPackage<BluePallasSuite>>,
+        PublicKeyPackage<BluePallasSuite>,
+        TransactionEnvelope,
+    ) {
+        let (shares, pubkey_package) = generate_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            IdentifierList::Default,
+            &mut thread_rng(),
+        )
+        .unwrap();
+        let key_packages = shares
+            .into_values()
+            .take(2)
+            .map(|share| KeyPackage::try_from(share).unwrap())
+            .collect();
+        let group_pk = translate_pk(pubkey_package.verifying_key()).unwrap();
+        let transaction = TransactionEnvelope::new_legacy(
+            NetworkId::Testnet,
+            LegacyTransaction::new_payment(group_pk.clone(), group_pk, 1_000, 1_000, 0),
+        );
+        (key_packages, pubkey_package, transaction)
+    }
+
+    #[test]
+    fn exported_request_is_signed_offline() {
+        let (key_packages, pubkey_package, transaction) = group();
+        let round1: Vec<_> = key_packages
+            .iter()
+            .map(|key_package| {
+                frost_bluepallas::round1::commit(key_package.signing_share(), &mut thread_rng())
+            })
+            .collect();
+        let commitments: BTreeMap<_, _> = key_packages
+            .iter()
+            .zip(&round1)
+            .map(|(key_package, (_, commitments))| (*key_package.identifier(), *commitments))
+            .collect();
+
+        let exported = SigningRequest::new(&transaction, commitments.clone())
+            .unwrap()
+            .to_json()
+            .unwrap();
+
+        // Each signer imports the request on their own machine
+        let shares: BTreeMap<_, _> = key_packages
+            .iter()
+            .zip(&round1)
+            .map(|(key_package, (nonces, _))| {
+                let request = SigningRequest::from_json(&exported).unwrap();
+                assert_eq!(request.transaction().unwrap(), transaction);
+                let share = request
+                    .sign(&NetworkId::Testnet, key_package, nonces, None)
+                    .unwrap();
+                (*key_package.identifier(), share)
+            })
+            .collect();
+
+        assert!(aggregate_and_verify(&transaction, commitments, &shares, &pubkey_package).is_ok());
+    }
+
+    #[test]
+    fn request_for_another_network_is_refused() {
+        let (key_packages, _, transaction) = group();
+        let (nonces, commitments) =
+            frost_bluepallas::round1::commit(key_packages[0].signing_share(), &mut thread_rng());
+        let request = SigningRequest::new(
+            &transaction,
+            BTreeMap::from([(*key_packages[0].identifier(), commitments)]),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            request.sign(&NetworkId::Mainnet, &key_packages[0], &nonces, None),
+            Err(SigningRequestError::WrongNetwork { .. })
+        ));
+
+        // Relabelling the network does not get past the transaction's own network id
+        let mut relabelled = request.clone();
+        relabelled.network_id = NetworkId::Mainnet;
+        assert!(matches!(
+            SigningRequest::from_json(&relabelled.to_json().unwrap()),
+            Err(SigningRequestError::InconsistentNetwork { .. })
+        ));
+    }
+
+    #[test]
+    fn request_with_foreign_nonces_is_refused() {
+        let (key_packages, _, transaction) = group();
+        let (_, commitments) =
+            frost_bluepallas::round1::commit(key_packages[0].signing_share(), &mut thread_rng());
+        let (other_nonces, _) =
+            frost_bluepallas::round1::commit(key_packages[0].signing_share(), &mut thread_rng());
+        let request = SigningRequest::new(
+            &transaction,
+            BTreeMap::from([(*key_packages[0].identifier(), commitments)]),
+        )
+        .unwrap();
+
+        assert!(matches!(
+            request.sign(&NetworkId::Testnet, &key_packages[0], &other_nonces, None),
+            Err(SigningRequestError::CommitmentMismatch(_))
+        ));
+        assert!(matches!(
+            request.sign(&NetworkId::Testnet, &key_packages[1], &other_nonces, None),
+            Err(SigningRequestError::NotAParticipant(_))
+        ));
+    }
+}
@@ -5,4 +5,4 @@ pub use config::Config;
 pub use keygen::keygen;
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
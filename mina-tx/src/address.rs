@@ -0,0 +1,60 @@
+//! Validation of `B62q...` Mina account addresses.
+//!
+//! Parsing an address with [`CompressedPubKey::from_address`] deep inside transaction
+//! handling turns a typo into a panic; these helpers let front ends reject it up front.
+
+use alloc::format;
+use mina_signer::CompressedPubKey;
+
+use crate::errors::MinaTxError;
+
+/// Whether `s` is a well-formed Mina address: correct base58check encoding, version
+/// bytes and a point on the Pallas curve.
+pub fn is_valid_address(s: &str) -> bool {
+    validate_address(s).is_ok()
+}
+
+/// Check that `s` is a well-formed Mina address.
+///
+/// The error names the rejected input, so it can be shown to the user as-is.
+pub fn validate_address(s: &str) -> Result<(), MinaTxError> {
+    CompressedPubKey::from_address(s)
+        .map(|_| ())
+        .map_err(|e| MinaTxError::InvalidPublicKey(format!("{s:?} is not a Mina address: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+
+    #[test]
+    fn accepts_valid_address() {
+        assert!(is_valid_address(ADDRESS));
+        assert_eq!(validate_address(ADDRESS), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        let truncated = &ADDRESS[..ADDRESS.len() - 1];
+        let mut bad_checksum = ADDRESS.to_string();
+        bad_checksum.replace_range(bad_checksum.len() - 1.., "h");
+        let invalid_chars = ADDRESS.replace('p', "0");
+
+        for input in [
+            "",
+            "B62q",
+            truncated,
+            bad_checksum.as_str(),
+            invalid_chars.as_str(),
+            " B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+        ] {
+            assert!(!is_valid_address(input), "{input:?} accepted");
+            assert!(matches!(
+                validate_address(input),
+                Err(MinaTxError::InvalidPublicKey(_))
+            ));
+        }
+    }
+}
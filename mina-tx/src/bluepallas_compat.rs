@@ -2,17 +2,20 @@
 //!
 //! Keep crypto bridge code here so core transaction modules stay clean.
 
+use alloc::vec::Vec;
 use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::PrimeField;
-use frost_bluepallas::BluePallas;
-use frost_core::{Scalar, Signature as FrSig, VerifyingKey};
+use ark_ff::{BigInteger, PrimeField};
+use frost_bluepallas::{BluePallas, PallasGroup, PallasScalarField};
+use frost_core::{Field, Group, Scalar, Signature as FrSig, SigningPackage, VerifyingKey};
+use mina_curves::pasta::Pallas;
 use mina_hasher::Hashable;
+use mina_signer::Signer;
 
 use crate::{
     errors::MinaTxError,
-    pallas_message::{translate_pk, PallasMessage},
+    pallas_message::{translate_pk, translate_sig, PallasMessage},
     signatures::{PubKeySer, Sig, TransactionSignature},
-    transactions::TransactionEnvelope,
+    transactions::{network_id::NetworkId, TransactionEnvelope},
 };
 
 type BluePallasSuite = BluePallas<PallasMessage>;
@@ -38,6 +41,31 @@ impl TryFrom<FrSig<BluePallasSuite>> for Sig {
     }
 }
 
+/// Decode a Mina base58 signature, such as the `authorization` of a zkApp fee payer, into a
+/// FROST signature that can be checked with [`VerifyingKey::verify`].
+///
+/// Mina only keeps the x coordinate of the nonce commitment `R`; the point with even y is
+/// used, since Mina signatures always have one.
+pub fn decode_zkapp_signature(auth: &str) -> Result<FrSig<BluePallasSuite>, MinaTxError> {
+    let sig = Sig::from_base58(auth)?;
+    let rx = mina_signer::BaseField::from_bigint(sig.field).ok_or_else(|| {
+        MinaTxError::InvalidSignature("rx is not a canonical field element".into())
+    })?;
+    let z = mina_signer::ScalarField::from_bigint(sig.scalar)
+        .ok_or_else(|| MinaTxError::InvalidSignature("s is not a canonical scalar".into()))?;
+
+    let r = Pallas::get_point_from_x_unchecked(rx, false)
+        .ok_or_else(|| MinaTxError::InvalidSignature("rx is not on the curve".into()))?;
+    let r = if r.y.into_bigint().is_even() { r } else { -r };
+
+    let mut bytes: Vec<u8> = PallasGroup::serialize(&r.into_group())
+        .map_err(|e| MinaTxError::InvalidSignature(e.to_string()))?
+        .to_vec();
+    bytes.extend_from_slice(&PallasScalarField::serialize(&z));
+    FrSig::<BluePallasSuite>::deserialize(&bytes)
+        .map_err(|e| MinaTxError::InvalidSignature(e.to_string()))
+}
+
 impl TryFrom<VerifyingKey<BluePallasSuite>> for PubKeySer {
     type Error = MinaTxError;
 
@@ -52,6 +80,70 @@ impl TransactionEnvelope {
     pub fn to_pallas_message(&self) -> PallasMessage {
         PallasMessage::from_parts(self.to_roinput(), self.network_id(), self.is_legacy())
     }
+
+    /// Verify a FROST signature over this transaction with `mina_signer`, exactly as a Mina node would.
+    ///
+    /// frost-core verification only proves the signature is consistent with the challenge
+    /// computed by [`PallasMessage`]; this check guards against encoding mismatches that
+    /// would make a node reject an otherwise valid FROST signature.
+    pub fn verify_mina(
+        &self,
+        public_key: &VerifyingKey<BluePallasSuite>,
+        signature: &FrSig<BluePallasSuite>,
+    ) -> Result<(), MinaTxError> {
+        let pub_key = translate_pk(public_key)?;
+        let mina_sig = translate_sig(signature)?;
+
+        let verified = if self.is_legacy() {
+            let mut ctx = mina_signer::create_legacy::<TransactionEnvelope>(self.network_id());
+            ctx.verify(&mina_sig, &pub_key, self)
+        } else {
+            let mut ctx = mina_signer::create_kimchi::<TransactionEnvelope>(self.network_id());
+            ctx.verify(&mina_sig, &pub_key, self)
+        };
+
+        if verified {
+            Ok(())
+        } else {
+            Err(MinaTxError::InvalidSignature(format!(
+                "signature does not verify under Mina rules for network {:?}",
+                self.network_id()
+            )))
+        }
+    }
+
+    /// Like [`Self::verify_mina`], but when verification fails, check whether the signature
+    /// was made for another network instead.
+    ///
+    /// The network is part of the signed hash, so a signature made for testnet never
+    /// verifies on mainnet and vice versa. If the signature verifies under the other of
+    /// the two (or under either one, for a custom network),
+    /// [`MinaTxError::NetworkMismatch`] is returned instead of the plain failure.
+    pub fn verify_mina_diagnostic(
+        &self,
+        public_key: &VerifyingKey<BluePallasSuite>,
+        signature: &FrSig<BluePallasSuite>,
+    ) -> Result<(), MinaTxError> {
+        let Err(err) = self.verify_mina(public_key, signature) else {
+            return Ok(());
+        };
+
+        let candidates = match self.network_id() {
+            NetworkId::Mainnet => vec![NetworkId::Testnet],
+            NetworkId::Testnet => vec![NetworkId::Mainnet],
+            NetworkId::Custom(_) => vec![NetworkId::Mainnet, NetworkId::Testnet],
+        };
+        for candidate in candidates {
+            let retargeted = TransactionEnvelope::new(candidate.clone(), self.inner().clone());
+            if retargeted.verify_mina(public_key, signature).is_ok() {
+                return Err(MinaTxError::NetworkMismatch {
+                    expected: self.network_id(),
+                    signed_for: candidate,
+                });
+            }
+        }
+        Err(err)
+    }
 }
 
 impl From<&TransactionEnvelope> for PallasMessage {
@@ -60,6 +152,39 @@ impl From<&TransactionEnvelope> for PallasMessage {
     }
 }
 
+/// What a [`SigningPackage`] asks participants to sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningMessage<'a> {
+    /// The exact bytes the signature commits to.
+    pub bytes: &'a [u8],
+    /// The transaction the bytes encode, or `None` if they are not a serialized
+    /// [`TransactionEnvelope`].
+    pub transaction: Option<TransactionEnvelope>,
+}
+
+impl AsRef<[u8]> for SigningMessage<'_> {
+    fn as_ref(&self) -> &[u8] {
+        self.bytes
+    }
+}
+
+/// Lets participants inspect a [`SigningPackage`] before signing it.
+pub trait SigningPackageExt {
+    /// The raw message along with the transaction it decodes to, if any. Decoding never
+    /// panics; a message that is not a transaction just has no decoded form.
+    fn signing_message(&self) -> SigningMessage<'_>;
+}
+
+impl SigningPackageExt for SigningPackage<BluePallasSuite> {
+    fn signing_message(&self) -> SigningMessage<'_> {
+        let bytes: &[u8] = self.message();
+        SigningMessage {
+            bytes,
+            transaction: TransactionEnvelope::deserialize(bytes).ok(),
+        }
+    }
+}
+
 impl TransactionSignature {
     pub fn from_frost_signature(
         public_key: VerifyingKey<BluePallasSuite>,
@@ -82,3 +207,99 @@ impl TransactionSignature {
         Self::from_frost_signature(public_key, signature, payload)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{keypair, payment, sign, verifying_key};
+
+    #[test]
+    fn test_sig_hex_roundtrip_verifies() {
+        let envelope = payment(1_000);
+        let signature = sign(&envelope);
+        let sig = Sig::try_from(signature).unwrap();
+
+        let encoded = sig.to_hex();
+        assert!(encoded.starts_with("0x"));
+        assert_eq!(encoded.len(), 2 + 128);
+
+        let decoded = Sig::from_hex(&encoded).unwrap();
+        assert_eq!(decoded.to_base58(), sig.to_base58());
+        let parsed = mina_signer::Signature::new(
+            mina_signer::BaseField::from_bigint(decoded.field).unwrap(),
+            mina_signer::ScalarField::from_bigint(decoded.scalar).unwrap(),
+        );
+        let mut ctx = mina_signer::create_legacy::<TransactionEnvelope>(NetworkId::Testnet);
+        assert!(ctx.verify(&parsed, &keypair().public, &envelope));
+    }
+
+    #[test]
+    fn test_verify_mina_diagnostic_reports_network_mismatch() {
+        let testnet = payment(1_000);
+        let signature = sign(&testnet);
+        let public_key = verifying_key();
+
+        assert!(testnet
+            .verify_mina_diagnostic(&public_key, &signature)
+            .is_ok());
+
+        let mainnet = TransactionEnvelope::new(NetworkId::Mainnet, testnet.inner().clone());
+        assert!(matches!(
+            mainnet.verify_mina(&public_key, &signature),
+            Err(MinaTxError::InvalidSignature(_))
+        ));
+        assert_eq!(
+            mainnet.verify_mina_diagnostic(&public_key, &signature),
+            Err(MinaTxError::NetworkMismatch {
+                expected: NetworkId::Mainnet,
+                signed_for: NetworkId::Testnet,
+            })
+        );
+
+        // A signature for another message fails plainly on both networks
+        let other = payment(2_000);
+        assert!(matches!(
+            other.verify_mina_diagnostic(&public_key, &signature),
+            Err(MinaTxError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_signing_message_decodes_transactions_only() {
+        let envelope = payment(1_000);
+        let bytes = envelope.serialize().unwrap();
+        let package = SigningPackage::<BluePallasSuite>::new(Default::default(), &bytes);
+        let message = package.signing_message();
+        assert_eq!(message.as_ref(), bytes.as_slice());
+        assert_eq!(message.transaction, Some(envelope));
+
+        let package = SigningPackage::<BluePallasSuite>::new(Default::default(), b"\x00raw bytes");
+        let message = package.signing_message();
+        assert_eq!(message.bytes, b"\x00raw bytes");
+        assert_eq!(message.transaction, None);
+    }
+
+    #[test]
+    fn test_decode_zkapp_signature_roundtrips_fee_payer_authorization() {
+        let auth = "7mWxjLYgbJUkZNcGouvhVj5tJ8yu9hoexb9ntvPK8t5LHqzmrL6QJjjKtf5SgmxB4QWkDw7qoMMbbNGtHVpsbJHPyTy2EzRQ";
+        let signature = decode_zkapp_signature(auth).unwrap();
+        assert!(signature.R().into_affine().y.into_bigint().is_even());
+        assert_eq!(Sig::try_from(signature).unwrap().to_base58(), auth);
+
+        assert!(matches!(
+            decode_zkapp_signature(&auth[1..]),
+            Err(MinaTxError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_zkapp_signature_verifies() {
+        let envelope = payment(1_000);
+        let signature = sign(&envelope);
+        let encoded = Sig::try_from(signature).unwrap().to_base58();
+
+        let decoded = decode_zkapp_signature(&encoded).unwrap();
+        let public_key = verifying_key();
+        assert!(envelope.verify_mina(&public_key, &decoded).is_ok());
+    }
+}
@@ -1,8 +1,10 @@
 //! Error types for the frost-bluepallas library
 
-use alloc::string::String;
+use alloc::{string::String, vec::Vec};
 use core::{error, fmt};
 
+use crate::transactions::network_id::NetworkId;
+
 pub type MinaTxResult<T> = Result<T, Box<dyn error::Error>>;
 
 /// Error enum for frost-bluepallas operations
@@ -34,6 +36,18 @@ pub enum MinaTxError {
 
     /// Unknown transaction type during deserialization
     UnknownTransactionType(String),
+
+    /// Transaction fields hold values Mina does not accept
+    InvalidTransaction(String),
+
+    /// Signature is valid, but for a different network than the one it was checked against
+    NetworkMismatch {
+        expected: NetworkId,
+        signed_for: NetworkId,
+    },
+
+    /// Node rejected a broadcast mutation through the GraphQL `errors` array
+    BroadcastRejected(Vec<String>),
 }
 
 impl fmt::Display for MinaTxError {
@@ -58,6 +72,18 @@ impl fmt::Display for MinaTxError {
             MinaTxError::UnknownTransactionType(msg) => {
                 write!(f, "Unknown transaction type: {}", msg)
             }
+            MinaTxError::InvalidTransaction(msg) => write!(f, "Invalid transaction: {}", msg),
+            MinaTxError::NetworkMismatch {
+                expected,
+                signed_for,
+            } => write!(
+                f,
+                "Network mismatch: signature is valid for {:?}, not {:?}",
+                signed_for, expected
+            ),
+            MinaTxError::BroadcastRejected(messages) => {
+                write!(f, "Transaction rejected by node: {}", messages.join("; "))
+            }
         }
     }
 }
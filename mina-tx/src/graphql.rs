@@ -1,6 +1,11 @@
-use crate::transactions::legacy_tx::LegacyTransaction;
-use alloc::string::{String, ToString};
-use serde::Serialize;
+use crate::{errors::MinaTxError, transactions::legacy_tx::LegacyTransaction};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use serde::{Deserialize, Serialize};
 
 // ------------------------------- GraphQL Request Structs --------------------------------
 #[derive(Serialize)]
@@ -159,6 +164,217 @@ mutation SendDelegationSigned($input: SendDelegationInput!, $signature: Signatur
     }
 }
 
+// ------------------------------- Broadcast Response --------------------------------
+
+/// What the node reported about a broadcast transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BroadcastStatus {
+    /// Accepted into the transaction pool; no failure was reported.
+    Pending,
+    /// Accepted by the node, but with the given failure reasons attached.
+    Failed(Vec<String>),
+}
+
+/// Result of a `sendPayment`, `sendDelegation` or `sendZkapp` mutation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BroadcastResult {
+    pub tx_hash: String,
+    pub status: BroadcastStatus,
+}
+
+#[derive(Deserialize)]
+struct BroadcastResponse {
+    data: Option<BroadcastData>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastData {
+    send_payment: Option<SentPayment>,
+    send_delegation: Option<SentDelegation>,
+    send_zkapp: Option<SentZkapp>,
+}
+
+#[derive(Deserialize)]
+struct SentPayment {
+    payment: SentCommand,
+}
+
+#[derive(Deserialize)]
+struct SentDelegation {
+    delegation: SentCommand,
+}
+
+#[derive(Deserialize)]
+struct SentZkapp {
+    zkapp: SentCommand,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SentCommand {
+    hash: String,
+    #[serde(default)]
+    failure_reason: serde_json::Value,
+}
+
+/// Flatten a `failureReason`: a string for signed commands, a list of
+/// `{ index, failures }` entries for zkApp commands, or null when there is none.
+fn failure_reasons(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::String(reason) => vec![reason.clone()],
+        serde_json::Value::Array(entries) => entries
+            .iter()
+            .flat_map(
+                |entry| match entry.get("failures").and_then(|f| f.as_array()) {
+                    Some(failures) => failures
+                        .iter()
+                        .map(|failure| match failure.as_str() {
+                            Some(failure) => failure.to_string(),
+                            None => failure.to_string(),
+                        })
+                        .collect(),
+                    None => vec![entry.to_string()],
+                },
+            )
+            .collect(),
+        other => vec![other.to_string()],
+    }
+}
+
+/// Extract the transaction hash and status from the response to a broadcast mutation.
+///
+/// A non-empty GraphQL `errors` array is returned as [`MinaTxError::BroadcastRejected`].
+pub fn parse_broadcast_response(body: &str) -> Result<BroadcastResult, MinaTxError> {
+    let response: BroadcastResponse = serde_json::from_str(body)
+        .map_err(|e| MinaTxError::DeSerializationError(format!("broadcast response: {e}")))?;
+    if !response.errors.is_empty() {
+        return Err(MinaTxError::BroadcastRejected(
+            response.errors.into_iter().map(|e| e.message).collect(),
+        ));
+    }
+
+    let data = response.data.ok_or_else(|| {
+        MinaTxError::DeSerializationError("broadcast response has no data".to_string())
+    })?;
+    let command = data
+        .send_payment
+        .map(|sent| sent.payment)
+        .or_else(|| data.send_delegation.map(|sent| sent.delegation))
+        .or_else(|| data.send_zkapp.map(|sent| sent.zkapp))
+        .ok_or_else(|| {
+            MinaTxError::DeSerializationError(
+                "broadcast response has no sendPayment, sendDelegation or sendZkapp result"
+                    .to_string(),
+            )
+        })?;
+
+    let reasons = failure_reasons(&command.failure_reason);
+    let status = if reasons.is_empty() {
+        BroadcastStatus::Pending
+    } else {
+        BroadcastStatus::Failed(reasons)
+    };
+    Ok(BroadcastResult {
+        tx_hash: command.hash,
+        status,
+    })
+}
+
+// ------------------------------- Fee Suggestion --------------------------------
+
+/// Lowest fee, in nanomina, that Mina nodes accept for a user command.
+pub const MIN_FEE: u64 = 1_000_000;
+
+/// How aggressively [`suggest_fee`] bids against the fees already in the pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeTier {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl FeeTier {
+    /// Percentile of the pooled fees to match.
+    fn percentile(self) -> usize {
+        match self {
+            FeeTier::Low => 25,
+            FeeTier::Medium => 50,
+            FeeTier::High => 75,
+        }
+    }
+
+    /// Fee to use when the pool is empty.
+    pub fn default_fee(self) -> u64 {
+        match self {
+            FeeTier::Low => MIN_FEE,
+            FeeTier::Medium => 10 * MIN_FEE,
+            FeeTier::High => 100 * MIN_FEE,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PooledFeesResponse {
+    data: PooledFeesData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PooledFeesData {
+    pooled_user_commands: Vec<PooledFee>,
+}
+
+#[derive(Deserialize)]
+struct PooledFee {
+    fee: String,
+}
+
+/// Query the fees of the user commands waiting in a node's transaction pool.
+pub fn build_pooled_fees_query() -> GraphqlRequest<()> {
+    GraphqlRequest {
+        operation_name: Some("PooledFees".to_string()),
+        query: "query PooledFees { pooledUserCommands { fee } }".to_string(),
+        variables: None,
+    }
+}
+
+/// Extract the fees from the response to [`build_pooled_fees_query`].
+pub fn parse_pooled_fees(body: &str) -> Result<Vec<u64>, MinaTxError> {
+    let response: PooledFeesResponse = serde_json::from_str(body)
+        .map_err(|e| MinaTxError::DeSerializationError(format!("pooled fees: {e}")))?;
+    response
+        .data
+        .pooled_user_commands
+        .iter()
+        .map(|command| {
+            command.fee.parse().map_err(|_| {
+                MinaTxError::DeSerializationError(format!("invalid pooled fee {}", command.fee))
+            })
+        })
+        .collect()
+}
+
+/// Suggest a fee, in nanomina, from the fees currently in the pool, never below [`MIN_FEE`].
+pub fn suggest_fee(pooled_fees: &[u64], tier: FeeTier) -> u64 {
+    if pooled_fees.is_empty() {
+        return tier.default_fee();
+    }
+    let mut fees = pooled_fees.to_vec();
+    fees.sort_unstable();
+    let index = (fees.len() - 1) * tier.percentile() / 100;
+    fees[index].max(MIN_FEE)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -427,4 +643,67 @@ mod tests {
         assert!(!json.contains("variables"));
         assert!(json.contains("\"operationName\":\"TestOp\""));
     }
+
+    #[test]
+    fn test_parse_pooled_fees() {
+        let body = r#"{"data": {"pooledUserCommands": [{"fee": "2000000"}, {"fee": "30000000"}]}}"#;
+        assert_eq!(
+            parse_pooled_fees(body).unwrap(),
+            vec![2_000_000, 30_000_000]
+        );
+
+        let body = r#"{"data": {"pooledUserCommands": [{"fee": "lots"}]}}"#;
+        assert!(matches!(
+            parse_pooled_fees(body),
+            Err(MinaTxError::DeSerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_suggest_fee_by_tier() {
+        let fees = [40_000_000, 10_000_000, 20_000_000, 30_000_000, 50_000_000];
+        assert_eq!(suggest_fee(&fees, FeeTier::Low), 20_000_000);
+        assert_eq!(suggest_fee(&fees, FeeTier::Medium), 30_000_000);
+        assert_eq!(suggest_fee(&fees, FeeTier::High), 40_000_000);
+
+        assert_eq!(suggest_fee(&[], FeeTier::High), FeeTier::High.default_fee());
+        assert_eq!(suggest_fee(&[1], FeeTier::Medium), MIN_FEE);
+    }
+
+    #[test]
+    fn test_parse_broadcast_response() {
+        let body = r#"{"data": {"sendZkapp": {"zkapp": {
+            "id": "WyJ6a2FwcF9jb21tYW5kIl0=",
+            "hash": "5JuJTtgVUTuMwSiAzmoWGUmuBbXt1BCVWPagvdoX5B9vqrkvNFwQ",
+            "failureReason": null
+        }}}}"#;
+        assert_eq!(
+            parse_broadcast_response(body).unwrap(),
+            BroadcastResult {
+                tx_hash: "5JuJTtgVUTuMwSiAzmoWGUmuBbXt1BCVWPagvdoX5B9vqrkvNFwQ".to_string(),
+                status: BroadcastStatus::Pending,
+            }
+        );
+
+        let body = r#"{"data": {"sendZkapp": {"zkapp": {
+            "hash": "5Ju...",
+            "failureReason": [{"index": "1", "failures": ["Cancelled", "Overflow"]}]
+        }}}}"#;
+        assert_eq!(
+            parse_broadcast_response(body).unwrap().status,
+            BroadcastStatus::Failed(vec!["Cancelled".to_string(), "Overflow".to_string()])
+        );
+
+        let body =
+            r#"{"data": {"sendPayment": {"payment": {"hash": "5Jv...", "failureReason": null}}}}"#;
+        assert_eq!(parse_broadcast_response(body).unwrap().tx_hash, "5Jv...");
+
+        let body = r#"{"errors": [{"message": "Insufficient_fee", "path": ["sendPayment"]}], "data": null}"#;
+        assert_eq!(
+            parse_broadcast_response(body),
+            Err(MinaTxError::BroadcastRejected(vec![
+                "Insufficient_fee".to_string()
+            ]))
+        );
+    }
 }
@@ -1,14 +1,21 @@
 extern crate alloc;
 
+pub mod address;
 pub mod base58;
 #[cfg(feature = "frost-bluepallas-compat")]
 pub mod bluepallas_compat;
 pub mod errors;
 pub mod graphql;
 pub mod pallas_message;
+pub mod rosetta;
 pub mod signatures;
+#[cfg(test)]
+mod test_fixtures;
 pub mod transactions;
+#[cfg(feature = "verify-cache")]
+pub mod verify_cache;
 
+pub use address::{is_valid_address, validate_address};
 pub use signatures::{PubKeySer, Sig, TransactionSignature};
 pub use transactions::network_id::NetworkId;
 pub use transactions::{
@@ -20,6 +20,20 @@ use crate::{
 
 const PALLAS_MESSAGE_VERSION: u8 = 1;
 
+/// Upper bound on the number of field elements an encoded `ROInput` may declare.
+///
+/// Legacy payments and delegations carry exactly three field-element public keys
+/// (fee payer, source, receiver) and zkApp commands are signed over a single
+/// commitment field. [`PallasMessage::serialize`] refuses messages with more, and
+/// [`PallasMessage::deserialize`] rejects them before `ROInput` allocates.
+pub const MAX_ROINPUT_FIELDS: usize = 3;
+
+/// The field count an encoded `ROInput` starts with, as a u32 LE.
+fn declared_fields(roi_bytes: &[u8]) -> Option<usize> {
+    let count = roi_bytes.get(..4)?;
+    Some(u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize)
+}
+
 #[cfg(feature = "frost-bluepallas-compat")]
 type BluePallasSuite = frost_bluepallas::BluePallas<PallasMessage>;
 
@@ -44,6 +58,14 @@ impl PallasMessage {
         }
     }
 
+    /// Create a message that signs a single field element, such as a zkApp commitment.
+    ///
+    /// The field is appended to the `ROInput` as-is and hashed in kimchi mode, which is how
+    /// Mina signs zkApp commitments; the field is not re-encoded as bytes.
+    pub fn from_field(field: BaseField, network_id: NetworkId) -> Self {
+        Self::from_parts(ROInput::new().append_field(field), network_id, false)
+    }
+
     /// Build a fallback message from raw bytes when explicit message encoding is unavailable.
     pub fn from_raw_bytes_default(input: &[u8]) -> Self {
         Self {
@@ -54,8 +76,19 @@ impl PallasMessage {
     }
 
     /// Serialize this message to bytes for transport/signing.
+    ///
+    /// Fails if the input holds more than [`MAX_ROINPUT_FIELDS`] field elements, since
+    /// [`Self::deserialize`] would refuse the bytes and the challenge would then hash them
+    /// as a raw-bytes message instead.
     pub fn serialize(&self) -> Result<Vec<u8>, MinaTxError> {
         let roi_bytes = self.input.serialize();
+        if let Some(fields_len) = declared_fields(&roi_bytes) {
+            if fields_len > MAX_ROINPUT_FIELDS {
+                return Err(MinaTxError::SerializationError(format!(
+                    "ROInput holds {fields_len} fields, maximum is {MAX_ROINPUT_FIELDS}"
+                )));
+            }
+        }
         let mut out = Vec::with_capacity(7 + roi_bytes.len());
         out.push(PALLAS_MESSAGE_VERSION);
         match &self.network_id {
@@ -158,6 +191,16 @@ impl PallasMessage {
             ));
         }
 
+        // The encoded ROInput starts with its field count as a u32 LE. Bound it before
+        // handing the buffer to `ROInput::deserialize`, which trusts the declared count.
+        if let Some(fields_len) = declared_fields(&input[data_start..]) {
+            if fields_len > MAX_ROINPUT_FIELDS {
+                return Err(crate::errors::MinaTxError::DeSerializationError(format!(
+                    "ROInput declares {fields_len} fields, maximum is {MAX_ROINPUT_FIELDS}"
+                )));
+            }
+        }
+
         let roi = ROInput::deserialize(&input[data_start..]).map_err(|_| {
             crate::errors::MinaTxError::DeSerializationError("Failed to deserialize ROInput".into())
         })?;
@@ -190,6 +233,20 @@ impl Hashable for PallasMessage {
     }
 }
 
+/// Encode any Mina [`Hashable`] as a message for BluePallas signing, the same way
+/// transactions are encoded for signing.
+///
+/// The challenge is hashed under the signature domain of `network`, not
+/// `H::domain_string`, and `is_legacy` selects the legacy or kimchi hasher. The input may
+/// hold at most [`MAX_ROINPUT_FIELDS`] field elements.
+pub fn sign_hashable<H: Hashable<D = NetworkId>>(
+    h: &H,
+    network: NetworkId,
+    is_legacy: bool,
+) -> Result<Vec<u8>, MinaTxError> {
+    PallasMessage::from_parts(h.to_roinput(), network, is_legacy).serialize()
+}
+
 /// Convert FROST public key to Mina public key.
 #[cfg(feature = "frost-bluepallas-compat")]
 pub fn translate_pk(
@@ -487,6 +544,42 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_deserialize_rejects_excessive_roi_field_count() {
+        // Legacy payment-shaped input with the maximum number of fields round-trips.
+        let fields_ok = (0..MAX_ROINPUT_FIELDS as u64).fold(ROInput::new(), |roi, i| {
+            roi.append_field(BaseField::from(i))
+        });
+        let msg = PallasMessage::from_parts(fields_ok, NetworkId::Testnet, true);
+        assert_eq!(round_trip(&msg), msg);
+
+        // Craft an ROInput body claiming thousands of fields without providing them.
+        let mut roi_bytes = Vec::new();
+        roi_bytes.extend_from_slice(&10_000u32.to_le_bytes());
+        roi_bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut bytes = vec![PALLAS_MESSAGE_VERSION, 0x00, 0x01];
+        bytes.extend_from_slice(&(roi_bytes.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&roi_bytes);
+
+        assert!(matches!(
+            PallasMessage::deserialize(&bytes),
+            Err(crate::errors::MinaTxError::DeSerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_serialize_rejects_excessive_roi_field_count() {
+        let fields = (0..=MAX_ROINPUT_FIELDS as u64).fold(ROInput::new(), |roi, i| {
+            roi.append_field(BaseField::from(i))
+        });
+        let msg = PallasMessage::from_parts(fields, NetworkId::Testnet, true);
+        assert!(matches!(
+            msg.serialize(),
+            Err(crate::errors::MinaTxError::SerializationError(_))
+        ));
+    }
+
     // --- from_raw_bytes_default contract ---
 
     #[test]
@@ -496,4 +589,77 @@ mod tests {
         assert_eq!(msg.network_id(), NetworkId::Testnet);
         assert!(msg.is_legacy());
     }
+
+    #[cfg(feature = "frost-bluepallas-compat")]
+    #[derive(Clone)]
+    struct Vote {
+        proposal: u32,
+        voter: BaseField,
+        approve: bool,
+    }
+
+    #[cfg(feature = "frost-bluepallas-compat")]
+    impl Hashable for Vote {
+        type D = NetworkId;
+
+        fn to_roinput(&self) -> ROInput {
+            ROInput::new()
+                .append_field(self.voter)
+                .append_u32(self.proposal)
+                .append_bool(self.approve)
+        }
+
+        fn domain_string(network_id: NetworkId) -> Option<String> {
+            Some(network_id.into_domain_string())
+        }
+    }
+
+    #[cfg(feature = "frost-bluepallas-compat")]
+    #[test]
+    fn test_sign_hashable_signs_and_verifies() {
+        use mina_signer::Signer;
+        use rand_core::SeedableRng;
+
+        let mut rng = rand_chacha::ChaCha12Rng::from_seed([7; 32]);
+        let keypair = Keypair::rand(&mut rng).unwrap();
+        let vote = Vote {
+            proposal: 42,
+            voter: keypair.public.point().x,
+            approve: true,
+        };
+
+        let message = sign_hashable(&vote, NetworkId::Testnet, false).unwrap();
+        let signing_key = translate_minask(&keypair).unwrap();
+        let signature = signing_key.sign(&mut rng, &message);
+        let verifying_key = frost_core::VerifyingKey::<BluePallasSuite>::from(&signing_key);
+        assert!(verifying_key.verify(&message, &signature).is_ok());
+
+        // A Mina verifier hashing the custom type directly accepts the same signature
+        let mut ctx = mina_signer::create_kimchi::<Vote>(NetworkId::Testnet);
+        assert!(ctx.verify(&translate_sig(&signature).unwrap(), &keypair.public, &vote));
+    }
+
+    #[test]
+    fn test_sign_hashable_rejects_too_many_fields() {
+        struct Fields;
+
+        impl Hashable for Fields {
+            type D = NetworkId;
+
+            fn to_roinput(&self) -> ROInput {
+                (0..=MAX_ROINPUT_FIELDS).fold(ROInput::new(), |roi, i| {
+                    roi.append_field(BaseField::from(i as u64))
+                })
+            }
+
+            fn domain_string(network_id: NetworkId) -> Option<String> {
+                Some(network_id.into_domain_string())
+            }
+        }
+
+        assert!(matches!(
+            sign_hashable(&Fields, NetworkId::Mainnet, false),
+            Err(MinaTxError::SerializationError(_))
+        ));
+    }
 }
@@ -0,0 +1,198 @@
+//! Signed transactions in the format accepted by Mina's Rosetta implementation.
+//!
+//! `/construction/combine` returns, and `/construction/submit` accepts, a
+//! `signed_transaction` string holding the JSON built here. Rosetta only handles
+//! payments and delegations, so zkApp commands are rejected.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use mina_signer::CompressedPubKey;
+use serde::Serialize;
+
+use crate::{
+    errors::MinaTxError,
+    legacy_tx::{LegacyTransaction, LegacyTransactionKind},
+    signatures::TransactionSignature,
+    TransactionKind,
+};
+
+/// Base58check encoding of the default (MINA) token id.
+pub const DEFAULT_TOKEN_ID: &str = "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf";
+
+/// The `signed_transaction` of a Rosetta combine response. Exactly one of `payment` and
+/// `stake_delegation` is set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RosettaSignedTransaction {
+    /// The 64 bytes `rx || s`, each 32 bytes little-endian, hex-encoded without prefix.
+    pub signature: String,
+    pub payment: Option<RosettaPayment>,
+    pub stake_delegation: Option<RosettaStakeDelegation>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RosettaPayment {
+    pub to: String,
+    pub from: String,
+    pub fee: String,
+    pub token: String,
+    pub nonce: String,
+    pub memo: Option<String>,
+    pub amount: String,
+    pub valid_until: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RosettaStakeDelegation {
+    pub delegator: String,
+    pub new_delegate: String,
+    pub fee: String,
+    pub nonce: String,
+    pub memo: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+impl RosettaSignedTransaction {
+    /// Build the Rosetta form of a signed payment or delegation. The signer's public key
+    /// must be the transaction's fee payer, since Rosetta identifies it by `from` or
+    /// `delegator`.
+    pub fn from_transaction_signature(tx_sig: &TransactionSignature) -> Result<Self, MinaTxError> {
+        let TransactionKind::Legacy(tx) = tx_sig.payload.inner() else {
+            return Err(MinaTxError::InvalidTransaction(
+                "Rosetta does not support zkApp commands".to_string(),
+            ));
+        };
+        if tx_sig.publicKey.pubKey.into_compressed() != tx.fee_payer_pk {
+            return Err(MinaTxError::InvalidPublicKey(
+                "signer is not the fee payer of the transaction".to_string(),
+            ));
+        }
+
+        let signature = tx_sig
+            .signature
+            .to_hex()
+            .trim_start_matches("0x")
+            .to_string();
+        let memo = memo(tx)?;
+        let valid_until = (tx.valid_until != u32::MAX).then(|| tx.valid_until.to_string());
+
+        let (payment, stake_delegation) = match tx.kind()? {
+            LegacyTransactionKind::Payment => (
+                Some(RosettaPayment {
+                    to: address(&tx.receiver_pk),
+                    from: address(&tx.source_pk),
+                    fee: tx.fee.to_string(),
+                    token: DEFAULT_TOKEN_ID.to_string(),
+                    nonce: tx.nonce.to_string(),
+                    memo,
+                    amount: tx.amount.to_string(),
+                    valid_until,
+                }),
+                None,
+            ),
+            LegacyTransactionKind::Delegation => (
+                None,
+                Some(RosettaStakeDelegation {
+                    delegator: address(&tx.source_pk),
+                    new_delegate: address(&tx.receiver_pk),
+                    fee: tx.fee.to_string(),
+                    nonce: tx.nonce.to_string(),
+                    memo,
+                    valid_until,
+                }),
+            ),
+        };
+
+        Ok(Self {
+            signature,
+            payment,
+            stake_delegation,
+        })
+    }
+
+    /// The `signed_transaction` string to pass to `/construction/submit`.
+    pub fn to_json(&self) -> Result<String, MinaTxError> {
+        serde_json::to_string(self).map_err(|e| MinaTxError::SerializationError(e.to_string()))
+    }
+}
+
+fn address(pk: &CompressedPubKey) -> String {
+    pk.clone().into_address()
+}
+
+/// Rosetta carries memos as text and omits empty ones.
+fn memo(tx: &LegacyTransaction) -> Result<Option<String>, MinaTxError> {
+    let memo = tx
+        .get_memo_string()
+        .map_err(|e| MinaTxError::InvalidMemo(format!("Rosetta only accepts text memos: {e}")))?;
+    Ok((!memo.is_empty()).then_some(memo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetworkId, PubKeySer, Sig, TransactionEnvelope};
+    use ark_ff::BigInt;
+    use mina_signer::{PubKey, SecKey};
+    use rand_core::SeedableRng;
+
+    fn test_pubkey(seed: [u8; 32]) -> PubKey {
+        let mut rng = rand_chacha::ChaCha12Rng::from_seed(seed);
+        let sec_key = SecKey::rand(&mut rng);
+        PubKey::from_secret_key(sec_key).unwrap()
+    }
+
+    fn signed(tx: LegacyTransaction, signer: PubKey) -> TransactionSignature {
+        TransactionSignature {
+            publicKey: PubKeySer { pubKey: signer },
+            signature: Sig {
+                field: BigInt::from(1u64),
+                scalar: BigInt::from(2u64),
+            },
+            payload: TransactionEnvelope::new_legacy(NetworkId::Mainnet, tx),
+        }
+    }
+
+    #[test]
+    fn test_rosetta_payment_json() {
+        let from = test_pubkey([1; 32]);
+        let to = test_pubkey([2; 32]);
+        let tx =
+            LegacyTransaction::new_payment(from.clone(), to.clone(), 1_000_000_000, 10_000_000, 7)
+                .set_memo_str("rosetta")
+                .unwrap();
+        let rosetta =
+            RosettaSignedTransaction::from_transaction_signature(&signed(tx, from.clone()))
+                .unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&rosetta.to_json().unwrap()).unwrap();
+        let mut expected_signature = "01".to_string();
+        expected_signature.push_str(&"00".repeat(31));
+        expected_signature.push_str("02");
+        expected_signature.push_str(&"00".repeat(31));
+        assert_eq!(json["signature"], expected_signature);
+        assert!(json["stake_delegation"].is_null());
+
+        let payment = &json["payment"];
+        assert_eq!(payment["from"], from.into_address());
+        assert_eq!(payment["to"], to.into_address());
+        assert_eq!(payment["fee"], "10000000");
+        assert_eq!(payment["amount"], "1000000000");
+        assert_eq!(payment["nonce"], "7");
+        assert_eq!(payment["token"], DEFAULT_TOKEN_ID);
+        assert_eq!(payment["memo"], "rosetta");
+        assert!(payment["valid_until"].is_null());
+    }
+
+    #[test]
+    fn test_rosetta_rejects_signer_other_than_fee_payer() {
+        let from = test_pubkey([1; 32]);
+        let delegate = test_pubkey([2; 32]);
+        let tx = LegacyTransaction::new_delegation(from, delegate.clone(), 10_000_000, 0);
+        assert!(matches!(
+            RosettaSignedTransaction::from_transaction_signature(&signed(tx, delegate)),
+            Err(MinaTxError::InvalidPublicKey(_))
+        ));
+    }
+}
@@ -1,13 +1,16 @@
-use alloc::{string::String, vec::Vec};
-use ark_ff::BigInt;
-use mina_signer::pubkey::PubKey;
+use alloc::{format, string::String, vec::Vec};
+use ark_ff::{BigInt, PrimeField};
+use mina_signer::{pubkey::PubKey, BaseField, ScalarField};
 use serde::{
     ser::{SerializeStruct, Serializer},
     Deserialize, Deserializer, Serialize,
 };
 
 use crate::{
-    base58::{to_base58_check, SIGNATURE_VERSION_BYTE, SIGNATURE_VERSION_NUMBER},
+    base58::{
+        from_base58_check, to_base58_check, SIGNATURE_VERSION_BYTE, SIGNATURE_VERSION_NUMBER,
+    },
+    errors::MinaTxError,
     transactions::{TransactionEnvelope, TransactionKind},
     zkapp_tx::SignatureInjectionResult,
 };
@@ -36,10 +39,74 @@ impl Sig {
         bytes
     }
 
+    fn bytes_to_bigint(bytes: &[u8]) -> BigInt<4> {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().expect("chunks are 8 bytes"));
+        }
+        BigInt(limbs)
+    }
+
     pub fn to_base58(&self) -> String {
         let bytes = self.to_bytes();
         to_base58_check(&bytes, SIGNATURE_VERSION_BYTE)
     }
+
+    /// Encode as `0x` followed by the 64 bytes `rx || s`, each 32 bytes little-endian.
+    pub fn to_hex(&self) -> String {
+        let mut bytes = Self::bigint_to_bytes(&self.field).to_vec();
+        bytes.extend_from_slice(&Self::bigint_to_bytes(&self.scalar));
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    /// Decode the output of [`Sig::to_hex`]. The `0x` prefix is required, and both halves
+    /// must be canonical encodings of their field elements.
+    pub fn from_hex(s: &str) -> Result<Sig, MinaTxError> {
+        let digits = s.strip_prefix("0x").ok_or_else(|| {
+            MinaTxError::InvalidSignature("hex signature must start with 0x".into())
+        })?;
+        let bytes = hex::decode(digits)
+            .map_err(|e| MinaTxError::InvalidSignature(format!("invalid hex signature: {e}")))?;
+        if bytes.len() != 64 {
+            return Err(MinaTxError::InvalidSignature(format!(
+                "hex signature must be 64 bytes, got {}",
+                bytes.len()
+            )));
+        }
+        Self::from_field_bytes(&bytes)
+    }
+
+    /// Decode the output of [`Sig::to_base58`], such as the `authorization` of a zkApp
+    /// fee payer.
+    pub fn from_base58(s: &str) -> Result<Sig, MinaTxError> {
+        let bytes = from_base58_check(s, SIGNATURE_VERSION_BYTE).map_err(|e| {
+            MinaTxError::InvalidSignature(format!("invalid base58 signature: {e:?}"))
+        })?;
+        match bytes.split_first() {
+            Some((&SIGNATURE_VERSION_NUMBER, rest)) if rest.len() == 64 => {
+                Self::from_field_bytes(rest)
+            }
+            Some((&SIGNATURE_VERSION_NUMBER, rest)) => Err(MinaTxError::InvalidSignature(format!(
+                "base58 signature must hold 64 bytes, got {}",
+                rest.len()
+            ))),
+            _ => Err(MinaTxError::InvalidSignature(
+                "unknown base58 signature version".into(),
+            )),
+        }
+    }
+
+    /// Parse the 64 bytes `rx || s`, rejecting non-canonical field encodings.
+    fn from_field_bytes(bytes: &[u8]) -> Result<Sig, MinaTxError> {
+        let field = Self::bytes_to_bigint(&bytes[..32]);
+        let scalar = Self::bytes_to_bigint(&bytes[32..]);
+        if BaseField::from_bigint(field).is_none() || ScalarField::from_bigint(scalar).is_none() {
+            return Err(MinaTxError::InvalidSignature(
+                "signature is not a canonical field encoding".into(),
+            ));
+        }
+        Ok(Sig { field, scalar })
+    }
 }
 
 impl Serialize for Sig {
@@ -121,6 +188,13 @@ impl<'de> Deserialize<'de> for PubKeySer {
         let pub_key = PubKey::from_address(&helper.address)
             .map_err(|_| serde::de::Error::custom("Failed to parse 'address' as PubKey"))?;
 
+        // Reject non-canonical encodings that decode to a key with a different address
+        if pub_key.into_address() != helper.address {
+            return Err(serde::de::Error::custom(
+                "'address' is not the canonical encoding of its public key",
+            ));
+        }
+
         Ok(PubKeySer { pubKey: pub_key })
     }
 }
@@ -159,3 +233,97 @@ impl TransactionSignature {
         self.payload.to_graphql_query_json(self.signature.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base58::{from_base58_check, to_base58_check};
+    use ark_ff::{One, Zero};
+    use mina_signer::{BaseField, CompressedPubKey, Keypair};
+
+    /// Version byte of base58check-encoded Mina public keys.
+    const ADDRESS_VERSION_BYTE: u8 = 0xcb;
+
+    fn deserialize_address(address: &str) -> Result<PubKeySer, serde_json::Error> {
+        serde_json::from_value(serde_json::json!({ "address": address }))
+    }
+
+    #[test]
+    fn test_sig_from_hex_rejects_malformed_input() {
+        let encoded = Sig {
+            field: BigInt::from(1u64),
+            scalar: BigInt::from(2u64),
+        }
+        .to_hex();
+
+        assert!(Sig::from_hex(&encoded[2..]).is_err());
+        assert!(Sig::from_hex(&encoded[..encoded.len() - 2]).is_err());
+        assert!(Sig::from_hex(&format!("{encoded}00")).is_err());
+        assert!(Sig::from_hex(&encoded.replace('1', "g")).is_err());
+        assert!(Sig::from_hex(&format!("0x{}", "ff".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn test_pubkey_roundtrips_for_both_parities() {
+        let keypair =
+            Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+                .unwrap();
+        let compressed = keypair.public.into_compressed();
+        for is_odd in [false, true] {
+            let key = CompressedPubKey {
+                x: compressed.x,
+                is_odd,
+            };
+            let address = key.into_address();
+            let pub_key = deserialize_address(&address).unwrap();
+            assert_eq!(pub_key.pubKey.into_address(), address);
+            assert_eq!(pub_key.pubKey.into_compressed().is_odd, is_odd);
+        }
+    }
+
+    #[test]
+    fn test_pubkey_boundary_x_coordinates() {
+        // x = 0 is not on y^2 = x^3 + 5, since 5 is not a square in the base field:
+        // the address must be rejected whatever the parity byte says.
+        for is_odd in [false, true] {
+            let address = CompressedPubKey {
+                x: BaseField::zero(),
+                is_odd,
+            }
+            .into_address();
+            assert!(deserialize_address(&address).is_err());
+        }
+
+        // x = p - 1 gives y^2 = 4, so the points (p - 1, 2) and (p - 1, p - 2) exist,
+        // with an even and an odd y respectively: both addresses round-trip.
+        for is_odd in [false, true] {
+            let compressed = CompressedPubKey {
+                x: -BaseField::one(),
+                is_odd,
+            };
+            let address = compressed.into_address();
+            let pub_key = deserialize_address(&address).unwrap();
+            assert_eq!(pub_key.pubKey.into_address(), address);
+            assert_eq!(pub_key.pubKey.into_compressed(), compressed);
+            let expected_y = if is_odd {
+                -BaseField::from(2u64)
+            } else {
+                BaseField::from(2u64)
+            };
+            assert_eq!(pub_key.pubKey.point().y, expected_y);
+        }
+    }
+
+    #[test]
+    fn test_pubkey_rejects_non_canonical_parity_byte() {
+        let keypair =
+            Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+                .unwrap();
+        let address = keypair.public.into_address();
+        let mut payload = from_base58_check(&address, ADDRESS_VERSION_BYTE).unwrap();
+        *payload.last_mut().unwrap() = 2;
+        let tampered = to_base58_check(&payload, ADDRESS_VERSION_BYTE);
+
+        assert!(deserialize_address(&tampered).is_err());
+    }
+}
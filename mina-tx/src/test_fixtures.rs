@@ -0,0 +1,56 @@
+//! Keys and transactions shared by the unit tests.
+
+use mina_signer::Keypair;
+
+/// The keypair the unit tests sign with.
+pub(crate) fn keypair() -> Keypair {
+    Keypair::from_hex("35dcca7620128d240cc3319c83dc6402ad439038361ba853af538a4cea3ddabc").unwrap()
+}
+
+#[cfg(feature = "frost-bluepallas-compat")]
+pub(crate) use compat::*;
+
+#[cfg(feature = "frost-bluepallas-compat")]
+mod compat {
+    use frost_core::{Signature as FrSig, VerifyingKey};
+    use rand_core::SeedableRng;
+
+    use super::keypair;
+    use crate::{
+        legacy_tx::LegacyTransaction,
+        pallas_message::{translate_minask, PallasMessage},
+        NetworkId, TransactionEnvelope,
+    };
+
+    type BluePallasSuite = frost_bluepallas::BluePallas<PallasMessage>;
+
+    /// A Testnet payment of `amount` from [`keypair`] to itself, with a fee of 1_000 and
+    /// nonce 0.
+    pub(crate) fn payment(amount: u64) -> TransactionEnvelope {
+        let keypair = keypair();
+        TransactionEnvelope::new_legacy(
+            NetworkId::Testnet,
+            LegacyTransaction::new_payment(
+                keypair.public.clone(),
+                keypair.public,
+                amount,
+                1_000,
+                0,
+            ),
+        )
+    }
+
+    /// The FROST verifying key of [`keypair`].
+    pub(crate) fn verifying_key() -> VerifyingKey<BluePallasSuite> {
+        VerifyingKey::from(&translate_minask(&keypair()).unwrap())
+    }
+
+    /// Sign `envelope` with [`keypair`] as a single FROST key, from a fixed nonce seed.
+    pub(crate) fn sign(envelope: &TransactionEnvelope) -> FrSig<BluePallasSuite> {
+        let mut rng = rand_chacha::ChaCha12Rng::from_seed([3; 32]);
+        let message = envelope.to_pallas_message().serialize().unwrap();
+        translate_minask(&keypair())
+            .unwrap()
+            .sign(&mut rng, &message)
+    }
+}
@@ -12,10 +12,11 @@ use crate::{
     },
 };
 use alloc::{
+    format,
     string::{String, ToString},
     vec::Vec,
 };
-use mina_hasher::Hashable;
+use mina_hasher::{Fp, Hashable};
 use serde::{Deserialize, Serialize};
 
 use crate::transactions::network_id::NetworkId;
@@ -27,6 +28,25 @@ pub mod zkapp_tx;
 pub const MEMO_BYTES: usize = 34;
 const MEMO_HEADER_BYTES: usize = 2; // 0x01 + length byte
 
+/// Check that a raw memo starts with the `0x01` header and that its length byte fits
+/// in the payload, so that it serializes back to the same memo.
+pub(crate) fn validate_memo(memo: &[u8; MEMO_BYTES]) -> Result<(), MinaTxError> {
+    if memo[0] != 0x01 {
+        return Err(MinaTxError::invalid_memo(format!(
+            "invalid memo header: expected 0x01, got {:#04x}",
+            memo[0]
+        )));
+    }
+    if memo[1] as usize > MEMO_BYTES - MEMO_HEADER_BYTES {
+        return Err(MinaTxError::invalid_memo(format!(
+            "memo length byte {} exceeds {} bytes",
+            memo[1],
+            MEMO_BYTES - MEMO_HEADER_BYTES
+        )));
+    }
+    Ok(())
+}
+
 // Enum distinguishing between legacy and zkApp transactions
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "tag", content = "transaction")]
@@ -146,6 +166,15 @@ impl TransactionEnvelope {
         self.kind.is_legacy()
     }
 
+    /// The field elements Mina signing absorbs for this transaction on its network.
+    ///
+    /// This is the `ROInput` packed into `Fp` elements, i.e. what [`crate::pallas_message::message_hash`]
+    /// hashes before appending the public key and nonce commitment. Hardware signers that
+    /// implement Mina signing natively can be handed these fields directly.
+    pub fn to_signing_fields(&self) -> Vec<Fp> {
+        self.to_roinput().to_fields()
+    }
+
     pub fn to_graphql_query_json(&self, signature: Sig) -> Result<String, serde_json::Error> {
         match &self.kind {
             TransactionKind::ZkApp(zkapp) => {
@@ -192,20 +221,16 @@ impl Hashable for TransactionEnvelope {
 
 #[cfg(test)]
 mod tests {
-    use mina_signer::Keypair;
-
     use crate::{
-        errors::MinaTxError, transactions::zkapp_tx::test_vectors::get_zkapp_test_vectors,
+        pallas_message::PallasMessage, test_fixtures::keypair,
+        transactions::zkapp_tx::test_vectors::get_zkapp_test_vectors,
     };
 
     use super::*;
 
     #[test]
     fn test_transaction_envelope_serialization_roundtrip() {
-        let private_key_hex = "35dcca7620128d240cc3319c83dc6402ad439038361ba853af538a4cea3ddabc";
-        let mina_keypair = Keypair::from_hex(private_key_hex)
-            .map_err(|_| MinaTxError::InvalidSignature("Failed to parse keypair".into()))
-            .unwrap();
+        let mina_keypair = keypair();
 
         let legacy_tx = LegacyTransaction::new_payment(
             mina_keypair.public.clone(),
@@ -224,6 +249,37 @@ mod tests {
         assert_eq!(deserialized, envelope);
     }
 
+    #[test]
+    fn test_max_amount_and_fee_survive_serialization_and_message_encoding() {
+        let mina_keypair = keypair();
+        let legacy_tx = LegacyTransaction::new_payment(
+            mina_keypair.public.clone(),
+            mina_keypair.public.clone(),
+            u64::MAX,
+            u64::MAX,
+            u32::MAX,
+        );
+        let envelope = TransactionEnvelope::new_legacy(NetworkId::Testnet, legacy_tx);
+
+        let deserialized =
+            TransactionEnvelope::deserialize(&envelope.serialize().unwrap()).unwrap();
+        let TransactionKind::Legacy(tx) = deserialized.inner() else {
+            panic!("expected a legacy transaction");
+        };
+        assert_eq!(tx.amount, u64::MAX);
+        assert_eq!(tx.fee, u64::MAX);
+        assert_eq!(tx.nonce, u32::MAX);
+
+        // The bytes handed to FROST decode back to the same signing input
+        let message =
+            PallasMessage::from_parts(deserialized.to_roinput(), NetworkId::Testnet, true);
+        let decoded = PallasMessage::deserialize(&message.serialize().unwrap()).unwrap();
+        assert_eq!(
+            decoded.to_roinput().to_fields(),
+            envelope.to_signing_fields()
+        );
+    }
+
     #[test]
     fn test_transaction_envelope_zkapp_roundtrip() {
         // Iterate through each test vector in test_vectors
@@ -247,6 +303,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_signing_fields_of_legacy_payment() {
+        let mina_keypair = keypair();
+        let pk = mina_keypair.public.into_compressed();
+        let legacy_tx = LegacyTransaction::new_payment(
+            mina_keypair.public.clone(),
+            mina_keypair.public,
+            1000,
+            1,
+            0,
+        );
+        let envelope = TransactionEnvelope::new_legacy(NetworkId::Mainnet, legacy_tx);
+
+        // Mina's legacy payment layout: fee payer, source and receiver keys as fields, then
+        // the fee, fee token, fee payer parity, nonce, valid until, memo, payment tag, source
+        // and receiver parities, token id, amount and token lock as bits.
+        let mut memo = [0u8; MEMO_BYTES];
+        memo[0] = 1;
+        let expected = mina_hasher::ROInput::new()
+            .append_field(pk.x)
+            .append_field(pk.x)
+            .append_field(pk.x)
+            .append_u64(1)
+            .append_u64(1)
+            .append_bool(pk.is_odd)
+            .append_u32(0)
+            .append_u32(u32::MAX)
+            .append_bytes(&memo)
+            .append_bool(false)
+            .append_bool(false)
+            .append_bool(false)
+            .append_bool(pk.is_odd)
+            .append_bool(pk.is_odd)
+            .append_u64(1)
+            .append_u64(1000)
+            .append_bool(false)
+            .to_fields();
+
+        let fields = envelope.to_signing_fields();
+        assert_eq!(&fields[..3], &[pk.x, pk.x, pk.x]);
+        assert_eq!(fields, expected);
+    }
+
     #[test]
     fn test_from_str_network_legacy_payment() {
         let json = r#"{
@@ -1,7 +1,10 @@
 //! This module defines the legacy transaction structure and related functionality (mainly serde and hashing/commitment).
 
-use alloc::string::{String, ToString};
-use core::fmt;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{self, Write};
 use mina_hasher::{Hashable, ROInput};
 use mina_signer::{CompressedPubKey, PubKey};
 
@@ -23,6 +26,77 @@ const TAG_BITS: usize = 3;
 const PAYMENT_TX_TAG: [bool; TAG_BITS] = [false, false, false];
 const DELEGATION_TX_TAG: [bool; TAG_BITS] = [false, false, true];
 
+/// Type of a legacy transaction, as encoded by its tag bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegacyTransactionKind {
+    Payment,
+    Delegation,
+}
+
+impl LegacyTransactionKind {
+    /// Tag bits hashed into the transaction for this kind.
+    pub fn tag(self) -> [bool; TAG_BITS] {
+        match self {
+            LegacyTransactionKind::Payment => PAYMENT_TX_TAG,
+            LegacyTransactionKind::Delegation => DELEGATION_TX_TAG,
+        }
+    }
+}
+
+impl TryFrom<[bool; TAG_BITS]> for LegacyTransactionKind {
+    type Error = MinaTxError;
+
+    fn try_from(tag: [bool; TAG_BITS]) -> Result<Self, Self::Error> {
+        match tag {
+            PAYMENT_TX_TAG => Ok(LegacyTransactionKind::Payment),
+            DELEGATION_TX_TAG => Ok(LegacyTransactionKind::Delegation),
+            _ => Err(MinaTxError::InvalidTransaction(format!(
+                "unknown tag {tag:?}"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for LegacyTransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegacyTransactionKind::Payment => write!(f, "payment"),
+            LegacyTransactionKind::Delegation => write!(f, "delegation"),
+        }
+    }
+}
+
+/// Fee token of native MINA, the only fee token Mina currently accepts.
+pub const FEE_TOKEN_DEFAULT: u64 = 1;
+
+/// How [`LegacyTransaction::validate_with`] treats values that are well-formed but
+/// probably a mistake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Validation {
+    /// Report them as warnings.
+    #[default]
+    Lenient,
+    /// Reject them like invalid values.
+    Strict,
+}
+
+/// Suspicious values found by [`LegacyTransaction::validate_with`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LegacyTransactionWarning {
+    /// A payment that moves no funds and only pays the fee. Nodes may refuse it.
+    ZeroAmountPayment,
+}
+
+impl fmt::Display for LegacyTransactionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LegacyTransactionWarning::ZeroAmountPayment => {
+                write!(f, "payment amount is 0, only the fee would be paid")
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LegacyTransaction {
     // Common
@@ -50,15 +124,21 @@ impl Serialize for LegacyTransaction {
         state.serialize_field("to", &self.receiver_pk.into_address())?;
         state.serialize_field("from", &self.source_pk.into_address())?;
         state.serialize_field("fee", &self.fee.to_string())?;
-        match self.tag {
-            DELEGATION_TX_TAG => {} // Noop
-            _ => state.serialize_field("amount", &self.amount.to_string())?,
+        match self.kind().map_err(serde::ser::Error::custom)? {
+            LegacyTransactionKind::Delegation => {} // Noop
+            LegacyTransactionKind::Payment => {
+                state.serialize_field("amount", &self.amount.to_string())?
+            }
         }
         state.serialize_field("nonce", &self.nonce.to_string())?;
 
-        // Serialize memo as a string, dropping the header bytes
-        let memo_str = Self::get_memo_string(self).map_err(serde::ser::Error::custom)?;
-        state.serialize_field("memo", &memo_str)?;
+        // Serialize memo as a string, dropping the header bytes. Binary memos that are not
+        // valid UTF-8 are hex-encoded under `memo_hex` instead.
+        let memo = self.memo_payload().map_err(serde::ser::Error::custom)?;
+        match core::str::from_utf8(memo) {
+            Ok(memo_str) => state.serialize_field("memo", memo_str)?,
+            Err(_) => state.serialize_field("memo_hex", &hex::encode(memo))?,
+        }
 
         state.serialize_field("valid_until", &self.valid_until.to_string())?;
         state.serialize_field("tag", &self.tag)?;
@@ -75,12 +155,15 @@ impl<'de> Deserialize<'de> for LegacyTransaction {
         struct TransactionData {
             to: String,
             from: String,
-            fee: String,
+            fee: NumericField,
+            #[serde(default)]
+            amount: Option<NumericField>,
+            nonce: NumericField,
             #[serde(default)]
-            amount: Option<String>,
-            nonce: String,
             memo: String,
-            valid_until: String,
+            #[serde(default)]
+            memo_hex: Option<String>,
+            valid_until: NumericField,
             tag: [bool; TAG_BITS],
         }
 
@@ -88,41 +171,90 @@ impl<'de> Deserialize<'de> for LegacyTransaction {
 
         let from = PubKey::from_address(&data.from).map_err(serde::de::Error::custom)?;
         let to = PubKey::from_address(&data.to).map_err(serde::de::Error::custom)?;
-        let fee = data.fee.parse().map_err(serde::de::Error::custom)?;
-        let nonce = data.nonce.parse().map_err(serde::de::Error::custom)?;
-        let valid_until = data.valid_until.parse().map_err(serde::de::Error::custom)?;
+        let fee = data.fee.0;
+        let nonce = u32::try_from(data.nonce.0).map_err(serde::de::Error::custom)?;
+        let valid_until = u32::try_from(data.valid_until.0).map_err(serde::de::Error::custom)?;
 
         // Match transaction tag to determine whether we have a payment or delegation transaction
-        let tx = match data.tag {
-            PAYMENT_TX_TAG => {
+        let kind = LegacyTransactionKind::try_from(data.tag).map_err(serde::de::Error::custom)?;
+        let tx = match kind {
+            LegacyTransactionKind::Payment => {
                 // Expect data.amount to exist
                 let ser_amount = data.amount.ok_or(serde::de::Error::custom(
                     "Missing amount for payment transaction",
                 ))?;
-                let amount = ser_amount.parse().map_err(serde::de::Error::custom)?;
+                let amount = ser_amount.0;
                 LegacyTransaction::new_payment(from, to, amount, fee, nonce)
-                    .set_memo_str(&data.memo)
-                    .map_err(serde::de::Error::custom)?
-                    .set_valid_until(valid_until)
             }
-            DELEGATION_TX_TAG => {
+            LegacyTransactionKind::Delegation => {
                 if data.amount.is_some() {
                     return Err(serde::de::Error::custom(
                         "Unexpected amount for delegation transaction",
                     ));
                 }
                 LegacyTransaction::new_delegation(from, to, fee, nonce)
-                    .set_memo_str(&data.memo)
-                    .map_err(serde::de::Error::custom)?
-                    .set_valid_until(valid_until)
             }
-            _ => return Err(serde::de::Error::custom("Invalid transaction tag")),
         };
+        let tx = match data.memo_hex {
+            Some(_) if !data.memo.is_empty() => {
+                return Err(serde::de::Error::custom(
+                    "memo and memo_hex cannot both be set",
+                ));
+            }
+            Some(memo_hex) => {
+                let memo = hex::decode(memo_hex).map_err(serde::de::Error::custom)?;
+                tx.set_memo_bytes(&memo)
+            }
+            None => tx.set_memo_str(&data.memo),
+        }
+        .map_err(serde::de::Error::custom)?
+        .set_valid_until(valid_until);
 
         Ok(tx)
     }
 }
 
+/// An unsigned integer field that tooling emits either as a decimal string, as Mina does,
+/// or as a JSON number. Negative and floating-point numbers are rejected.
+struct NumericField(u64);
+
+impl<'de> Deserialize<'de> for NumericField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct NumericVisitor;
+
+        impl serde::de::Visitor<'_> for NumericVisitor {
+            type Value = NumericField;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an unsigned integer as a string or a number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map(NumericField).map_err(E::custom)
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(NumericField(v))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                u64::try_from(v)
+                    .map(NumericField)
+                    .map_err(|_| E::custom(format!("expected an unsigned integer, found {v}")))
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                Err(E::custom(format!("expected an integer, found {v}")))
+            }
+        }
+
+        deserializer.deserialize_any(NumericVisitor)
+    }
+}
+
 impl Hashable for LegacyTransaction {
     type D = NetworkId;
 
@@ -156,20 +288,16 @@ impl Hashable for LegacyTransaction {
 
 impl fmt::Display for LegacyTransaction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let memo_str = match self.memo.len() {
-            len if len < MEMO_HEADER_BYTES => String::new(),
-            _ => self.memo[MEMO_HEADER_BYTES..]
-                .iter()
-                .take_while(|&&b| b != 0)
-                .map(|&b| b as char)
-                .collect::<String>(),
+        let memo_str = match (self.decoded_memo(), self.memo_payload()) {
+            (Some(memo_str), _) => memo_str,
+            (None, Ok(memo)) => format!("0x{}", hex::encode(memo)),
+            (None, Err(_)) => String::new(),
         };
 
-        let tx_type = match self.tag {
-            PAYMENT_TX_TAG => "payment",
-            DELEGATION_TX_TAG => "delegation",
-            _ => "unknown",
-        };
+        let tx_type = self
+            .kind()
+            .map(|kind| kind.to_string())
+            .unwrap_or_else(|_| "unknown".into());
 
         write!(
             f,
@@ -195,6 +323,22 @@ impl fmt::Display for LegacyTransaction {
 
 impl LegacyTransaction {
     pub fn get_memo_string(&self) -> Result<String, MinaTxError> {
+        String::from_utf8(self.memo_payload()?.to_vec())
+            .map_err(|e| MinaTxError::MemoSerializationError(e.to_string()))
+    }
+
+    /// The memo as text, or `None` if it is not valid UTF-8 or its length byte is out of
+    /// range. Unlike [`Self::get_memo_string`] this never fails, so callers handling
+    /// structured memos can tell text from binary payloads without matching on errors.
+    /// An unset memo decodes to the empty string.
+    pub fn decoded_memo(&self) -> Option<String> {
+        let memo = self.memo_payload().ok()?;
+        core::str::from_utf8(memo).ok().map(ToString::to_string)
+    }
+
+    /// The memo contents without the header bytes, as set by [`Self::set_memo_str`] or
+    /// [`Self::set_memo_bytes`].
+    pub fn memo_payload(&self) -> Result<&[u8], MinaTxError> {
         // Drops header bytes and uses length byte to extract memo
         let memo_len = self.memo[1] as usize;
 
@@ -205,19 +349,18 @@ impl LegacyTransaction {
             ));
         }
 
-        String::from_utf8(self.memo[MEMO_HEADER_BYTES..MEMO_HEADER_BYTES + memo_len].to_vec())
-            .map_err(|e| MinaTxError::MemoSerializationError(e.to_string()))
+        Ok(&self.memo[MEMO_HEADER_BYTES..MEMO_HEADER_BYTES + memo_len])
     }
 
     pub fn new_payment(from: PubKey, to: PubKey, amount: u64, fee: u64, nonce: u32) -> Self {
         LegacyTransaction {
             fee,
-            fee_token: 1,
+            fee_token: FEE_TOKEN_DEFAULT,
             fee_payer_pk: from.into_compressed(),
             nonce,
             valid_until: u32::MAX,
             memo: core::array::from_fn(|i| (i == 0) as u8),
-            tag: PAYMENT_TX_TAG,
+            tag: LegacyTransactionKind::Payment.tag(),
             source_pk: from.into_compressed(),
             receiver_pk: to.into_compressed(),
             token_id: 1,
@@ -229,12 +372,12 @@ impl LegacyTransaction {
     pub fn new_delegation(from: PubKey, to: PubKey, fee: u64, nonce: u32) -> Self {
         LegacyTransaction {
             fee,
-            fee_token: 1,
+            fee_token: FEE_TOKEN_DEFAULT,
             fee_payer_pk: from.into_compressed(),
             nonce,
             valid_until: u32::MAX,
             memo: core::array::from_fn(|i| (i == 0) as u8),
-            tag: DELEGATION_TX_TAG,
+            tag: LegacyTransactionKind::Delegation.tag(),
             source_pk: from.into_compressed(),
             receiver_pk: to.into_compressed(),
             token_id: 1,
@@ -249,6 +392,16 @@ impl LegacyTransaction {
         self
     }
 
+    /// The same transaction with the next nonce, for signing several transactions from one
+    /// account in sequence. Fails at `u32::MAX`, where Mina has no next nonce.
+    pub fn with_next_nonce(mut self) -> Result<Self, MinaTxError> {
+        self.nonce = self.nonce.checked_add(1).ok_or_else(|| {
+            MinaTxError::InvalidTransaction(format!("nonce {} has no successor", self.nonce))
+        })?;
+
+        Ok(self)
+    }
+
     pub fn set_memo(mut self, memo: [u8; MEMO_BYTES - 2]) -> Self {
         self.memo[0] = 0x01;
         self.memo[1] = (MEMO_BYTES - 2) as u8;
@@ -271,15 +424,145 @@ impl LegacyTransaction {
         Ok(self)
     }
 
+    /// Set a binary memo, e.g. a hash, of at most 32 bytes.
+    ///
+    /// Unlike [`Self::set_memo_str`] the bytes are not interpreted as UTF-8. A memo that is
+    /// not valid UTF-8 is serialized to JSON as hex under `memo_hex`. Mina's GraphQL
+    /// `sendPayment` only accepts text memos, so such a transaction has to be broadcast by
+    /// other means.
+    pub fn set_memo_bytes(mut self, memo: &[u8]) -> Result<Self, MinaTxError> {
+        if memo.len() > MEMO_BYTES - MEMO_HEADER_BYTES {
+            return Err(MinaTxError::invalid_memo("Memo exceeds maximum length"));
+        }
+
+        self.memo = [0u8; MEMO_BYTES];
+        self.memo[0] = 0x01;
+        self.memo[1] = memo.len() as u8;
+        self.memo[MEMO_HEADER_BYTES..MEMO_HEADER_BYTES + memo.len()].copy_from_slice(memo);
+
+        Ok(self)
+    }
+
+    /// Lay out the [`ROInput`] this transaction hashes to, in the order `to_roinput` appends
+    /// it: the three field elements by index, then every bit-level value with its bit range
+    /// and value. A debug aid for tracking down encoding mismatches with Mina.
+    pub fn debug_roinput(&self) -> String {
+        let bit = |b: bool| if b { '1' } else { '0' };
+        let fields = [
+            ("fee_payer_pk.x", self.fee_payer_pk.x),
+            ("source_pk.x", self.source_pk.x),
+            ("receiver_pk.x", self.receiver_pk.x),
+        ];
+        let bits = [
+            ("fee", 64, self.fee.to_string()),
+            ("fee_token", 64, self.fee_token.to_string()),
+            (
+                "fee_payer_pk.is_odd",
+                1,
+                bit(self.fee_payer_pk.is_odd).to_string(),
+            ),
+            ("nonce", 32, self.nonce.to_string()),
+            ("valid_until", 32, self.valid_until.to_string()),
+            ("memo", MEMO_BYTES * 8, hex::encode(self.memo)),
+            (
+                "tag",
+                TAG_BITS,
+                self.tag.iter().copied().map(bit).collect::<String>(),
+            ),
+            (
+                "source_pk.is_odd",
+                1,
+                bit(self.source_pk.is_odd).to_string(),
+            ),
+            (
+                "receiver_pk.is_odd",
+                1,
+                bit(self.receiver_pk.is_odd).to_string(),
+            ),
+            ("token_id", 64, self.token_id.to_string()),
+            ("amount", 64, self.amount.to_string()),
+            ("token_locked", 1, bit(self.token_locked).to_string()),
+        ];
+
+        // Writing to a String cannot fail
+        let mut out = String::new();
+        for (index, (label, value)) in fields.iter().enumerate() {
+            let _ = writeln!(out, "field {index} {label}: {value}");
+        }
+        let mut offset = 0;
+        for (label, width, value) in bits {
+            let _ = writeln!(out, "bits {offset}..{} {label}: {value}", offset + width);
+            offset += width;
+        }
+        let _ = write!(out, "total: {} fields, {offset} bits", fields.len());
+        out
+    }
+
+    /// Compare two transactions, treating memos as equal when their header and decoded
+    /// contents match, whatever their padding bytes. Use this for dedup and caching;
+    /// `==` still compares the raw memo bytes. The padding is part of the hashed input,
+    /// so transactions equal here can still have different signatures.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        let same_memo = match (self.memo_payload(), other.memo_payload()) {
+            (Ok(ours), Ok(theirs)) => self.memo[0] == other.memo[0] && ours == theirs,
+            _ => self.memo == other.memo,
+        };
+        same_memo
+            && Self {
+                memo: other.memo,
+                ..self.clone()
+            } == *other
+    }
+
     pub fn is_delegation(&self) -> bool {
         self.tag == DELEGATION_TX_TAG
     }
+
+    /// Decode the transaction type from its tag bits.
+    pub fn kind(&self) -> Result<LegacyTransactionKind, MinaTxError> {
+        LegacyTransactionKind::try_from(self.tag)
+    }
+
+    /// Check for field values that Mina does not support yet.
+    ///
+    /// Suspicious values are accepted, see [`LegacyTransaction::validate_with`].
+    pub fn validate(&self) -> Result<(), MinaTxError> {
+        self.validate_with(Validation::Lenient).map(|_| ())
+    }
+
+    /// Like [`LegacyTransaction::validate`], also checking for values that are valid
+    /// but probably a mistake, such as a zero-amount payment. These are returned as
+    /// warnings, or rejected under [`Validation::Strict`].
+    pub fn validate_with(
+        &self,
+        validation: Validation,
+    ) -> Result<Vec<LegacyTransactionWarning>, MinaTxError> {
+        // Only native MINA can pay fees until multi-token fees are supported.
+        if self.fee_token != FEE_TOKEN_DEFAULT {
+            return Err(MinaTxError::InvalidTransaction(format!(
+                "Unsupported fee token {}, only {} (MINA) is accepted",
+                self.fee_token, FEE_TOKEN_DEFAULT
+            )));
+        }
+
+        let mut warnings = Vec::new();
+        if self.kind()? == LegacyTransactionKind::Payment && self.amount == 0 {
+            warnings.push(LegacyTransactionWarning::ZeroAmountPayment);
+        }
+        match (validation, warnings.first()) {
+            (Validation::Strict, Some(warning)) => {
+                Err(MinaTxError::InvalidTransaction(warning.to_string()))
+            }
+            _ => Ok(warnings),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mina_signer::{PubKey, SecKey};
+    use core::str::FromStr;
+    use mina_signer::{BaseField, PubKey, SecKey};
     use rand_core::SeedableRng;
 
     fn create_test_pubkey(seed: [u8; 32]) -> PubKey {
@@ -374,6 +657,35 @@ mod tests {
         assert_eq!(original, reconstructed);
     }
 
+    #[test]
+    fn test_fee_payer_parity_roundtrips() {
+        // Find one fee payer of each parity, since the address encodes x and the parity
+        // separately
+        let mut by_parity = [None, None];
+        for seed in 0u8.. {
+            let pubkey = create_test_pubkey([seed; 32]);
+            let slot = &mut by_parity[usize::from(pubkey.into_compressed().is_odd)];
+            slot.get_or_insert(pubkey);
+            if by_parity.iter().all(Option::is_some) {
+                break;
+            }
+        }
+
+        for from in by_parity.into_iter().flatten() {
+            let to = create_test_pubkey([9; 32]);
+            let original = LegacyTransaction::new_payment(from.clone(), to, 1, 1, 0);
+            let bytes = serde_json::to_vec(&original).unwrap();
+            let reconstructed = serde_json::from_slice::<LegacyTransaction>(&bytes).unwrap();
+
+            assert_eq!(reconstructed.fee_payer_pk, from.into_compressed());
+            assert_eq!(reconstructed.source_pk, from.into_compressed());
+            assert_eq!(
+                reconstructed.to_roinput().to_fields(),
+                original.to_roinput().to_fields()
+            );
+        }
+    }
+
     #[test]
     fn test_bytes_roundtrip_delegation() {
         let from = create_test_pubkey([7; 32]);
@@ -477,6 +789,59 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn payment_json(fee: &str, amount: &str, nonce: &str, valid_until: &str) -> String {
+        format!(
+            r#"{{
+            "to": "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+            "from": "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg",
+            "fee": {fee},
+            "amount": {amount},
+            "nonce": {nonce},
+            "memo": "test",
+            "valid_until": {valid_until},
+            "tag": [false, false, false]
+        }}"#
+        )
+    }
+
+    #[test]
+    fn test_deserialize_numeric_fields_as_numbers_or_strings() {
+        let from_strings: LegacyTransaction = serde_json::from_str(&payment_json(
+            r#""10000""#,
+            r#""1000000""#,
+            r#""42""#,
+            r#""12345""#,
+        ))
+        .unwrap();
+        let from_numbers: LegacyTransaction =
+            serde_json::from_str(&payment_json("10000", "1000000", "42", "12345")).unwrap();
+
+        assert_eq!(from_numbers, from_strings);
+        assert_eq!(from_numbers.fee, 10000);
+        assert_eq!(from_numbers.amount, 1000000);
+        assert_eq!(from_numbers.nonce, 42);
+        assert_eq!(from_numbers.valid_until, 12345);
+
+        // Serialization still emits strings, as Mina expects
+        let value = serde_json::to_value(&from_numbers).unwrap();
+        assert_eq!(value["fee"], "10000");
+        assert_eq!(value["amount"], "1000000");
+        assert_eq!(value["nonce"], "42");
+    }
+
+    #[test]
+    fn test_deserialize_rejects_float_and_negative_numbers() {
+        for json in [
+            payment_json("10000.5", "1000000", "42", "12345"),
+            payment_json("1e4", "1000000", "42", "12345"),
+            payment_json("-10000", "1000000", "42", "12345"),
+            payment_json("10000", "1000000", "4294967296", "12345"),
+        ] {
+            let result: Result<LegacyTransaction, _> = serde_json::from_str(&json);
+            assert!(result.is_err(), "accepted {json}");
+        }
+    }
+
     #[test]
     fn test_deserialize_invalid_fee_negative() {
         let json = r#"{
@@ -656,4 +1021,293 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_validate_rejects_non_default_fee_token() {
+        let from = create_test_pubkey([23; 32]);
+        let to = create_test_pubkey([24; 32]);
+
+        let payment = LegacyTransaction::new_payment(from.clone(), to.clone(), 1_000, 1_000, 1);
+        assert_eq!(payment.fee_token, FEE_TOKEN_DEFAULT);
+        assert!(payment.validate().is_ok());
+        assert!(LegacyTransaction::new_delegation(from, to, 1_000, 1)
+            .validate()
+            .is_ok());
+
+        let mut custom_fee_token = payment;
+        custom_fee_token.fee_token = 2;
+        assert!(matches!(
+            custom_fee_token.validate(),
+            Err(MinaTxError::InvalidTransaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_zero_amount_payment() {
+        let from = create_test_pubkey([27; 32]);
+        let to = create_test_pubkey([28; 32]);
+        let payment = LegacyTransaction::new_payment(from.clone(), to.clone(), 0, 1_000, 1);
+
+        assert!(payment.validate().is_ok());
+        assert_eq!(
+            payment.validate_with(Validation::Lenient).unwrap(),
+            vec![LegacyTransactionWarning::ZeroAmountPayment]
+        );
+        assert!(matches!(
+            payment.validate_with(Validation::Strict),
+            Err(MinaTxError::InvalidTransaction(_))
+        ));
+
+        // Delegations carry no amount and are never flagged
+        let delegation = LegacyTransaction::new_delegation(from, to, 1_000, 1);
+        assert!(delegation
+            .validate_with(Validation::Strict)
+            .unwrap()
+            .is_empty());
+
+        // A zero amount is still written out explicitly, while delegations omit it
+        let value = serde_json::to_value(&payment).unwrap();
+        assert_eq!(value["amount"], "0");
+        let roundtrip: LegacyTransaction = serde_json::from_value(value).unwrap();
+        assert_eq!(roundtrip.amount, 0);
+        assert_eq!(roundtrip.kind().unwrap(), LegacyTransactionKind::Payment);
+        assert!(serde_json::to_value(&delegation)
+            .unwrap()
+            .get("amount")
+            .is_none());
+    }
+
+    #[test]
+    fn test_kind_maps_tags() {
+        let from = create_test_pubkey([25; 32]);
+        let to = create_test_pubkey([26; 32]);
+
+        let payment = LegacyTransaction::new_payment(from.clone(), to.clone(), 1_000, 1_000, 1);
+        assert_eq!(payment.kind().unwrap(), LegacyTransactionKind::Payment);
+        let delegation = LegacyTransaction::new_delegation(from, to, 1_000, 1);
+        assert_eq!(
+            delegation.kind().unwrap(),
+            LegacyTransactionKind::Delegation
+        );
+
+        let mut unknown = payment;
+        unknown.tag = [true, false, false];
+        assert!(matches!(
+            unknown.kind(),
+            Err(MinaTxError::InvalidTransaction(_))
+        ));
+        assert!(serde_json::to_string(&unknown).is_err());
+        assert!(unknown.to_string().contains("\"type\": \"unknown\""));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_unknown_tag() {
+        let json = r#"{
+            "to": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+            "from": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+            "fee": "1000",
+            "amount": "1000",
+            "nonce": "1",
+            "memo": "",
+            "valid_until": "100",
+            "tag": [false, true, false]
+        }"#;
+        let err = serde_json::from_str::<LegacyTransaction>(json).unwrap_err();
+        assert!(err.to_string().contains("unknown tag"));
+    }
+
+    #[test]
+    fn test_binary_memo_roundtrips() {
+        let from = create_test_pubkey([23; 32]);
+        let to = create_test_pubkey([24; 32]);
+        let hash: [u8; 32] = core::array::from_fn(|i| 0xe0 ^ i as u8);
+        let tx = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 1)
+            .set_memo_bytes(&hash)
+            .unwrap();
+
+        assert_eq!(tx.memo[0], 0x01);
+        assert_eq!(tx.memo[1], 32);
+        assert_eq!(tx.memo_payload().unwrap(), &hash);
+        assert!(tx.get_memo_string().is_err());
+
+        let json = serde_json::to_string(&tx).unwrap();
+        assert!(json.contains(&format!("\"memo_hex\":\"{}\"", hex::encode(hash))));
+        assert!(!json.contains("\"memo\":"));
+
+        let deserialized: LegacyTransaction = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, tx);
+        assert!(tx.to_string().contains(&format!("0x{}", hex::encode(hash))));
+    }
+
+    #[test]
+    fn test_decoded_memo() {
+        let from = create_test_pubkey([27; 32]);
+        let to = create_test_pubkey([28; 32]);
+        let base = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 1);
+
+        assert_eq!(base.decoded_memo(), Some(String::new()));
+
+        let text = base.clone().set_memo_str(r#"{"ref":"inv-42"}"#).unwrap();
+        assert_eq!(text.decoded_memo().as_deref(), Some(r#"{"ref":"inv-42"}"#));
+
+        let binary = base.set_memo_bytes(&[0xff, 0xfe, 0x00, 0x80]).unwrap();
+        assert_eq!(binary.decoded_memo(), None);
+        assert!(binary.to_string().contains("0xfffe0080"));
+    }
+
+    /// Rebuild the [`ROInput`] described by a [`LegacyTransaction::debug_roinput`] layout,
+    /// checking that its bit ranges follow each other.
+    fn roinput_from_layout(layout: &str) -> ROInput {
+        let mut roi = ROInput::new();
+        let mut offset = 0;
+        for line in layout.lines() {
+            let (head, value) = line.split_once(": ").unwrap();
+            if head.starts_with("field ") {
+                roi = roi.append_field(BaseField::from_str(value).unwrap());
+                continue;
+            }
+            let Some(range) = head.strip_prefix("bits ") else {
+                continue;
+            };
+            let (start, end) = range.split_once(' ').unwrap().0.split_once("..").unwrap();
+            let (start, end): (usize, usize) = (start.parse().unwrap(), end.parse().unwrap());
+            assert_eq!(start, offset, "gap or overlap before {line}");
+            offset = end;
+            roi = match end - start {
+                1 => roi.append_bool(value == "1"),
+                32 => roi.append_u32(value.parse().unwrap()),
+                64 => roi.append_u64(value.parse().unwrap()),
+                width if width == MEMO_BYTES * 8 => roi.append_bytes(&hex::decode(value).unwrap()),
+                _ => value.chars().fold(roi, |roi, b| roi.append_bool(b == '1')),
+            };
+        }
+        roi
+    }
+
+    #[test]
+    fn test_debug_roinput_matches_to_roinput() {
+        let from = create_test_pubkey([33; 32]);
+        let to = create_test_pubkey([34; 32]);
+        let payment = LegacyTransaction::new_payment(from.clone(), to.clone(), 5_000, 1_000, 9)
+            .set_memo_str("layout")
+            .unwrap()
+            .set_valid_until(77);
+        let delegation = LegacyTransaction::new_delegation(from, to, 1_000, 4);
+
+        for tx in [payment, delegation] {
+            assert_eq!(roinput_from_layout(&tx.debug_roinput()), tx.to_roinput());
+        }
+    }
+
+    #[test]
+    fn test_debug_roinput_lists_fields_and_memo() {
+        let from = create_test_pubkey([31; 32]);
+        let to = create_test_pubkey([32; 32]);
+        let tx = LegacyTransaction::new_payment(from.clone(), to.clone(), 1_000_000, 1_000, 3)
+            .set_memo_str("debug")
+            .unwrap();
+        let layout = tx.debug_roinput();
+        let lines: Vec<&str> = layout.lines().collect();
+
+        let from = from.into_compressed();
+        let to = to.into_compressed();
+        assert_eq!(lines[0], format!("field 0 fee_payer_pk.x: {}", from.x));
+        assert_eq!(lines[1], format!("field 1 source_pk.x: {}", from.x));
+        assert_eq!(lines[2], format!("field 2 receiver_pk.x: {}", to.x));
+        assert_eq!(lines[3], "bits 0..64 fee: 1000");
+        assert!(lines.contains(&"bits 129..161 nonce: 3"));
+        assert!(lines.contains(&format!("bits 193..465 memo: {}", hex::encode(tx.memo)).as_str()));
+        assert!(lines.contains(&"bits 465..468 tag: 000"));
+        assert_eq!(*lines.last().unwrap(), "total: 3 fields, 599 bits");
+    }
+
+    #[test]
+    fn test_with_next_nonce() {
+        let from = create_test_pubkey([29; 32]);
+        let to = create_test_pubkey([30; 32]);
+        let base = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 7);
+
+        let next = base.clone().with_next_nonce().unwrap();
+        assert_eq!(next.nonce, 8);
+        assert_eq!(LegacyTransaction { nonce: 7, ..next }, base);
+
+        let last = LegacyTransaction {
+            nonce: u32::MAX - 1,
+            ..base
+        }
+        .with_next_nonce()
+        .unwrap();
+        assert_eq!(last.nonce, u32::MAX);
+        assert!(matches!(
+            last.with_next_nonce(),
+            Err(MinaTxError::InvalidTransaction(msg)) if msg.contains(&u32::MAX.to_string())
+        ));
+    }
+
+    #[test]
+    fn test_set_memo_bytes_rejects_too_long() {
+        let from = create_test_pubkey([25; 32]);
+        let to = create_test_pubkey([26; 32]);
+        let base = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 1);
+
+        assert!(matches!(
+            base.set_memo_bytes(&[0u8; 33]),
+            Err(MinaTxError::InvalidMemo(_))
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_memo_and_memo_hex() {
+        let json = r#"{
+            "to": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+            "from": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+            "fee": "1000",
+            "amount": "1000",
+            "nonce": "1",
+            "memo": "text",
+            "memo_hex": "ff",
+            "valid_until": "100",
+            "tag": [false, false, false]
+        }"#;
+        let err = serde_json::from_str::<LegacyTransaction>(json).unwrap_err();
+        assert!(err.to_string().contains("memo_hex"));
+    }
+
+    #[test]
+    fn test_semantically_eq_ignores_memo_padding() {
+        let from = create_test_pubkey([27; 32]);
+        let to = create_test_pubkey([28; 32]);
+        let tx = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 1)
+            .set_memo_str("hello")
+            .unwrap();
+        let mut padded = tx.clone();
+        padded.memo[MEMO_BYTES - 1] = 0xff;
+
+        assert_ne!(tx, padded);
+        assert!(tx.semantically_eq(&padded));
+        assert!(padded.semantically_eq(&tx));
+    }
+
+    #[test]
+    fn test_semantically_eq_compares_contents_and_other_fields() {
+        let from = create_test_pubkey([29; 32]);
+        let to = create_test_pubkey([30; 32]);
+        let tx = LegacyTransaction::new_payment(from, to, 1_000_000, 1_000, 1)
+            .set_memo_str("hello")
+            .unwrap();
+
+        // Same payload bytes but a shorter declared length
+        let mut truncated = tx.clone();
+        truncated.memo[1] = 4;
+        assert!(!tx.semantically_eq(&truncated));
+
+        let other_memo = tx.clone().set_memo_str("hellp").unwrap();
+        assert!(!tx.semantically_eq(&other_memo));
+
+        let other_nonce = LegacyTransaction {
+            nonce: 2,
+            ..tx.clone()
+        };
+        assert!(!tx.semantically_eq(&other_nonce));
+    }
 }
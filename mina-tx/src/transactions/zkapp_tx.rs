@@ -8,12 +8,17 @@ use mina_hasher::{Hashable, ROInput};
 use mina_signer::CompressedPubKey;
 use serde::{Deserialize, Serialize};
 
-use crate::transactions::network_id::NetworkId;
+use crate::{
+    errors::{MinaTxError, MinaTxResult},
+    pallas_message::PallasMessage,
+    signatures::Sig,
+    transactions::network_id::NetworkId,
+};
 
 use crate::transactions::{
     zkapp_tx::{
         commit::zk_commit,
-        constants::{APP_STATE_LENGTH, DUMMY_HASH},
+        constants::{APP_STATE_LENGTH, DUMMY_HASH, TXN_VERSION_CURRENT},
     },
     MEMO_BYTES,
 };
@@ -33,6 +38,7 @@ pub mod test_vectors;
 /// Compile-time flag indicating whether this build uses Mesa hardfork transaction semantics.
 pub const IS_MESA_HARDFORK: bool = cfg!(feature = "mesa-hardfork");
 
+pub use commit::{derive_token_id, validate_call_tree};
 // Re-export signature injection types for convenience
 pub use signature_injection::{SignatureInjectionResult, SignatureInjectionWarning};
 
@@ -68,6 +74,73 @@ impl<'a> Hashable for ZKAppCommandHashable<'a> {
     }
 }
 
+/// The message bytes a FROST group must sign to authorize `command` as its fee payer.
+///
+/// The fee payer always signs the full commitment, so for commands whose account updates
+/// are all proof-authorized this is the only signature needed. Place the resulting
+/// signature with [`ZKAppCommand::set_fee_payer_signature`].
+pub fn sign_zkapp_fee_payer(command: &ZKAppCommand, network: NetworkId) -> MinaTxResult<Vec<u8>> {
+    let (_, full_commitment) = zk_commit(command, &network)?;
+    Ok(PallasMessage::from_field(full_commitment, network).serialize()?)
+}
+
+/// Sign `template` for broadcast, filling in every signature authorization it requires.
+///
+/// `sign` is called with the key that must sign and the message bytes to sign: the full
+/// commitment for the fee payer and for account updates with `use_full_commitment`, the
+/// account updates commitment otherwise. Each key signs each message at most once, so a FROST
+/// group authorizing several parts of the command runs a single session per commitment.
+/// Proof-authorized account updates must already carry their proof.
+pub fn sign_zkapp_command<F>(
+    template: &ZKAppCommand,
+    network: NetworkId,
+    mut sign: F,
+) -> MinaTxResult<ZKAppCommand>
+where
+    F: FnMut(&PublicKey, &[u8]) -> MinaTxResult<Sig>,
+{
+    let (commitment, full_commitment) = zk_commit(template, &network)?;
+    let full_message = PallasMessage::from_field(full_commitment, network.clone()).serialize()?;
+    let partial_message = PallasMessage::from_field(commitment, network).serialize()?;
+
+    // Authorizations are not part of either commitment, so signatures can be reused freely
+    let mut signatures: Vec<(PublicKey, bool, String)> = Vec::new();
+    let mut signature_for = |public_key: &PublicKey, full: bool| -> MinaTxResult<String> {
+        if let Some((_, _, signature)) = signatures
+            .iter()
+            .find(|(key, is_full, _)| key == public_key && *is_full == full)
+        {
+            return Ok(signature.clone());
+        }
+        let message = if full {
+            &full_message
+        } else {
+            &partial_message
+        };
+        let signature = sign(public_key, message)?.to_base58();
+        signatures.push((public_key.clone(), full, signature.clone()));
+        Ok(signature)
+    };
+
+    let mut command = template.clone();
+    command.fee_payer.authorization = signature_for(&command.fee_payer.body.public_key, true)?;
+    for (index, update) in command.account_updates.iter_mut().enumerate() {
+        let kind = &update.body.authorization_kind;
+        if kind.is_signed {
+            update.authorization.signature = Some(signature_for(
+                &update.body.public_key,
+                update.body.use_full_commitment,
+            )?);
+        } else if kind.is_proved && update.authorization.proof.is_none() {
+            return Err(Box::new(MinaTxError::InvalidZkAppCommand(format!(
+                "account update {index} is authorized by a proof but carries none"
+            ))));
+        }
+    }
+
+    Ok(command)
+}
+
 // -------------------------------------------------------------------------------------------------
 // ----------------------------- ZKApp Transaction Structs -----------------------------------------
 // -------------------------------------------------------------------------------------------------
@@ -134,6 +207,24 @@ pub struct AccountUpdate {
     pub authorization: Authorization,
 }
 
+impl AccountUpdate {
+    /// Mina's `Account_update.dummy`: an update on the empty public key under the default token
+    /// that changes nothing, accepts any preconditions and carries no authorization (`None_given`).
+    pub fn dummy() -> Self {
+        let mut account_update = AccountUpdate::default();
+        account_update.body.authorization_kind = AuthorizationKind {
+            is_signed: false,
+            is_proved: false,
+            verification_key_hash: *DUMMY_HASH,
+        };
+        account_update.authorization = Authorization {
+            proof: None,
+            signature: None,
+        };
+        account_update
+    }
+}
+
 impl From<FeePayer> for AccountUpdate {
     fn from(fee_payer: FeePayer) -> Self {
         // Unpack fee payer pieces
@@ -247,6 +338,36 @@ pub struct SetVerificationKey {
     pub txn_version: UInt32,
 }
 
+impl Permissions {
+    /// Check the permission values a Mina node would reject.
+    ///
+    /// Deserialization keeps raw values untouched so existing commands round-trip; call this
+    /// before signing a command built or edited locally.
+    pub fn validate(&self) -> Result<(), MinaTxError> {
+        self.set_verification_key.validate()
+    }
+}
+
+impl SetVerificationKey {
+    /// Create a `set_verification_key` permission, rejecting transaction versions unknown to Mina.
+    pub fn new(auth: AuthRequired, txn_version: UInt32) -> Result<Self, MinaTxError> {
+        let permission = Self { auth, txn_version };
+        permission.validate()?;
+        Ok(permission)
+    }
+
+    /// Mina only knows transaction versions up to the one of the targeted hardfork.
+    pub fn validate(&self) -> Result<(), MinaTxError> {
+        if self.txn_version > TXN_VERSION_CURRENT {
+            return Err(MinaTxError::InvalidZkAppCommand(format!(
+                "set_verification_key txn_version {} exceeds current transaction version {}",
+                self.txn_version, TXN_VERSION_CURRENT
+            )));
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Preconditions {
@@ -292,6 +413,33 @@ pub struct Actions {
     pub data: Vec<Vec<Field>>,
 }
 
+impl Events {
+    /// Check that no event is empty; Mina rejects zero-width events.
+    ///
+    /// An empty list of events is valid and hashes to the `MinaZkappEventsEmpty` constant.
+    pub fn validate(&self) -> Result<(), MinaTxError> {
+        validate_field_vectors("event", &self.data)
+    }
+}
+
+impl Actions {
+    /// Check that no action is empty; Mina rejects zero-width actions.
+    ///
+    /// An empty list of actions is valid and hashes to the `MinaZkappActionsEmpty` constant.
+    pub fn validate(&self) -> Result<(), MinaTxError> {
+        validate_field_vectors("action", &self.data)
+    }
+}
+
+fn validate_field_vectors(kind: &str, data: &[Vec<Field>]) -> Result<(), MinaTxError> {
+    match data.iter().position(Vec::is_empty) {
+        Some(index) => Err(MinaTxError::InvalidZkAppCommand(format!(
+            "{kind} at index {index} has no fields"
+        ))),
+        None => Ok(()),
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct Authorization {
     pub proof: Option<String>,
@@ -675,3 +823,156 @@ where
         None => Ok(None),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_verification_key_accepts_current_txn_version() {
+        let permission = SetVerificationKey::new(AuthRequired::Signature, TXN_VERSION_CURRENT)
+            .expect("current transaction version should be accepted");
+        assert_eq!(permission.txn_version, TXN_VERSION_CURRENT);
+
+        let permissions = Permissions {
+            set_verification_key: permission,
+            ..Default::default()
+        };
+        assert!(permissions.validate().is_ok());
+    }
+
+    #[test]
+    fn test_set_verification_key_rejects_implausible_txn_version() {
+        assert!(matches!(
+            SetVerificationKey::new(AuthRequired::Signature, 10781045),
+            Err(MinaTxError::InvalidZkAppCommand(_))
+        ));
+
+        // Raw values are kept as-is until validated so existing vectors still round-trip.
+        let permissions = Permissions {
+            set_verification_key: SetVerificationKey {
+                auth: AuthRequired::Proof,
+                txn_version: 690302,
+            },
+            ..Default::default()
+        };
+        assert!(permissions.validate().is_err());
+    }
+
+    #[test]
+    fn test_derive_token_id_matches_mina_hasher() {
+        #[derive(Clone)]
+        struct TokenOwner(CompressedPubKey, mina_hasher::Fp);
+
+        impl Hashable for TokenOwner {
+            type D = ();
+
+            fn to_roinput(&self) -> ROInput {
+                ROInput::new()
+                    .append_field(self.0.x)
+                    .append_field(self.1)
+                    .append_bool(self.0.is_odd)
+            }
+
+            fn domain_string(_: Self::D) -> Option<String> {
+                Some("MinaDeriveTokenId".to_string())
+            }
+        }
+
+        let owner = PublicKey(
+            CompressedPubKey::from_address(
+                "B62qqxKu6uAH8YE4ruCVEHiFrGEA8yfkgoypyziLPRJyNQhSZko6e6j",
+            )
+            .unwrap(),
+        );
+        let parent = TokenId::default();
+
+        let derived = derive_token_id(&owner, &parent).unwrap();
+        let mut hasher = mina_hasher::create_kimchi::<TokenOwner>(());
+        let expected = hasher.hash(&TokenOwner(owner.0.clone(), parent.0 .0));
+        assert_eq!(derived.0 .0, expected);
+        assert_ne!(derived, parent);
+
+        // Nested tokens and the owner's parity both change the id
+        assert_ne!(derive_token_id(&owner, &derived).unwrap(), derived);
+        let mut flipped = owner.clone();
+        flipped.0.is_odd = !flipped.0.is_odd;
+        assert_ne!(derive_token_id(&flipped, &parent).unwrap(), derived);
+    }
+
+    #[test]
+    fn test_derive_token_id_matches_o1js_token_accounts() {
+        // Token accounts created by the o1js fungible token deploys in tests/data: the
+        // last account update of each is the token owner's child, using its custom token.
+        let vectors = [
+            (
+                // deploy-v0.0.4-unsigned.json
+                "B62qntaKodiAjVbLPT5H8SptyGZLSn3gtaoHkbRJTQtPmdkjh5wcB2S",
+                "wm5vpHbpEsSeE5ADDSbjKEd6s6jUaTX6EDohi3oeXYSuLSadto",
+            ),
+            (
+                // deploy-v0.0.6-admin-signed.json
+                "B62qoRFbwf8gCHVXwp6wN2Ebfgw2zAz1gc7YDC59CRSifJWdQPrqGbW",
+                "x1vCjS6khCU2diJ1C2vVvUduJbhfK7Rm5CZSEeHzyiosVprdyk",
+            ),
+        ];
+
+        for (owner, token_id) in vectors {
+            let owner = PublicKey(CompressedPubKey::from_address(owner).unwrap());
+            let expected: TokenId = serde_json::from_value(token_id.into()).unwrap();
+            assert_eq!(
+                derive_token_id(&owner, &TokenId::default()).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_empty_actions_and_events_produce_expected_commitment() {
+        use crate::transactions::zkapp_tx::test_vectors::{
+            get_zkapp_test_vectors, parse_expected_hash,
+        };
+
+        let vector = get_zkapp_test_vectors()
+            .into_iter()
+            .find(|tv| {
+                tv.zkapp_command.account_updates.iter().all(|update| {
+                    update.body.events.data.is_empty() && update.body.actions.data.is_empty()
+                })
+            })
+            .expect("a vector without events or actions should exist");
+
+        for update in &vector.zkapp_command.account_updates {
+            assert_eq!(update.body.events, Events::default());
+            assert_eq!(update.body.actions, Actions::default());
+            assert!(update.body.events.validate().is_ok());
+            assert!(update.body.actions.validate().is_ok());
+        }
+
+        let (_, full_commitment) = zk_commit(&vector.zkapp_command, &vector.network).unwrap();
+        assert_eq!(
+            full_commitment,
+            parse_expected_hash(vector.expected_full_commitment)
+        );
+    }
+
+    #[test]
+    fn test_empty_inner_action_or_event_is_rejected() {
+        let field = Field(mina_hasher::Fp::from(1u64));
+        let actions = Actions {
+            data: vec![vec![field], vec![]],
+        };
+        assert!(matches!(
+            actions.validate(),
+            Err(MinaTxError::InvalidZkAppCommand(msg)) if msg.contains("index 1")
+        ));
+
+        let events = Events { data: vec![vec![]] };
+        assert!(events.validate().is_err());
+        assert!(Events {
+            data: vec![vec![field]]
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     transactions::zkapp_tx::{
         constants::{self, ZkAppBodyPrefix, DUMMY_HASH},
         packing::{Packable, PackedInput},
-        AccountUpdate, FeePayer, ZKAppCommand,
+        AccountUpdate, FeePayer, PublicKey, TokenId, ZKAppCommand,
     },
 };
 use ark_ff::Field;
@@ -24,15 +24,13 @@ use mina_poseidon::{
 // -------------------------------------------------------------------------------------------------
 
 /// Produces a commitment for a ZkApp command by hashing its structure and contents.
-/// Validates call depths and authorization kinds before computing the commitment.
+/// Validates the number of account updates, call depths and authorization kinds before computing
+/// the commitment.
 /// Returns two Fp elements, representing the accountUpdates commitment and the overall commitment respectively.
 /// Overall commitment includes memo, fee payer, and account updates commitments.
 pub(crate) fn zk_commit(tx: &ZKAppCommand, network: &NetworkId) -> MinaTxResult<(Fp, Fp)> {
-    if !is_call_depth_valid(tx) {
-        return Err(Box::new(MinaTxError::InvalidZkAppCommand(
-            "Call depths are not valid".to_string(),
-        )));
-    }
+    validate_account_update_count(&tx.account_updates).map_err(Box::new)?;
+    validate_call_tree(&tx.account_updates).map_err(Box::new)?;
 
     let forest = CallForest::from(tx.clone());
 
@@ -156,9 +154,21 @@ fn hash_account_update(account_update: &AccountUpdate, network: &NetworkId) -> M
     // Check that account update is valid
     assert_account_update_authorization_kind(account_update)?;
 
-    let inputs = account_update.pack().pack_to_fields();
+    let inputs = account_update.pack().to_fields();
     let network_zk = Cow::<str>::from(ZkAppBodyPrefix::from(network.clone()));
-    hash_with_prefix(&network_zk, &inputs.fields)
+    hash_with_prefix(&network_zk, &inputs)
+}
+
+/// Derives the id of the custom token owned by `owner` under the `parent` token.
+///
+/// Follows o1js `TokenId.derive`: the owner's x-coordinate and the parent token id are
+/// hashed as fields, followed by the owner's parity bit packed into its own field, under
+/// the `MinaDeriveTokenId` prefix. Tokens owned directly by an account use the MINA
+/// token ([`TokenId::default`]) as parent.
+pub fn derive_token_id(owner: &PublicKey, parent: &TokenId) -> MinaTxResult<TokenId> {
+    let inputs = [owner.0.x, parent.0 .0, Fp::from(owner.0.is_odd)];
+    let token_id = hash_with_prefix(constants::PREFIX_DERIVE_TOKEN_ID, &inputs)?;
+    Ok(TokenId(token_id.into()))
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -223,35 +233,47 @@ fn assert_account_update_authorization_kind(account_update: &AccountUpdate) -> M
 /// The first account update must have call depth 0, and subsequent call depths
 /// must not be negative and can only increase by at most 1 from the previous.
 pub fn is_call_depth_valid(zkapp_command: &ZKAppCommand) -> bool {
-    let mut call_depths: Vec<u32> = zkapp_command
-        .account_updates
-        .iter()
-        .map(|a| a.body.call_depth)
-        .collect();
+    validate_call_tree(&zkapp_command.account_updates).is_ok()
+}
 
-    let mut current = match call_depths.first() {
-        Some(&depth) => {
-            call_depths.remove(0);
-            depth
+/// Checks that the call depths of `updates` describe a forest, as [`is_call_depth_valid`]
+/// does, and reports the first account update that breaks it.
+pub fn validate_call_tree(updates: &[AccountUpdate]) -> Result<(), MinaTxError> {
+    let mut current = 0;
+    for (index, update) in updates.iter().enumerate() {
+        let call_depth = update.body.call_depth;
+        if index == 0 && call_depth != 0 {
+            return Err(MinaTxError::InvalidZkAppCommand(format!(
+                "Call depths are not valid: the first account update has call depth {call_depth}, expected 0"
+            )));
         }
-        None => 0,
-    };
-
-    if current != 0 {
-        return false;
+        // Returning to any shallower depth is fine, going deeper only one level at a time
+        if call_depth > current + 1 {
+            return Err(MinaTxError::InvalidZkAppCommand(format!(
+                "Call depths are not valid: account update {index} has call depth {call_depth} after depth {current}"
+            )));
+        }
+        current = call_depth;
     }
 
-    for call_depth in call_depths {
-        // If we reset call depth back to < current we approve
-        // Or if we increase call depth by 1 we approve
-        if call_depth <= current + 1 {
-            current = call_depth;
-        } else {
-            return false;
-        }
+    Ok(())
+}
+
+/// Checks that `updates` fits within [`constants::MAX_ACCOUNT_UPDATES`], so that commands a
+/// node would reject are caught before any hashing is done.
+///
+/// Only the hard cap on the number of updates is checked. A command that passes can still be
+/// rejected by a node for exceeding the transaction cost limit.
+pub fn validate_account_update_count(updates: &[AccountUpdate]) -> Result<(), MinaTxError> {
+    if updates.len() > constants::MAX_ACCOUNT_UPDATES {
+        return Err(MinaTxError::InvalidZkAppCommand(format!(
+            "Too many account updates: got {}, at most {} are allowed besides the fee payer",
+            updates.len(),
+            constants::MAX_ACCOUNT_UPDATES
+        )));
     }
 
-    true
+    Ok(())
 }
 
 pub(crate) fn param_to_field(param: &str) -> Result<Fp, MinaTxError> {
@@ -343,6 +365,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_packed_fields_hash_to_fee_payer_hash() {
+        for test_vector in get_zkapp_test_vectors() {
+            let account_update = AccountUpdate::from(test_vector.zkapp_command.fee_payer.clone());
+            let fields = account_update.pack().to_fields();
+
+            let network_zk = Cow::<str>::from(ZkAppBodyPrefix::from(test_vector.network.clone()));
+            let computed_hash = hash_with_prefix(&network_zk, &fields).unwrap();
+
+            assert_eq!(
+                computed_hash,
+                parse_expected_hash(test_vector.expected_fee_payer_hash),
+                "Packed fields do not hash to the fee payer hash for test: {}",
+                test_vector.name
+            );
+        }
+    }
+
     #[test]
     fn test_zk_commit() {
         let test_vectors = get_zkapp_test_vectors();
@@ -432,6 +472,101 @@ mod tests {
         }
     }
 
+    fn updates_with_depths(depths: &[u32]) -> Vec<AccountUpdate> {
+        depths
+            .iter()
+            .map(|&call_depth| {
+                let mut update = AccountUpdate::default();
+                update.body.call_depth = call_depth;
+                update
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_call_tree_accepts_forest() {
+        assert!(validate_call_tree(&[]).is_ok());
+        assert!(validate_call_tree(&updates_with_depths(&[0, 1, 2, 1, 2, 0, 1, 0])).is_ok());
+    }
+
+    #[test]
+    fn test_validate_call_tree_rejects_depth_jump() {
+        let err = validate_call_tree(&updates_with_depths(&[0, 2])).unwrap_err();
+        assert!(
+            matches!(err, MinaTxError::InvalidZkAppCommand(msg) if msg.contains("account update 1"))
+        );
+        assert!(validate_call_tree(&updates_with_depths(&[1])).is_err());
+
+        let tx = ZKAppCommand {
+            account_updates: updates_with_depths(&[0, 1, 3]),
+            ..Default::default()
+        };
+        assert!(!is_call_depth_valid(&tx));
+        assert!(matches!(
+            zk_commit(&tx, &NetworkId::Testnet).map_err(|e| *e),
+            Err(MinaTxError::InvalidZkAppCommand(_))
+        ));
+    }
+
+    #[test]
+    fn test_account_update_limit() {
+        let mut tx = ZKAppCommand {
+            account_updates: vec![AccountUpdate::dummy(); constants::MAX_ACCOUNT_UPDATES],
+            ..Default::default()
+        };
+        assert!(validate_account_update_count(&tx.account_updates).is_ok());
+        assert!(zk_commit(&tx, &NetworkId::Testnet).is_ok());
+
+        tx.account_updates.push(AccountUpdate::dummy());
+        let err = zk_commit(&tx, &NetworkId::Testnet).unwrap_err();
+        assert!(
+            matches!(*err, MinaTxError::InvalidZkAppCommand(msg) if msg.contains("Too many account updates"))
+        );
+    }
+
+    /// Checks that the dummy is committed to as a plain leaf of the call forest. The
+    /// expected value is rebuilt with this crate's own hashing, so this does not show
+    /// agreement with Mina.
+    #[test]
+    fn test_dummy_account_update_commits() {
+        let dummy = AccountUpdate::dummy();
+        assert_eq!(dummy.body.public_key, PublicKey::default());
+        assert_eq!(dummy.body.token_id, TokenId::default());
+        assert_eq!(dummy.body.balance_change.magnitude, 0);
+        assert!(!dummy.body.authorization_kind.is_signed);
+        assert!(!dummy.body.authorization_kind.is_proved);
+        assert!(dummy.authorization.signature.is_none());
+        assert!(assert_account_update_authorization_kind(&dummy).is_ok());
+
+        // Named differently in the pre-Mesa and Mesa vectors
+        let base = get_zkapp_test_vectors()
+            .into_iter()
+            .find(|v| v.zkapp_command.account_updates.is_empty())
+            .expect("a vector without account updates");
+        let (base_commitment, _) = zk_commit(&base.zkapp_command, &base.network).unwrap();
+
+        let mut tx = base.zkapp_command.clone();
+        tx.account_updates.push(AccountUpdate::dummy());
+        let (commitment, full_commitment) = zk_commit(&tx, &base.network).unwrap();
+
+        // The dummy is hashed like any other leaf: node(update, empty) consed onto the empty stack
+        let tree_hash = hash_account_update(&dummy, &base.network).unwrap();
+        let node_hash = hash_with_prefix(
+            constants::PREFIX_ACCOUNT_UPDATE_NODE,
+            &[tree_hash, constants::EMPTY_STACK_HASH],
+        )
+        .unwrap();
+        let expected = hash_with_prefix(
+            constants::PREFIX_ACCOUNT_UPDATE_CONS,
+            &[node_hash, constants::EMPTY_STACK_HASH],
+        )
+        .unwrap();
+        assert_eq!(commitment, expected);
+        assert_ne!(commitment, base_commitment);
+        assert_ne!(commitment, Fp::from(0u64));
+        assert_ne!(full_commitment, commitment);
+    }
+
     #[test]
     fn test_prefix_to_field() {
         let prefix = "MinaAcctUpdateNode";
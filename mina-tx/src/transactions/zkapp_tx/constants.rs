@@ -41,6 +41,7 @@ pub const ZK_APP_BODY_TESTNET: &str = "TestnetZkappBody****";
 pub const PREFIX_ACCOUNT_UPDATE_NODE: &str = "MinaAcctUpdateNode**";
 pub const PREFIX_ACCOUNT_UPDATE_CONS: &str = "MinaAcctUpdateCons**";
 pub const ZK_APP_MEMO: &str = "MinaZkappMemo";
+pub const PREFIX_DERIVE_TOKEN_ID: &str = "MinaDeriveTokenId";
 
 pub const ZK_APP_EVENTS_EMPTY: &str = "MinaZkappEventsEmpty";
 pub const ZK_APP_EVENTS_PREFIX: &str = "MinaZkappEvents";
@@ -53,6 +54,14 @@ pub const ZK_ACTION_STATE_EMPTY: &str = "MinaZkappActionStateEmptyElt";
 
 pub const MINA_ZKAPP_URI: &str = "MinaZkappUri";
 
+// Nodes reject zkApp commands with more than 128 account updates (`zkapp_cmd_limit_hardcap`),
+// counting the fee payer, so this many remain for `account_updates`.
+//
+// This is only the hard cap. Nodes also bound a command's proof and signature cost
+// (`zkapp_transaction_cost_limit`), which in practice rejects commands with far fewer updates;
+// that limit is not checked here.
+pub const MAX_ACCOUNT_UPDATES: usize = 127;
+
 #[cfg(not(feature = "mesa-hardfork"))]
 pub(crate) const APP_STATE_LENGTH: usize = 8;
 
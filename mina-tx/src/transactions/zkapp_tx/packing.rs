@@ -95,20 +95,23 @@ impl PackedInput {
 
     // New version of packToFields that matches o1js behavior (non-legacy packing)
     pub fn pack_to_fields(self) -> PackedInput {
-        let fields = self.fields;
-        let bits = self.bits;
-
-        if bits.is_empty() {
-            return PackedInput {
-                bits: vec![],
-                fields,
-            };
+        PackedInput {
+            bits: vec![],
+            fields: self.to_fields(),
+        }
+    }
+
+    /// Returns the exact field sequence that the commitment hasher absorbs: the raw fields
+    /// first, followed by the bits packed greedily into fields of fewer than 255 bits.
+    pub fn to_fields(&self) -> Vec<Fp> {
+        let mut fields = self.fields.clone();
+        if self.bits.is_empty() {
+            return fields;
         }
 
-        let mut packed_bits = Vec::new();
         let mut current_packed_field = Fp::ZERO;
         let mut current_size = 0;
-        for bit_data in bits {
+        for bit_data in &self.bits {
             let size = bit_data.bit_data_size();
             let field = bit_data.to_field();
 
@@ -117,16 +120,13 @@ impl PackedInput {
                 current_packed_field =
                     current_packed_field * Fp::from(BigInt::from(1u64) << size as u32) + field;
             } else {
-                packed_bits.push(current_packed_field);
+                fields.push(current_packed_field);
                 current_size = size;
                 current_packed_field = field;
             }
         }
-        packed_bits.push(current_packed_field);
-        PackedInput {
-            bits: vec![],
-            fields: [fields, packed_bits].concat(),
-        }
+        fields.push(current_packed_field);
+        fields
     }
 
     /// Static function that packs a slice of bits into field elements, taking chunks of 254 bits at a time.
@@ -138,6 +138,16 @@ impl PackedInput {
     }
 }
 
+// Iterates over the packed field sequence, see `PackedInput::to_fields`
+impl IntoIterator for &PackedInput {
+    type Item = Fp;
+    type IntoIter = alloc::vec::IntoIter<Fp>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.to_fields().into_iter()
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub enum BitData {
     U32 { val: u32 },
@@ -823,6 +833,26 @@ mod test {
         assert_eq!(roi.to_fields(), expected_roi.to_fields());
     }
 
+    #[test]
+    fn test_to_fields_packs_bits_after_fields() {
+        let packed_input = PackedInput::new()
+            .append_field(Fp::from(42u64))
+            .append_bool(true)
+            .append_u32(7)
+            .append_u64(u64::MAX)
+            .append_u64(u64::MAX)
+            .append_u64(u64::MAX)
+            .append_u64(u64::MAX);
+
+        let fields = packed_input.to_fields();
+        // 1 + 32 + 3 * 64 = 225 bits fit in the first packed field, the fourth u64 overflows
+        assert_eq!(fields.len(), 3);
+        assert_eq!(fields[0], Fp::from(42u64));
+        assert_eq!(fields[2], Fp::from(u64::MAX));
+        assert_eq!((&packed_input).into_iter().collect::<Vec<_>>(), fields);
+        assert_eq!(packed_input.pack_to_fields().fields, fields);
+    }
+
     #[test]
     fn test_pack_to_fields_bool() {
         let mut bits = vec![true];
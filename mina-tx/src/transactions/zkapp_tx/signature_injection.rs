@@ -115,6 +115,16 @@ impl ZKAppCommand {
         }
     }
 
+    /// Place a fee payer signature into `fee_payer.authorization` as base58.
+    ///
+    /// Unlike [`Self::inject_signature`], account updates are left untouched and the fee
+    /// payer key is not compared with the signer. Returns the previous authorization if
+    /// it was non-empty.
+    pub fn set_fee_payer_signature(&mut self, signature: &Sig) -> Option<String> {
+        let previous = core::mem::replace(&mut self.fee_payer.authorization, signature.to_base58());
+        (!previous.is_empty()).then_some(previous)
+    }
+
     /// Inject signature into fee payer if public key matches.
     fn inject_fee_payer_signature(
         &mut self,
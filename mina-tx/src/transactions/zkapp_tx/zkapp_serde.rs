@@ -6,6 +6,7 @@ use crate::{
         from_base58_check, to_base58_check, Base58Error, MEMO_VERSION_BYTE, TOKEN_ID_VERSION_BYTE,
     },
     transactions::{
+        validate_memo,
         zkapp_tx::{Field, PublicKey, StringU32, StringU64, TokenId},
         MEMO_BYTES,
     },
@@ -100,14 +101,9 @@ where
         )));
     }
 
-    if decoded.first() != Some(&0x01) {
-        return Err(serde::de::Error::custom(
-            "Invalid memo header: missing 0x01 prefix",
-        ));
-    }
-
     let mut memo = [0u8; MEMO_BYTES];
     memo.copy_from_slice(&decoded);
+    validate_memo(&memo).map_err(serde::de::Error::custom)?;
     Ok(memo)
 }
 
@@ -211,6 +207,7 @@ impl<'de> Deserialize<'de> for StringU64 {
 
 #[cfg(test)]
 mod tests {
+    use super::memo_deser;
     use crate::base58::{to_base58_check, MEMO_VERSION_BYTE};
     use crate::transactions::zkapp_tx::*;
     use mina_signer::CompressedPubKey;
@@ -411,6 +408,28 @@ mod tests {
         assert_eq!(result.fee_payer.body.valid_until, None);
     }
 
+    #[test]
+    fn test_deserialize_rejects_malformed_memo_header() {
+        let mut bad_header = TEST_MEMO;
+        bad_header[0] = 0x02;
+        let mut too_long = TEST_MEMO;
+        too_long[1] = (MEMO_BYTES - 1) as u8;
+
+        for memo in [bad_header, too_long] {
+            let json = format!("\"{}\"", to_base58_check(&memo, MEMO_VERSION_BYTE));
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            let err = memo_deser(&mut deserializer).unwrap_err().to_string();
+            assert!(err.contains("memo"), "unexpected error: {err}");
+        }
+
+        // The largest length that still fits in the payload is accepted.
+        let mut full = TEST_MEMO;
+        full[1] = (MEMO_BYTES - 2) as u8;
+        let json = format!("\"{}\"", to_base58_check(&full, MEMO_VERSION_BYTE));
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        assert_eq!(memo_deser(&mut deserializer).unwrap(), full);
+    }
+
     #[test]
     fn test_invalid_public_key_fails() {
         let json_str = r#""invalid_key_format""#;
@@ -0,0 +1,216 @@
+//! Memoization of successful Mina signature checks.
+//!
+//! Verifying a signature under Mina rules costs a couple of scalar multiplications and a
+//! Poseidon hash. A service that checks the same aggregated signature over and over, for
+//! instance while retrying a broadcast, can put a [`VerificationCache`] in front of
+//! [`TransactionEnvelope::verify_mina`] to only pay for it once.
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use frost_core::{Signature as FrSig, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::{errors::MinaTxError, pallas_message::PallasMessage, TransactionEnvelope};
+
+type BluePallasSuite = frost_bluepallas::BluePallas<PallasMessage>;
+
+/// Digest of everything a verification depends on: the message (including its network and
+/// signing scheme), the signature `(R, s)` and the verifying key.
+type CacheKey = [u8; 32];
+
+/// A bounded least-recently-used cache of signatures that passed
+/// [`TransactionEnvelope::verify_mina`].
+///
+/// Only successes are remembered: a failed check is always run again, so a transient bug
+/// cannot get stuck in the cache.
+#[derive(Debug)]
+pub struct VerificationCache {
+    capacity: usize,
+    // Most recently used at the back
+    entries: Mutex<VecDeque<CacheKey>>,
+}
+
+impl VerificationCache {
+    /// Create a cache holding at most `capacity` verified signatures. A capacity of zero
+    /// disables caching.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Same as [`TransactionEnvelope::verify_mina`], but returns early if this exact
+    /// signature was already found valid for this message and key.
+    pub fn verify_mina(
+        &self,
+        envelope: &TransactionEnvelope,
+        public_key: &VerifyingKey<BluePallasSuite>,
+        signature: &FrSig<BluePallasSuite>,
+    ) -> Result<(), MinaTxError> {
+        self.verify_with(
+            envelope,
+            public_key,
+            signature,
+            TransactionEnvelope::verify_mina,
+        )
+    }
+
+    /// [`VerificationCache::verify_mina`], checking cache misses with `verify`.
+    fn verify_with(
+        &self,
+        envelope: &TransactionEnvelope,
+        public_key: &VerifyingKey<BluePallasSuite>,
+        signature: &FrSig<BluePallasSuite>,
+        verify: impl FnOnce(
+            &TransactionEnvelope,
+            &VerifyingKey<BluePallasSuite>,
+            &FrSig<BluePallasSuite>,
+        ) -> Result<(), MinaTxError>,
+    ) -> Result<(), MinaTxError> {
+        let key = cache_key(envelope, public_key, signature)?;
+        if self.touch(&key) {
+            return Ok(());
+        }
+
+        verify(envelope, public_key, signature)?;
+        self.insert(key);
+        Ok(())
+    }
+
+    /// Whether a successful verification of `signature` over `envelope` is cached.
+    pub fn contains(
+        &self,
+        envelope: &TransactionEnvelope,
+        public_key: &VerifyingKey<BluePallasSuite>,
+        signature: &FrSig<BluePallasSuite>,
+    ) -> Result<bool, MinaTxError> {
+        let key = cache_key(envelope, public_key, signature)?;
+        Ok(self.lock().contains(&key))
+    }
+
+    /// Number of cached verifications.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Move `key` to the most recently used position, if present.
+    fn touch(&self, key: &CacheKey) -> bool {
+        let mut entries = self.lock();
+        let Some(position) = entries.iter().position(|entry| entry == key) else {
+            return false;
+        };
+        if let Some(entry) = entries.remove(position) {
+            entries.push_back(entry);
+        }
+        true
+    }
+
+    fn insert(&self, key: CacheKey) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if entries.contains(&key) {
+            return;
+        }
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(key);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<CacheKey>> {
+        // The queue is always left consistent, so a panic elsewhere does not invalidate it
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn cache_key(
+    envelope: &TransactionEnvelope,
+    public_key: &VerifyingKey<BluePallasSuite>,
+    signature: &FrSig<BluePallasSuite>,
+) -> Result<CacheKey, MinaTxError> {
+    let message = envelope.to_pallas_message().serialize()?;
+    let signature = signature
+        .serialize()
+        .map_err(|e| MinaTxError::InvalidSignature(e.to_string()))?;
+    let public_key = public_key
+        .serialize()
+        .map_err(|e| MinaTxError::InvalidPublicKey(e.to_string()))?;
+
+    // Length-prefix the message so that its end cannot be confused with the signature
+    let mut hasher = Sha256::new();
+    hasher.update((message.len() as u64).to_le_bytes());
+    hasher.update(&message);
+    hasher.update(&signature);
+    hasher.update(&public_key);
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use super::*;
+    use crate::test_fixtures::{payment, sign, verifying_key};
+
+    #[test]
+    fn test_verification_cache_hits_and_misses() {
+        let public_key = verifying_key();
+        let envelope = payment(1_000);
+        let signature = sign(&envelope);
+        let cache = VerificationCache::new(2);
+
+        // Counts the signatures actually checked, which cache hits must skip
+        let checks = Cell::new(0);
+        let verify = |envelope: &TransactionEnvelope, signature: &FrSig<BluePallasSuite>| {
+            cache.verify_with(envelope, &public_key, signature, |envelope, key, sig| {
+                checks.set(checks.get() + 1);
+                envelope.verify_mina(key, sig)
+            })
+        };
+
+        verify(&envelope, &signature).unwrap();
+        assert_eq!(checks.get(), 1);
+        assert!(cache.contains(&envelope, &public_key, &signature).unwrap());
+        verify(&envelope, &signature).unwrap();
+        assert_eq!(checks.get(), 1);
+        assert_eq!(cache.len(), 1);
+
+        // A different message is a miss, and a failed check is not remembered
+        let other = payment(2_000);
+        assert!(!cache.contains(&other, &public_key, &signature).unwrap());
+        assert!(verify(&other, &signature).is_err());
+        assert!(verify(&other, &signature).is_err());
+        assert_eq!(checks.get(), 3);
+        assert!(!cache.contains(&other, &public_key, &signature).unwrap());
+        assert_eq!(cache.len(), 1);
+
+        // The least recently used entry is evicted once the cache is full
+        let other_signature = sign(&other);
+        let third = payment(3_000);
+        let third_signature = sign(&third);
+        verify(&other, &other_signature).unwrap();
+        verify(&envelope, &signature).unwrap();
+        verify(&third, &third_signature).unwrap();
+        assert_eq!(checks.get(), 5);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&envelope, &public_key, &signature).unwrap());
+        assert!(!cache
+            .contains(&other, &public_key, &other_signature)
+            .unwrap());
+
+        // The public entry point goes through the same cache
+        cache
+            .verify_mina(&third, &public_key, &third_signature)
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+    }
+}
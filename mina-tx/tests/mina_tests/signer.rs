@@ -516,3 +516,218 @@ fn test_zkapp_tx_mina_signer_compatibility() {
 
     println!("All ZkApp transaction signing tests passed!");
 }
+
+#[test]
+fn test_sign_zkapp_commitment_field() {
+    use mina_tx::zkapp_tx::test_vectors::get_zkapp_test_vectors;
+
+    let mut rng = rand_core::OsRng;
+
+    for test_vector in get_zkapp_test_vectors() {
+        let (shares, pubkey_package) =
+            frost_bluepallas::keys::generate_with_dealer::<PallasMessage, _>(
+                3,
+                2,
+                frost_bluepallas::keys::IdentifierList::Default,
+                &mut rng,
+            )
+            .expect("Failed to generate key shares");
+
+        let tx_env = TransactionEnvelope::new_zkapp(
+            test_vector.network.clone(),
+            test_vector.zkapp_command.clone(),
+        );
+        let fields = tx_env.to_signing_fields();
+        assert_eq!(fields.len(), 1, "zkApp commands sign a single commitment");
+
+        // Sign the commitment directly rather than the whole transaction
+        let msg = PallasMessage::from_field(fields[0], test_vector.network.clone())
+            .serialize()
+            .unwrap();
+        let (sig, vk) = signing_utilities::sign_from_packages::<PallasMessage, _>(
+            &msg,
+            shares,
+            pubkey_package,
+            &mut rng,
+        )
+        .expect("Failed to sign commitment with FROST");
+
+        let mina_sig = mina_tx::pallas_message::translate_sig(&sig)
+            .expect("Failed to translate FROST signature to Mina signature");
+        let mina_vk = mina_tx::pallas_message::translate_pk(&vk)
+            .expect("Failed to translate FROST verifying key to Mina public key");
+
+        // The node verifies zkApp signatures over the full transaction
+        let mut ctx = mina_signer::create_kimchi(test_vector.network.clone());
+        assert!(
+            ctx.verify(&mina_sig, &mina_vk, &tx_env),
+            "Commitment signature rejected for test: {}",
+            test_vector.name
+        );
+    }
+}
+
+#[test]
+fn test_sign_zkapp_fee_payer() {
+    use ark_ff::PrimeField;
+    use mina_tx::{
+        zkapp_tx::{sign_zkapp_fee_payer, test_vectors::get_zkapp_test_vectors},
+        Sig,
+    };
+    use std::str::FromStr;
+
+    let mut rng = rand_core::OsRng;
+    let test_vector = get_zkapp_test_vectors()
+        .into_iter()
+        .find(|tv| tv.name == "single_account_update")
+        .expect("single_account_update vector should exist");
+    let network = test_vector.network.clone();
+
+    let msg = sign_zkapp_fee_payer(&test_vector.zkapp_command, network.clone()).unwrap();
+    let full_commitment = mina_signer::BaseField::from_str(test_vector.expected_full_commitment)
+        .expect("expected full commitment should parse");
+    assert_eq!(
+        msg,
+        PallasMessage::from_field(full_commitment, network.clone())
+            .serialize()
+            .unwrap()
+    );
+
+    let (shares, pubkey_package) =
+        frost_bluepallas::keys::generate_with_dealer::<PallasMessage, _>(
+            3,
+            2,
+            frost_bluepallas::keys::IdentifierList::Default,
+            &mut rng,
+        )
+        .expect("Failed to generate key shares");
+    let (sig, vk) = signing_utilities::sign_from_packages::<PallasMessage, _>(
+        &msg,
+        shares,
+        pubkey_package,
+        &mut rng,
+    )
+    .expect("Failed to sign fee payer message with FROST");
+
+    let mina_sig = mina_tx::pallas_message::translate_sig(&sig).unwrap();
+    let mina_vk = translate_pk(&vk).unwrap();
+
+    // The node checks the fee payer signature against the full commitment
+    let tx_env = TransactionEnvelope::new_zkapp(network.clone(), test_vector.zkapp_command.clone());
+    let mut ctx = mina_signer::create_kimchi(network);
+    assert!(ctx.verify(&mina_sig, &mina_vk, &tx_env));
+
+    let signature = Sig {
+        field: mina_sig.rx.into_bigint(),
+        scalar: mina_sig.s.into_bigint(),
+    };
+    let mut command = test_vector.zkapp_command.clone();
+    command.set_fee_payer_signature(&signature);
+    assert_eq!(command.fee_payer.authorization, signature.to_base58());
+    assert_eq!(
+        command.account_updates,
+        test_vector.zkapp_command.account_updates
+    );
+}
+
+#[test]
+fn test_sign_zkapp_command_fills_every_authorization() {
+    use ark_ff::PrimeField;
+    use mina_signer::Signature;
+    use mina_tx::{
+        zkapp_tx::{
+            sign_zkapp_command, sign_zkapp_fee_payer, test_vectors::get_zkapp_test_vectors,
+            PublicKey,
+        },
+        Sig,
+    };
+    use rand_core::SeedableRng;
+    use std::cell::RefCell;
+
+    let test_vector = get_zkapp_test_vectors()
+        .into_iter()
+        .find(|tv| tv.name == "single_account_update")
+        .expect("single_account_update vector should exist");
+    let network = test_vector.network.clone();
+
+    // Swap in keys we hold, keeping the vector's mix of full and partial commitments
+    let fee_payer =
+        Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+            .unwrap();
+    let account =
+        Keypair::from_hex("35dcca7620128d240cc3319c83dc6402ad439038361ba853af538a4cea3ddabc")
+            .unwrap();
+    let mut template = test_vector.zkapp_command.clone();
+    template.fee_payer.body.public_key = PublicKey(fee_payer.public.into_compressed());
+    template.fee_payer.authorization = String::new();
+    let update = &mut template.account_updates[0];
+    update.body.public_key = PublicKey(account.public.into_compressed());
+    assert!(update.body.authorization_kind.is_signed);
+    assert!(!update.body.use_full_commitment);
+    update.authorization.signature = None;
+
+    let requests = RefCell::new(Vec::new());
+    let signed = sign_zkapp_command(&template, network.clone(), |public_key, message| {
+        let keypair = [&fee_payer, &account]
+            .into_iter()
+            .find(|kp| kp.public.into_compressed() == public_key.0)
+            .expect("signing requested for an unknown key");
+        let (sig, _) = signing_utilities::generate_signature_from_sk::<PallasMessage, _>(
+            message,
+            &translate_minask(keypair).unwrap(),
+            rand_chacha::ChaCha20Rng::from_seed([0u8; 32]),
+        )
+        .unwrap();
+        requests
+            .borrow_mut()
+            .push((keypair.public.clone(), message.to_vec()));
+        let sig = mina_tx::pallas_message::translate_sig(&sig).unwrap();
+        Ok(Sig {
+            field: sig.rx.into_bigint(),
+            scalar: sig.s.into_bigint(),
+        })
+    })
+    .unwrap();
+
+    // One FROST session per key and commitment, and nothing else changed
+    let requests = requests.into_inner();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[0].1,
+        sign_zkapp_fee_payer(&template, network.clone()).unwrap()
+    );
+    assert_ne!(requests[0].1, requests[1].1);
+    assert_eq!(signed.memo, template.memo);
+    assert_eq!(
+        signed.account_updates[0].body,
+        template.account_updates[0].body
+    );
+
+    let decode = |base58: &str| {
+        let sig = Sig::from_base58(base58).unwrap();
+        Signature::new(
+            mina_signer::BaseField::from_bigint(sig.field).unwrap(),
+            mina_signer::ScalarField::from_bigint(sig.scalar).unwrap(),
+        )
+    };
+    let mut ctx = mina_signer::create_kimchi(network.clone());
+
+    // The node checks the fee payer against the full transaction commitment
+    let fee_payer_sig = decode(&signed.fee_payer.authorization);
+    let tx_env = TransactionEnvelope::new_zkapp(network.clone(), signed.clone());
+    assert!(ctx.verify(&fee_payer_sig, &fee_payer.public, &tx_env));
+
+    // and the account update against the account updates commitment it was asked to sign
+    let update_sig = decode(
+        signed.account_updates[0]
+            .authorization
+            .signature
+            .as_deref()
+            .expect("account update signature injected"),
+    );
+    let (key, message) = &requests[1];
+    assert!(key.into_compressed() == account.public.into_compressed());
+    let message = PallasMessage::deserialize(message).unwrap();
+    assert!(ctx.verify(&update_sig, &account.public, &message));
+    assert!(!ctx.verify(&update_sig, &account.public, &tx_env));
+}